@@ -0,0 +1,705 @@
+use super::fingerprint::{properties_for, BrowserProperties};
+use super::headers::{beacon_headers, image_headers, ipapi_headers, poll_headers, script_headers};
+use super::preset::{builtin_preset, Preset, PRESET_NAMES};
+use wreq::header::HeaderMap;
+
+/// A single inconsistency between two parts of a browser identity.
+#[derive(Clone, Debug)]
+pub struct Mismatch {
+    /// Where the conflicting value was found: a header context or "properties".
+    pub context: &'static str,
+    pub message: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Family {
+    Chromium,
+    Firefox,
+    Safari,
+    Unknown,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Os {
+    Windows,
+    Mac,
+    Linux,
+    Unknown,
+}
+
+/// Cross-validate a preset's User-Agent, header contexts and navigator properties.
+///
+/// Returns every mismatch found; an empty list means the identity is coherent.
+pub fn check_preset(preset: &Preset) -> Vec<Mismatch> {
+    let mut issues = Vec::new();
+    let props = properties_for(preset);
+    let family = ua_family(preset.user_agent);
+    let os = ua_os(preset.user_agent);
+
+    if family == Family::Unknown {
+        push(
+            &mut issues,
+            "user-agent",
+            "unrecognized browser family".to_string(),
+        );
+    }
+    if os == Os::Unknown {
+        push(
+            &mut issues,
+            "user-agent",
+            "unrecognized operating system".to_string(),
+        );
+    }
+
+    check_properties(preset, &props, family, os, &mut issues);
+    check_machine(preset, &props, family, &mut issues);
+    check_client_hints(preset, family, os, &mut issues);
+
+    let contexts = [
+        ("script", script_headers(preset)),
+        ("image", image_headers(preset)),
+        ("beacon", beacon_headers(preset)),
+        ("poll", poll_headers(preset)),
+        ("ipapi", ipapi_headers(preset)),
+    ];
+    for (context, headers) in contexts.iter() {
+        check_headers(context, headers, preset, &props, family, os, &mut issues);
+    }
+
+    issues
+}
+
+fn check_properties(
+    preset: &Preset,
+    props: &BrowserProperties,
+    family: Family,
+    os: Os,
+    issues: &mut Vec<Mismatch>,
+) {
+    let ctx = "properties";
+
+    let expected_vendor = match family {
+        Family::Chromium => Some("Google Inc."),
+        Family::Firefox => Some(""),
+        Family::Safari => Some("Apple Computer, Inc."),
+        Family::Unknown => None,
+    };
+    if let Some(expected) = expected_vendor {
+        if props.vendor != expected {
+            push(
+                issues,
+                ctx,
+                format!(
+                    "vendor {:?} does not match {:?} browser (expected {:?})",
+                    props.vendor, family, expected
+                ),
+            );
+        }
+    }
+
+    let platform_ok = match os {
+        Os::Windows => props.platform == "Win32",
+        Os::Mac => props.platform == "MacIntel",
+        Os::Linux => props.platform.starts_with("Linux"),
+        Os::Unknown => true,
+    };
+    if !platform_ok {
+        push(
+            issues,
+            ctx,
+            format!(
+                "platform {:?} does not match User-Agent OS {:?}",
+                props.platform, os
+            ),
+        );
+    }
+
+    let expected_product_sub = if family == Family::Firefox {
+        "20100101"
+    } else {
+        "20030107"
+    };
+    if props.product_sub != expected_product_sub {
+        push(
+            issues,
+            ctx,
+            format!(
+                "productSub {:?} does not match {:?} browser (expected {:?})",
+                props.product_sub, family, expected_product_sub
+            ),
+        );
+    }
+
+    match (family, props.oscpu) {
+        (Family::Firefox, None) => push(issues, ctx, "Firefox must expose oscpu".to_string()),
+        (Family::Firefox, Some(oscpu)) => {
+            if !preset.user_agent.contains(&format!("({};", oscpu)) {
+                push(
+                    issues,
+                    ctx,
+                    format!("oscpu {:?} does not match User-Agent platform token", oscpu),
+                );
+            }
+        }
+        (_, Some(oscpu)) => push(
+            issues,
+            ctx,
+            format!("oscpu {:?} is only exposed by Firefox", oscpu),
+        ),
+        (_, None) => {}
+    }
+
+    if family != Family::Firefox && props.build_id.is_some() {
+        push(
+            issues,
+            ctx,
+            "buildID is only exposed by Firefox".to_string(),
+        );
+    }
+    if family == Family::Firefox && props.build_id.is_none() {
+        push(issues, ctx, "Firefox must expose buildID".to_string());
+    }
+
+    if family != Family::Chromium && props.device_memory.is_some() {
+        push(
+            issues,
+            ctx,
+            "deviceMemory is only exposed by Chromium".to_string(),
+        );
+    }
+
+    if props.languages.split(',').next() != Some(props.language.as_str()) {
+        push(
+            issues,
+            ctx,
+            format!(
+                "languages {:?} does not start with language {:?}",
+                props.languages, props.language
+            ),
+        );
+    }
+}
+
+fn check_machine(
+    preset: &Preset,
+    props: &BrowserProperties,
+    family: Family,
+    issues: &mut Vec<Mismatch>,
+) {
+    let ctx = "machine";
+    let machine = &preset.machine;
+
+    if machine.ua_identifiers != (family == Family::Chromium) {
+        push(
+            issues,
+            ctx,
+            format!(
+                "uaIdentifiers={} does not match {:?} browser (navigator.userAgentData is Chromium-only)",
+                machine.ua_identifiers, family
+            ),
+        );
+    }
+    if machine.device_memory != props.device_memory.is_some() {
+        push(
+            issues,
+            ctx,
+            format!(
+                "deviceMemory={} does not match navigator.deviceMemory {:?}",
+                machine.device_memory, props.device_memory
+            ),
+        );
+    }
+    if machine.hardware_concurrency != (props.hardware_concurrency > 0) {
+        push(
+            issues,
+            ctx,
+            format!(
+                "hardwareConcurrency={} does not match navigator.hardwareConcurrency {}",
+                machine.hardware_concurrency, props.hardware_concurrency
+            ),
+        );
+    }
+    if machine.gpu && family != Family::Chromium {
+        push(
+            issues,
+            ctx,
+            format!("gpu=true but {:?} does not ship WebGPU by default", family),
+        );
+    }
+}
+
+fn check_client_hints(preset: &Preset, family: Family, os: Os, issues: &mut Vec<Mismatch>) {
+    let ctx = "client-hints";
+    let hints = match preset.client_hints {
+        Some(ref hints) => hints,
+        None => {
+            if family == Family::Chromium {
+                push(
+                    issues,
+                    ctx,
+                    "Chromium preset has no high-entropy client hint values".to_string(),
+                );
+            }
+            return;
+        }
+    };
+
+    if family != Family::Chromium {
+        push(
+            issues,
+            ctx,
+            format!("{:?} does not support User-Agent client hints", family),
+        );
+    }
+
+    let ua_version = ua_major_version(preset.user_agent, family);
+    if hints.full_version.split('.').next() != ua_version.as_deref() {
+        push(
+            issues,
+            ctx,
+            format!(
+                "full version {} does not match User-Agent version {}",
+                hints.full_version,
+                ua_version.as_deref().unwrap_or("unknown")
+            ),
+        );
+    }
+    if hints.full_version.split('.').count() != 4 {
+        push(
+            issues,
+            ctx,
+            format!(
+                "full version {} must have four components",
+                hints.full_version
+            ),
+        );
+    }
+
+    let platform_major = hints
+        .platform_version
+        .split('.')
+        .next()
+        .and_then(|major| major.parse::<u32>().ok());
+    let platform_version_ok = match (os, platform_major) {
+        (_, None) => false,
+        // Windows 10 reports 1-10, Windows 11 reports 13+; both send "Windows NT 10.0".
+        (Os::Windows, Some(major)) => {
+            preset.user_agent.contains("Windows NT 10.0")
+                && major >= 1
+                && major != 11
+                && major != 12
+        }
+        (Os::Mac, Some(major)) => major >= 10,
+        (_, Some(_)) => true,
+    };
+    if !platform_version_ok {
+        push(
+            issues,
+            ctx,
+            format!(
+                "platform version {:?} does not match User-Agent OS {:?}",
+                hints.platform_version, os
+            ),
+        );
+    }
+
+    let arch_ok = match os {
+        Os::Windows if preset.user_agent.contains("x64") => hints.arch == "x86",
+        Os::Windows if preset.user_agent.contains("ARM64") => hints.arch == "arm",
+        // Apple silicon still reports an Intel Mac User-Agent.
+        Os::Mac => hints.arch == "x86" || hints.arch == "arm",
+        _ => !hints.arch.is_empty(),
+    };
+    if !arch_ok {
+        push(
+            issues,
+            ctx,
+            format!(
+                "architecture {:?} does not match User-Agent platform token",
+                hints.arch
+            ),
+        );
+    }
+
+    if !hints.model.is_empty() && !preset.user_agent.contains("Mobile") {
+        push(
+            issues,
+            ctx,
+            format!(
+                "desktop browsers report an empty model, not {:?}",
+                hints.model
+            ),
+        );
+    }
+}
+
+fn check_headers(
+    ctx: &'static str,
+    headers: &HeaderMap,
+    preset: &Preset,
+    props: &BrowserProperties,
+    family: Family,
+    os: Os,
+    issues: &mut Vec<Mismatch>,
+) {
+    match header_str(headers, "User-Agent") {
+        Some(ua) if ua == preset.user_agent => {}
+        Some(ua) => push(
+            issues,
+            ctx,
+            format!("User-Agent header {:?} differs from preset", ua),
+        ),
+        None => push(issues, ctx, "missing User-Agent header".to_string()),
+    }
+
+    let has_client_hints = headers
+        .keys()
+        .any(|name| name.as_str().starts_with("sec-ch-ua"));
+    if family == Family::Chromium {
+        for name in ["Sec-Ch-Ua", "Sec-Ch-Ua-Mobile", "Sec-Ch-Ua-Platform"] {
+            if !headers.contains_key(name) {
+                push(issues, ctx, format!("missing {} header", name));
+            }
+        }
+    } else if has_client_hints && family != Family::Unknown {
+        push(
+            issues,
+            ctx,
+            format!("{:?} does not send Sec-Ch-Ua client hints", family),
+        );
+    }
+
+    if let Some(brands) = header_str(headers, "Sec-Ch-Ua") {
+        let ua_version = ua_major_version(preset.user_agent, family);
+        for (brand, version) in parse_brands(brands) {
+            let is_browser_brand = matches!(
+                brand.as_str(),
+                "Chromium" | "Google Chrome" | "Microsoft Edge"
+            );
+            if is_browser_brand && ua_version.as_deref() != Some(version.as_str()) {
+                push(
+                    issues,
+                    ctx,
+                    format!(
+                        "Sec-Ch-Ua brand {:?} version {} does not match User-Agent version {}",
+                        brand,
+                        version,
+                        ua_version.as_deref().unwrap_or("unknown")
+                    ),
+                );
+            }
+        }
+    }
+
+    if let (Some(brands), Some(full_list)) = (
+        header_str(headers, "Sec-Ch-Ua"),
+        header_str(headers, "Sec-Ch-Ua-Full-Version-List"),
+    ) {
+        let brands = parse_brands(brands);
+        for (brand, full_version) in parse_brands(full_list) {
+            let major = full_version.split('.').next().unwrap_or("");
+            match brands.iter().find(|(name, _)| *name == brand) {
+                Some((_, version)) if version == major => {}
+                Some((_, version)) => push(
+                    issues,
+                    ctx,
+                    format!(
+                        "Sec-Ch-Ua-Full-Version-List brand {:?} version {} does not match Sec-Ch-Ua version {}",
+                        brand, full_version, version
+                    ),
+                ),
+                None => push(
+                    issues,
+                    ctx,
+                    format!(
+                        "Sec-Ch-Ua-Full-Version-List brand {:?} is missing from Sec-Ch-Ua",
+                        brand
+                    ),
+                ),
+            }
+        }
+    }
+
+    if let Some(platform) = header_str(headers, "Sec-Ch-Ua-Platform") {
+        let platform_ok = match os {
+            Os::Windows => platform == "\"Windows\"",
+            Os::Mac => platform == "\"macOS\"",
+            Os::Linux => platform == "\"Linux\"",
+            Os::Unknown => true,
+        };
+        if !platform_ok {
+            push(
+                issues,
+                ctx,
+                format!(
+                    "Sec-Ch-Ua-Platform {} does not match User-Agent OS {:?} (platform {:?})",
+                    platform, os, props.platform
+                ),
+            );
+        }
+    }
+
+    if let Some(mobile) = header_str(headers, "Sec-Ch-Ua-Mobile") {
+        let ua_mobile = preset.user_agent.contains("Mobile");
+        if (mobile == "?1") != ua_mobile {
+            push(
+                issues,
+                ctx,
+                format!("Sec-Ch-Ua-Mobile {} does not match User-Agent", mobile),
+            );
+        }
+    }
+
+    if let Some(accept_language) = header_str(headers, "Accept-Language") {
+        let primary = accept_language
+            .split(',')
+            .next()
+            .and_then(|tag| tag.split(';').next())
+            .unwrap_or("")
+            .trim();
+        if !primary.eq_ignore_ascii_case(&props.language) {
+            push(
+                issues,
+                ctx,
+                format!(
+                    "Accept-Language {:?} does not lead with navigator language {:?}",
+                    accept_language, props.language
+                ),
+            );
+        }
+    }
+
+    if let Some(encoding) = header_str(headers, "Accept-Encoding") {
+        if family == Family::Safari && encoding.contains("zstd") {
+            push(
+                issues,
+                ctx,
+                "Safari does not advertise zstd in Accept-Encoding".to_string(),
+            );
+        }
+    }
+}
+
+fn push(issues: &mut Vec<Mismatch>, context: &'static str, message: String) {
+    issues.push(Mismatch { context, message });
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+fn ua_family(user_agent: &str) -> Family {
+    if user_agent.contains("Firefox/") {
+        Family::Firefox
+    } else if user_agent.contains("Chrome/") {
+        Family::Chromium
+    } else if user_agent.contains("Version/") && user_agent.contains("Safari/") {
+        Family::Safari
+    } else {
+        Family::Unknown
+    }
+}
+
+/// Built-in presets whose User-Agent OS matches a free-form OS guess such as the
+/// engine's TCP/IP fingerprint ("Windows", "Linux", "Mac OS").
+pub fn presets_matching_os(os_guess: &str) -> Vec<&'static str> {
+    PRESET_NAMES
+        .iter()
+        .copied()
+        .filter(|name| preset_matches_os(&builtin_preset(name), os_guess))
+        .collect()
+}
+
+/// Whether the preset's User-Agent claims the OS family of `os_guess`.
+pub fn preset_matches_os(preset: &Preset, os_guess: &str) -> bool {
+    let guess = os_guess.to_ascii_lowercase();
+    let os = if guess.contains("windows") {
+        Os::Windows
+    } else if guess.contains("mac") || guess.contains("ios") || guess.contains("darwin") {
+        Os::Mac
+    } else if guess.contains("linux") || guess.contains("android") {
+        Os::Linux
+    } else {
+        return false;
+    };
+    ua_os(preset.user_agent) == os
+}
+
+fn ua_os(user_agent: &str) -> Os {
+    if user_agent.contains("Windows NT") {
+        Os::Windows
+    } else if user_agent.contains("Macintosh") {
+        Os::Mac
+    } else if user_agent.contains("Linux") || user_agent.contains("X11") {
+        Os::Linux
+    } else {
+        Os::Unknown
+    }
+}
+
+/// Major version from the User-Agent token that identifies the browser.
+fn ua_major_version(user_agent: &str, family: Family) -> Option<String> {
+    let token = match family {
+        Family::Chromium => "Chrome/",
+        Family::Firefox => "Firefox/",
+        Family::Safari => "Version/",
+        Family::Unknown => return None,
+    };
+    let start = user_agent.find(token)? + token.len();
+    let major: String = user_agent[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    if major.is_empty() {
+        None
+    } else {
+        Some(major)
+    }
+}
+
+/// Parse a structured `Sec-Ch-Ua` brand list into (brand, version) pairs.
+fn parse_brands(value: &str) -> Vec<(String, String)> {
+    value
+        .split(',')
+        .filter_map(|entry| {
+            let (brand, version) = entry.split_once(";v=")?;
+            Some((
+                brand.trim().trim_matches('"').to_string(),
+                version.trim().trim_matches('"').to_string(),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browser::preset::ClientHints;
+
+    fn messages(issues: &[Mismatch], context: &str) -> Vec<String> {
+        issues
+            .iter()
+            .filter(|issue| issue.context == context)
+            .map(|issue| issue.message.clone())
+            .collect()
+    }
+
+    #[test]
+    fn builtin_presets_are_coherent() {
+        for name in PRESET_NAMES {
+            let issues = check_preset(&builtin_preset(name));
+            assert!(issues.is_empty(), "{}: {:?}", name, issues);
+        }
+    }
+
+    #[test]
+    fn firefox_user_agent_on_chrome_preset() {
+        let preset = Preset {
+            user_agent:
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:133.0) Gecko/20100101 Firefox/133.0",
+            ..builtin_preset("chrome-143")
+        };
+        let issues = check_preset(&preset);
+
+        let properties = messages(&issues, "properties");
+        assert!(properties
+            .iter()
+            .any(|m| m.starts_with("vendor \"Google Inc.\"")));
+        assert!(properties.iter().any(|m| m == "Firefox must expose oscpu"));
+        assert!(messages(&issues, "machine")
+            .iter()
+            .any(|m| m.starts_with("uaIdentifiers=true")));
+        assert!(messages(&issues, "client-hints")
+            .iter()
+            .any(|m| m == "Firefox does not support User-Agent client hints"));
+        assert!(messages(&issues, "script")
+            .iter()
+            .any(|m| m == "Firefox does not send Sec-Ch-Ua client hints"));
+    }
+
+    #[test]
+    fn windows_user_agent_on_linux_properties() {
+        let preset = Preset {
+            user_agent: builtin_preset("chrome-143").user_agent,
+            ..builtin_preset("chrome-143-linux")
+        };
+        let issues = check_preset(&preset);
+        assert!(messages(&issues, "properties").iter().any(
+            |m| m.starts_with("platform \"Linux x86_64\" does not match User-Agent OS Windows")
+        ));
+    }
+
+    #[test]
+    fn client_hint_values_must_match_user_agent() {
+        let preset = Preset {
+            client_hints: Some(ClientHints {
+                full_version: "142.0.7444.60",
+                // Windows never reports platform versions 11 or 12.
+                platform_version: "11.0.0",
+                arch: "arm",
+                model: "Pixel 9",
+            }),
+            ..builtin_preset("chrome-143")
+        };
+        let hints = messages(&check_preset(&preset), "client-hints");
+        assert_eq!(
+            hints,
+            [
+                "full version 142.0.7444.60 does not match User-Agent version 143",
+                "platform version \"11.0.0\" does not match User-Agent OS Windows",
+                "architecture \"arm\" does not match User-Agent platform token",
+                "desktop browsers report an empty model, not \"Pixel 9\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_user_agent() {
+        let preset = Preset {
+            user_agent: "curl/8.5.0",
+            ..builtin_preset("chrome-143")
+        };
+        let issues = messages(&check_preset(&preset), "user-agent");
+        assert_eq!(
+            issues,
+            [
+                "unrecognized browser family",
+                "unrecognized operating system"
+            ]
+        );
+    }
+
+    #[test]
+    fn os_guess_matching() {
+        assert!(preset_matches_os(
+            &builtin_preset("chrome-143"),
+            "Windows NT kernel"
+        ));
+        assert!(preset_matches_os(
+            &builtin_preset("chrome-143-linux"),
+            "Linux 2.2.x-3.x"
+        ));
+        assert!(preset_matches_os(&builtin_preset("safari-18"), "Mac OS X"));
+        assert!(!preset_matches_os(&builtin_preset("safari-18"), "Linux"));
+        assert!(!preset_matches_os(&builtin_preset("chrome-143"), "unknown"));
+        assert_eq!(presets_matching_os("Linux"), ["chrome-143-linux"]);
+    }
+
+    #[test]
+    fn brand_and_version_parsing() {
+        assert_eq!(
+            parse_brands(r#""Google Chrome";v="143", "Chromium";v="143", "Not A(Brand";v="24""#),
+            [
+                ("Google Chrome".to_string(), "143".to_string()),
+                ("Chromium".to_string(), "143".to_string()),
+                ("Not A(Brand".to_string(), "24".to_string()),
+            ]
+        );
+        let safari = builtin_preset("safari-18").user_agent;
+        assert_eq!(
+            ua_major_version(safari, Family::Safari).as_deref(),
+            Some("18")
+        );
+        assert_eq!(ua_major_version(safari, Family::Unknown), None);
+    }
+}
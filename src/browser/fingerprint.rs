@@ -1,21 +1,23 @@
+use super::headers::language_preferences;
+use super::preset::Preset;
 use std::fmt::Write;
 
 /// Browser navigator properties for fingerprint generation.
-struct BrowserProperties {
+pub(super) struct BrowserProperties {
     boolean_fingerprint: u32,
-    hardware_concurrency: i32,
-    device_memory: Option<&'static str>,
-    platform: &'static str,
-    oscpu: Option<&'static str>,
+    pub(super) hardware_concurrency: i32,
+    pub(super) device_memory: Option<String>,
+    pub(super) platform: &'static str,
+    pub(super) oscpu: Option<&'static str>,
     cpu_class: Option<&'static str>,
-    vendor: &'static str,
-    build_id: Option<&'static str>,
+    pub(super) vendor: &'static str,
+    pub(super) build_id: Option<&'static str>,
     product: &'static str,
-    product_sub: &'static str,
+    pub(super) product_sub: &'static str,
     plugins_support: bool,
     max_touch_points: i32,
-    language: &'static str,
-    languages: &'static str,
+    pub(super) language: String,
+    pub(super) languages: String,
     session_storage: bool,
     local_storage: bool,
     indexed_db: bool,
@@ -25,7 +27,7 @@ struct BrowserProperties {
     sayswho: &'static str,
     load_purpose: &'static str,
     webdriver: bool,
-    dimensions: &'static str,
+    dimensions: String,
     geolocation: bool,
     vibrate: bool,
     get_battery: bool,
@@ -40,46 +42,72 @@ struct BrowserProperties {
     selenium: bool,
 }
 
-fn build_fingerprint_string(props: &BrowserProperties) -> String {
-    let mut s = String::with_capacity(1024);
+/// Ordered `key:value` pairs hashed into the fingerprint.
+///
+/// Key order matches the detection script; `webdriver` appears twice (navigator and window).
+fn fingerprint_entries(props: &BrowserProperties) -> Vec<(&'static str, String)> {
+    vec![
+        ("booleanFingerprint", props.boolean_fingerprint.to_string()),
+        (
+            "hardwareConcurrency",
+            props.hardware_concurrency.to_string(),
+        ),
+        (
+            "deviceMemory",
+            props.device_memory.as_deref().unwrap_or("").to_string(),
+        ),
+        ("platform", props.platform.to_string()),
+        ("oscpu", props.oscpu.unwrap_or("").to_string()),
+        ("cpuClass", props.cpu_class.unwrap_or("").to_string()),
+        ("vendor", props.vendor.to_string()),
+        ("buildID", props.build_id.unwrap_or("").to_string()),
+        ("product", props.product.to_string()),
+        ("productSub", props.product_sub.to_string()),
+        ("pluginsSupport", props.plugins_support.to_string()),
+        ("maxTouchPoints", props.max_touch_points.to_string()),
+        ("language", props.language.clone()),
+        ("languages", props.languages.clone()),
+        ("sessionStorage", props.session_storage.to_string()),
+        ("localStorage", props.local_storage.to_string()),
+        ("indexedDB", props.indexed_db.to_string()),
+        ("openDatabase", props.open_database.to_string()),
+        ("navigatorCookieEnabled", props.cookie_enabled.to_string()),
+        ("doNotTrack", props.do_not_track.to_string()),
+        ("sayswho", props.sayswho.to_string()),
+        ("loadPurpose", props.load_purpose.to_string()),
+        ("webdriver", props.webdriver.to_string()),
+        ("dimensions", props.dimensions.clone()),
+        ("geolocation", props.geolocation.to_string()),
+        ("vibrate", props.vibrate.to_string()),
+        ("getBattery", props.get_battery.to_string()),
+        ("webrtcKey", props.webrtc_key.to_string()),
+        ("_phantom", props.phantom.to_string()),
+        ("webdriver", props.window_webdriver.to_string()),
+        ("domAutomation", props.dom_automation.to_string()),
+        ("auto", props.auto.to_string()),
+        ("wd1", props.wd1.to_string()),
+        ("XPathResult", props.xpath_result.to_string()),
+        ("wd2", props.wd2.to_string()),
+        ("selenium", props.selenium.to_string()),
+    ]
+}
 
-    write!(s, "booleanFingerprint:{};", props.boolean_fingerprint).unwrap();
-    write!(s, "hardwareConcurrency:{};", props.hardware_concurrency).unwrap();
-    write!(s, "deviceMemory:{};", props.device_memory.unwrap_or("")).unwrap();
-    write!(s, "platform:{};", props.platform).unwrap();
-    write!(s, "oscpu:{};", props.oscpu.unwrap_or("")).unwrap();
-    write!(s, "cpuClass:{};", props.cpu_class.unwrap_or("")).unwrap();
-    write!(s, "vendor:{};", props.vendor).unwrap();
-    write!(s, "buildID:{};", props.build_id.unwrap_or("")).unwrap();
-    write!(s, "product:{};", props.product).unwrap();
-    write!(s, "productSub:{};", props.product_sub).unwrap();
-    write!(s, "pluginsSupport:{};", props.plugins_support).unwrap();
-    write!(s, "maxTouchPoints:{};", props.max_touch_points).unwrap();
-    write!(s, "language:{};", props.language).unwrap();
-    write!(s, "languages:{};", props.languages).unwrap();
-    write!(s, "sessionStorage:{};", props.session_storage).unwrap();
-    write!(s, "localStorage:{};", props.local_storage).unwrap();
-    write!(s, "indexedDB:{};", props.indexed_db).unwrap();
-    write!(s, "openDatabase:{};", props.open_database).unwrap();
-    write!(s, "navigatorCookieEnabled:{};", props.cookie_enabled).unwrap();
-    write!(s, "doNotTrack:{};", props.do_not_track).unwrap();
-    write!(s, "sayswho:{};", props.sayswho).unwrap();
-    write!(s, "loadPurpose:{};", props.load_purpose).unwrap();
-    write!(s, "webdriver:{};", props.webdriver).unwrap();
-    write!(s, "dimensions:{};", props.dimensions).unwrap();
-    write!(s, "geolocation:{};", props.geolocation).unwrap();
-    write!(s, "vibrate:{};", props.vibrate).unwrap();
-    write!(s, "getBattery:{};", props.get_battery).unwrap();
-    write!(s, "webrtcKey:{};", props.webrtc_key).unwrap();
-    write!(s, "_phantom:{};", props.phantom).unwrap();
-    write!(s, "webdriver:{};", props.window_webdriver).unwrap();
-    write!(s, "domAutomation:{};", props.dom_automation).unwrap();
-    write!(s, "auto:{};", props.auto).unwrap();
-    write!(s, "wd1:{};", props.wd1).unwrap();
-    write!(s, "XPathResult:{};", props.xpath_result).unwrap();
-    write!(s, "wd2:{};", props.wd2).unwrap();
-    write!(s, "selenium:{};", props.selenium).unwrap();
+/// Property names accepted by fingerprint overrides.
+pub fn fingerprint_keys() -> Vec<&'static str> {
+    let mut keys: Vec<&'static str> = Vec::new();
+    for (key, _) in fingerprint_entries(&chrome_properties()) {
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    keys
+}
 
+fn build_fingerprint_string(entries: &[(&'static str, String)]) -> String {
+    let mut s = String::with_capacity(1024);
+    for (key, value) in entries {
+        write!(s, "{}:{};", key, value).unwrap();
+    }
     s
 }
 
@@ -87,7 +115,7 @@ fn chrome_properties() -> BrowserProperties {
     BrowserProperties {
         boolean_fingerprint: 25952189,
         hardware_concurrency: 16,
-        device_memory: Some("8"),
+        device_memory: Some("8".to_string()),
         platform: "Win32",
         oscpu: None,
         cpu_class: None,
@@ -97,8 +125,8 @@ fn chrome_properties() -> BrowserProperties {
         product_sub: "20030107",
         plugins_support: true,
         max_touch_points: 0,
-        language: "en-US",
-        languages: "en-US,en",
+        language: "en-US".to_string(),
+        languages: "en-US,en".to_string(),
         session_storage: true,
         local_storage: true,
         indexed_db: true,
@@ -108,7 +136,7 @@ fn chrome_properties() -> BrowserProperties {
         sayswho: "",
         load_purpose: "",
         webdriver: false,
-        dimensions: "1920,1080",
+        dimensions: "1920,1080".to_string(),
         geolocation: true,
         vibrate: true,
         get_battery: true,
@@ -124,6 +152,13 @@ fn chrome_properties() -> BrowserProperties {
     }
 }
 
+fn chrome_linux_properties() -> BrowserProperties {
+    BrowserProperties {
+        platform: "Linux x86_64",
+        ..chrome_properties()
+    }
+}
+
 fn firefox_properties() -> BrowserProperties {
     BrowserProperties {
         boolean_fingerprint: 26066385,
@@ -138,8 +173,8 @@ fn firefox_properties() -> BrowserProperties {
         product_sub: "20100101",
         plugins_support: true,
         max_touch_points: 0,
-        language: "en-US",
-        languages: "en-US,en",
+        language: "en-US".to_string(),
+        languages: "en-US,en".to_string(),
         session_storage: true,
         local_storage: true,
         indexed_db: true,
@@ -149,7 +184,7 @@ fn firefox_properties() -> BrowserProperties {
         sayswho: "",
         load_purpose: "",
         webdriver: false,
-        dimensions: "1920,1080",
+        dimensions: "1920,1080".to_string(),
         geolocation: true,
         vibrate: true,
         get_battery: true,
@@ -179,8 +214,8 @@ fn safari_properties() -> BrowserProperties {
         product_sub: "20030107",
         plugins_support: true,
         max_touch_points: 0,
-        language: "en-US",
-        languages: "en-US,en",
+        language: "en-US".to_string(),
+        languages: "en-US,en".to_string(),
         session_storage: true,
         local_storage: true,
         indexed_db: true,
@@ -190,7 +225,7 @@ fn safari_properties() -> BrowserProperties {
         sayswho: "",
         load_purpose: "",
         webdriver: false,
-        dimensions: "1920,1080",
+        dimensions: "1920,1080".to_string(),
         geolocation: true,
         vibrate: false,
         get_battery: false,
@@ -206,6 +241,31 @@ fn safari_properties() -> BrowserProperties {
     }
 }
 
+/// Navigator properties reported by a browser preset.
+pub(super) fn properties_for(preset: &Preset) -> BrowserProperties {
+    let mut props = match preset.base {
+        "chrome-143-linux" => chrome_linux_properties(),
+        "firefox-133" => firefox_properties(),
+        "safari-18" => safari_properties(),
+        _ => chrome_properties(),
+    };
+    let languages = language_preferences(preset);
+    props.language = languages.first().cloned().unwrap_or_default();
+    props.languages = languages.join(",");
+
+    if let Some(ref hardware) = preset.hardware {
+        props.hardware_concurrency = hardware.hardware_concurrency;
+        if let Some(memory) = hardware
+            .device_memory
+            .filter(|_| props.device_memory.is_some())
+        {
+            props.device_memory = Some(memory.to_string());
+        }
+        props.dimensions = hardware.dimensions();
+    }
+    props
+}
+
 /// MurmurHash3 x86 32-bit (v3) implementation.
 fn murmur_hash3_v3(data: &[u8], seed: u32) -> u32 {
     let length = data.len();
@@ -279,13 +339,25 @@ fn imul32(val: u32, mul: u32) -> u32 {
     ((val & 0xffff).wrapping_mul(mul)).wrapping_add(((val >> 16).wrapping_mul(mul) & 0xffff) << 16)
 }
 
+/// Fingerprint properties of a preset in hash order, with its overrides applied.
+///
+/// An override replaces every entry with that key, so `webdriver` sets both occurrences.
+pub fn fingerprint_properties(preset: &Preset) -> Vec<(&'static str, String)> {
+    let mut entries = fingerprint_entries(&properties_for(preset));
+    for (key, value) in &preset.fingerprint_overrides {
+        for entry in entries.iter_mut().filter(|(name, _)| name == key) {
+            entry.1 = value.clone();
+        }
+    }
+    entries
+}
+
+/// Exact property string hashed for a preset.
+pub fn fingerprint_string(preset: &Preset) -> String {
+    build_fingerprint_string(&fingerprint_properties(preset))
+}
+
 /// Compute the MurmurHash3 fingerprint for a browser preset.
-pub fn compute_fingerprint(preset_name: &str) -> u32 {
-    let props = match preset_name {
-        "firefox-133" => firefox_properties(),
-        "safari-18" => safari_properties(),
-        _ => chrome_properties(),
-    };
-    let input = build_fingerprint_string(&props);
-    murmur_hash3_v3(input.as_bytes(), 0)
+pub fn compute_fingerprint(preset: &Preset) -> u32 {
+    murmur_hash3_v3(fingerprint_string(preset).as_bytes(), 0)
 }
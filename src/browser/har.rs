@@ -0,0 +1,440 @@
+use super::preset::EMULATIONS;
+use super::preset_file::{ClientHintsFile, PresetFile, RecordedHeaders};
+use serde_json::Value;
+use url::Url;
+
+/// Request contexts in the order the scanner sends them.
+pub const HAR_CONTEXTS: &[&str] = &["script", "image", "beacon", "poll", "ipapi"];
+
+/// Headers owned by the connection rather than the page; never stored in presets.
+const SKIPPED_HEADERS: &[&str] = &["host", "content-length", "cookie", "connection"];
+
+/// A request of one scanner context found in a HAR.
+pub struct HarRequest {
+    pub context: &'static str,
+    /// HAR `httpVersion`, e.g. "HTTP/1.1", "http/2.0" or "h2".
+    pub http_version: String,
+    /// All request headers in recorded order, pseudo-headers included.
+    pub headers: Vec<(String, String)>,
+}
+
+/// Requests to proxydetect.live and ipapi.is in a HAR, in recorded order.
+pub fn har_requests(
+    path: &str,
+) -> Result<Vec<HarRequest>, Box<dyn std::error::Error + Send + Sync>> {
+    let content =
+        std::fs::read_to_string(path).map_err(|err| format!("reading {}: {}", path, err))?;
+    let har: Value =
+        serde_json::from_str(&content).map_err(|err| format!("parsing {}: {}", path, err))?;
+    let entries = har
+        .pointer("/log/entries")
+        .and_then(|v| v.as_array())
+        .ok_or("not a HAR file (missing log.entries)")?;
+
+    let mut requests = Vec::new();
+    for request in entries.iter().filter_map(|entry| entry.get("request")) {
+        let method = request
+            .get("method")
+            .and_then(|v| v.as_str())
+            .unwrap_or("GET");
+        let Some(url) = request
+            .get("url")
+            .and_then(|v| v.as_str())
+            .and_then(|url| Url::parse(url).ok())
+        else {
+            continue;
+        };
+        let Some(context) = classify(method, &url) else {
+            continue;
+        };
+        requests.push(HarRequest {
+            context,
+            http_version: request
+                .get("httpVersion")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            headers: raw_headers(request),
+        });
+    }
+    Ok(requests)
+}
+
+/// Build a preset file from a HAR recorded while a real browser ran proxydetect.live.
+///
+/// The first request of each context wins; headers keep the browser's order.
+pub fn import_har(
+    path: &str,
+    name: Option<&str>,
+) -> Result<PresetFile, Box<dyn std::error::Error + Send + Sync>> {
+    let mut recorded = RecordedHeaders::default();
+    let mut user_agent: Option<String> = None;
+
+    for request in har_requests(path)? {
+        let slot = match request.context {
+            "script" => &mut recorded.script,
+            "image" => &mut recorded.image,
+            "beacon" => &mut recorded.beacon,
+            "poll" => &mut recorded.poll,
+            "ipapi" => &mut recorded.ipapi,
+            _ => continue,
+        };
+        if !slot.is_empty() {
+            continue;
+        }
+
+        let headers = preset_headers(request.headers);
+        if user_agent.is_none() {
+            user_agent = headers
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case("user-agent"))
+                .map(|(_, value)| value.clone());
+        }
+        *slot = headers;
+    }
+
+    let user_agent =
+        user_agent.ok_or("no proxydetect.live or ipapi.is requests with a User-Agent in HAR")?;
+    let (family, base, major) = ua_family(&user_agent)
+        .ok_or_else(|| format!("unsupported browser User-Agent: {}", user_agent))?;
+
+    Ok(PresetFile {
+        name: name
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{}-{}", family, major)),
+        base: base.to_string(),
+        emulation: closest_emulation(family, major).to_string(),
+        user_agent,
+        client_hints: client_hints(&recorded),
+        headers: recorded,
+        tls_fingerprints: None,
+    })
+}
+
+/// Scanner request context a HAR request corresponds to.
+fn classify(method: &str, url: &Url) -> Option<&'static str> {
+    let host = url.host_str()?;
+    if host == "api.ipapi.is" {
+        return Some("ipapi");
+    }
+    if !host.ends_with("proxydetect.live") {
+        return None;
+    }
+    let path = url.path();
+    if path.ends_with("/pd-lib.js") {
+        Some("script")
+    } else if path.starts_with("/images/") {
+        Some("image")
+    } else if method == "POST" && path == "/s" {
+        Some("beacon")
+    } else if method == "GET" && path == "/i" {
+        Some("poll")
+    } else {
+        None
+    }
+}
+
+/// Request headers in recorded order.
+fn raw_headers(request: &Value) -> Vec<(String, String)> {
+    request
+        .get("headers")
+        .and_then(|v| v.as_array())
+        .map(|headers| {
+            headers
+                .iter()
+                .filter_map(|header| {
+                    let name = header.get("name")?.as_str()?;
+                    let value = header.get("value")?.as_str()?;
+                    Some((name.to_string(), value.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Recorded headers without HTTP/2 pseudo-headers and connection headers.
+fn preset_headers(headers: Vec<(String, String)>) -> Vec<(String, String)> {
+    headers
+        .into_iter()
+        .filter(|(name, _)| {
+            !name.starts_with(':')
+                && !SKIPPED_HEADERS
+                    .iter()
+                    .any(|skipped| name.eq_ignore_ascii_case(skipped))
+        })
+        .collect()
+}
+
+/// Emulation family, built-in base preset and major version of a User-Agent.
+fn ua_family(user_agent: &str) -> Option<(&'static str, &'static str, u32)> {
+    let major = |token: &str| -> Option<u32> {
+        let rest = &user_agent[user_agent.find(token)? + token.len()..];
+        let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+        digits.parse().ok()
+    };
+
+    if let Some(version) = major("Firefox/") {
+        Some(("firefox", "firefox-133", version))
+    } else if let Some(version) = major("Edg/") {
+        Some(("edge", "chrome-143", version))
+    } else if let Some(version) = major("Chrome/") {
+        let base = if user_agent.contains("X11; Linux") {
+            "chrome-143-linux"
+        } else {
+            "chrome-143"
+        };
+        Some(("chrome", base, version))
+    } else if user_agent.contains("Safari/") {
+        major("Version/").map(|version| ("safari", "safari-18", version))
+    } else {
+        None
+    }
+}
+
+/// Newest emulation of the family not newer than the browser, else the oldest one.
+fn closest_emulation(family: &str, major: u32) -> &'static str {
+    let candidates: Vec<(&'static str, u32)> = EMULATIONS
+        .iter()
+        .filter_map(|(name, _)| {
+            let version = name.strip_prefix(family)?.strip_prefix('-')?;
+            let emulation_major = version.split('.').next()?.parse().ok()?;
+            Some((*name, emulation_major))
+        })
+        .collect();
+
+    candidates
+        .iter()
+        .filter(|(_, emulation_major)| *emulation_major <= major)
+        .max_by_key(|(_, emulation_major)| *emulation_major)
+        .or_else(|| {
+            candidates
+                .iter()
+                .min_by_key(|(_, emulation_major)| *emulation_major)
+        })
+        .map(|(name, _)| *name)
+        .unwrap_or("chrome-143")
+}
+
+/// High-entropy client hints, when the browser sent them on any recorded request.
+fn client_hints(recorded: &RecordedHeaders) -> Option<ClientHintsFile> {
+    let find = |wanted: &str| recorded.value(wanted).map(str::to_string);
+    let find_unquoted = |wanted: &str| -> String {
+        find(wanted)
+            .map(|value| value.trim_matches('"').to_string())
+            .unwrap_or_default()
+    };
+
+    let full_version_list = find("sec-ch-ua-full-version-list")?;
+    let full_version = full_version_list.split(", ").find_map(|entry| {
+        let (brand, version) = entry.split_once(";v=")?;
+        matches!(
+            brand.trim_matches('"'),
+            "Google Chrome" | "Microsoft Edge" | "Chromium"
+        )
+        .then(|| version.trim_matches('"').to_string())
+    })?;
+
+    Some(ClientHintsFile {
+        full_version,
+        platform_version: find_unquoted("sec-ch-ua-platform-version"),
+        arch: find_unquoted("sec-ch-ua-arch"),
+        model: find_unquoted("sec-ch-ua-model"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAC_CHROME: &str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/138.0.0.0 Safari/537.36";
+
+    fn entry(method: &str, url: &str, headers: &[(&str, &str)]) -> Value {
+        let headers: Vec<Value> = headers
+            .iter()
+            .map(|(name, value)| serde_json::json!({ "name": name, "value": value }))
+            .collect();
+        serde_json::json!({
+            "request": {
+                "method": method,
+                "url": url,
+                "httpVersion": "h2",
+                "headers": headers,
+            }
+        })
+    }
+
+    /// A Chrome 138 on macOS capture with every context plus unrelated traffic.
+    fn write_har(name: &str) -> String {
+        let ua = ("user-agent", MAC_CHROME);
+        let entries = [
+            entry("GET", "https://example.com/", &[ua]),
+            entry(
+                "GET",
+                "https://proxydetect.live/pd-lib.js",
+                &[
+                    (":method", "GET"),
+                    (":authority", "proxydetect.live"),
+                    (
+                        "sec-ch-ua",
+                        r#""Not)A;Brand";v="8", "Chromium";v="138", "Google Chrome";v="138""#,
+                    ),
+                    ("sec-ch-ua-platform", r#""macOS""#),
+                    ua,
+                    ("accept", "*/*"),
+                    ("cookie", "session=1"),
+                ],
+            ),
+            entry(
+                "GET",
+                "https://proxydetect.live/images/1.png",
+                &[
+                    (
+                        "sec-ch-ua-full-version-list",
+                        r#""Not)A;Brand";v="8.0.0.0", "Chromium";v="138.0.7204.158", "Google Chrome";v="138.0.7204.158""#,
+                    ),
+                    ("sec-ch-ua-platform-version", r#""15.5.0""#),
+                    ("sec-ch-ua-arch", r#""arm""#),
+                    ("sec-ch-ua-model", r#""""#),
+                    ua,
+                ],
+            ),
+            entry(
+                "POST",
+                "https://proxydetect.live/s",
+                &[ua, ("content-length", "42")],
+            ),
+            entry("GET", "https://proxydetect.live/i", &[ua]),
+            entry("GET", "https://api.ipapi.is/", &[ua]),
+            // Only the first request of a context is kept.
+            entry("GET", "https://proxydetect.live/pd-lib.js", &[ua]),
+        ];
+        let har = serde_json::json!({ "log": { "entries": entries } });
+        let path = std::env::temp_dir().join(format!("har-{}-{}.har", name, std::process::id()));
+        std::fs::write(&path, har.to_string()).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    fn url(value: &str) -> Url {
+        Url::parse(value).unwrap()
+    }
+
+    #[test]
+    fn classifies_requests_per_context() {
+        let cases = [
+            ("GET", "https://proxydetect.live/pd-lib.js", Some("script")),
+            (
+                "GET",
+                "https://cdn.proxydetect.live/v2/pd-lib.js",
+                Some("script"),
+            ),
+            (
+                "GET",
+                "https://proxydetect.live/images/7.png",
+                Some("image"),
+            ),
+            ("POST", "https://proxydetect.live/s", Some("beacon")),
+            ("GET", "https://proxydetect.live/s", None),
+            ("GET", "https://proxydetect.live/i", Some("poll")),
+            ("POST", "https://proxydetect.live/i", None),
+            ("GET", "https://api.ipapi.is/?q=1.2.3.4", Some("ipapi")),
+            ("GET", "https://example.com/pd-lib.js", None),
+        ];
+        for (method, value, expected) in cases {
+            assert_eq!(
+                classify(method, &url(value)),
+                expected,
+                "{} {}",
+                method,
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn strips_pseudo_and_connection_headers() {
+        let headers = preset_headers(vec![
+            (":method".to_string(), "GET".to_string()),
+            ("Host".to_string(), "proxydetect.live".to_string()),
+            ("accept".to_string(), "*/*".to_string()),
+            ("Cookie".to_string(), "a=1".to_string()),
+            ("user-agent".to_string(), MAC_CHROME.to_string()),
+            ("Connection".to_string(), "keep-alive".to_string()),
+        ]);
+        let names: Vec<_> = headers.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["accept", "user-agent"]);
+    }
+
+    #[test]
+    fn picks_family_base_and_emulation_from_the_user_agent() {
+        let firefox =
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:140.0) Gecko/20100101 Firefox/140.0";
+        let edge = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/140.0.0.0 Safari/537.36 Edg/140.0.0.0";
+        let linux = "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/143.0.0.0 Safari/537.36";
+        let safari = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/18.5 Safari/605.1.15";
+
+        assert_eq!(ua_family(firefox), Some(("firefox", "firefox-133", 140)));
+        assert_eq!(ua_family(edge), Some(("edge", "chrome-143", 140)));
+        assert_eq!(ua_family(linux), Some(("chrome", "chrome-143-linux", 143)));
+        assert_eq!(ua_family(MAC_CHROME), Some(("chrome", "chrome-143", 138)));
+        assert_eq!(ua_family(safari), Some(("safari", "safari-18", 18)));
+        assert_eq!(ua_family("curl/8.5.0"), None);
+
+        assert_eq!(closest_emulation("chrome", 138), "chrome-131");
+        assert_eq!(closest_emulation("chrome", 150), "chrome-143");
+        assert_eq!(closest_emulation("firefox", 140), "firefox-136");
+        // Older than every emulation of the family: the oldest one.
+        assert_eq!(closest_emulation("firefox", 120), "firefox-133");
+        assert_eq!(closest_emulation("edge", 140), "edge-134");
+        assert_eq!(closest_emulation("safari", 18), "safari-18.5");
+    }
+
+    #[test]
+    fn imports_contexts_identity_and_client_hints() {
+        let path = write_har("import");
+        let file = import_har(&path, None).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(file.name, "chrome-138");
+        assert_eq!(file.base, "chrome-143");
+        assert_eq!(file.emulation, "chrome-131");
+        assert_eq!(file.user_agent, MAC_CHROME);
+
+        let script: Vec<_> = file
+            .headers
+            .script
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(
+            script,
+            ["sec-ch-ua", "sec-ch-ua-platform", "user-agent", "accept"]
+        );
+        for context in HAR_CONTEXTS {
+            assert!(!file.headers.for_context(context).is_empty(), "{}", context);
+        }
+        assert!(!file
+            .headers
+            .beacon
+            .iter()
+            .any(|(name, _)| name == "content-length"));
+
+        let hints = file.client_hints.as_ref().unwrap();
+        assert_eq!(hints.full_version, "138.0.7204.158");
+        assert_eq!(hints.platform_version, "15.5.0");
+        assert_eq!(hints.arch, "arm");
+        assert_eq!(hints.model, "");
+
+        let preset = file.into_preset().unwrap();
+        assert_eq!(preset.user_agent, MAC_CHROME);
+        assert_eq!(preset.client_hints.unwrap().full_version, "138.0.7204.158");
+    }
+
+    #[test]
+    fn no_client_hints_without_a_full_version_list() {
+        let recorded = RecordedHeaders {
+            script: vec![("sec-ch-ua-arch".to_string(), "\"x86\"".to_string())],
+            ..Default::default()
+        };
+        assert!(client_hints(&recorded).is_none());
+    }
+}
@@ -0,0 +1,220 @@
+use super::preset::Preset;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+/// Per-scan hardware identity reported through navigator and screen properties.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HardwareProfile {
+    pub hardware_concurrency: i32,
+    /// navigator.deviceMemory bucket in GB; `None` for engines without it (Firefox, Safari).
+    pub device_memory: Option<u32>,
+    pub screen_width: u32,
+    pub screen_height: u32,
+    /// Seed the profile was sampled from.
+    pub seed: u64,
+}
+
+impl HardwareProfile {
+    /// Screen size in the "width,height" form used by the fingerprint string.
+    pub fn dimensions(&self) -> String {
+        format!("{},{}", self.screen_width, self.screen_height)
+    }
+
+    /// Short description for logs and tables, e.g. "8 cores, 8 GB, 1920x1080".
+    pub fn summary(&self) -> String {
+        let memory = self
+            .device_memory
+            .map(|gb| format!(", {} GB", gb))
+            .unwrap_or_default();
+        format!(
+            "{} cores{}, {}x{}",
+            self.hardware_concurrency, memory, self.screen_width, self.screen_height
+        )
+    }
+}
+
+// Weights are rough shares from public hardware surveys (desktop browsers only).
+
+const WINDOWS_CORES: &[(i32, u32)] = &[
+    (4, 12),
+    (6, 17),
+    (8, 22),
+    (12, 16),
+    (16, 18),
+    (20, 5),
+    (24, 6),
+    (32, 4),
+];
+
+/// Chromium caps navigator.deviceMemory at 8.
+const WINDOWS_MEMORY: &[(u32, u32)] = &[(4, 14), (8, 86)];
+
+const WINDOWS_SCREENS: &[((u32, u32), u32)] = &[
+    ((1920, 1080), 45),
+    ((1536, 864), 10),
+    ((2560, 1440), 14),
+    ((1366, 768), 9),
+    ((1600, 900), 5),
+    ((3840, 2160), 5),
+    ((1440, 900), 4),
+    ((1680, 1050), 3),
+    ((1280, 720), 3),
+    ((2560, 1080), 2),
+];
+
+const LINUX_CORES: &[(i32, u32)] = &[
+    (4, 14),
+    (6, 10),
+    (8, 24),
+    (12, 14),
+    (16, 22),
+    (24, 8),
+    (32, 8),
+];
+
+const LINUX_MEMORY: &[(u32, u32)] = &[(4, 8), (8, 92)];
+
+const LINUX_SCREENS: &[((u32, u32), u32)] = &[
+    ((1920, 1080), 48),
+    ((2560, 1440), 18),
+    ((3840, 2160), 8),
+    ((1920, 1200), 7),
+    ((1366, 768), 6),
+    ((2560, 1600), 5),
+    ((1600, 900), 4),
+    ((3440, 1440), 4),
+];
+
+/// WebKit reports 8 logical cores on most Macs and 4 on low-end models.
+const MAC_CORES: &[(i32, u32)] = &[(8, 90), (4, 10)];
+
+const MAC_SCREENS: &[((u32, u32), u32)] = &[
+    ((1440, 900), 22),
+    ((1470, 956), 18),
+    ((1512, 982), 20),
+    ((1728, 1117), 12),
+    ((1680, 1050), 6),
+    ((1920, 1080), 10),
+    ((2560, 1440), 12),
+];
+
+/// Sample a realistic hardware profile for the preset's platform.
+///
+/// The same seed always yields the same profile.
+pub fn sample_hardware(preset: &Preset, seed: u64) -> HardwareProfile {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let (cores, memory, screens) = if preset.user_agent.contains("Macintosh") {
+        (MAC_CORES, WINDOWS_MEMORY, MAC_SCREENS)
+    } else if preset.user_agent.contains("X11; Linux") {
+        (LINUX_CORES, LINUX_MEMORY, LINUX_SCREENS)
+    } else {
+        (WINDOWS_CORES, WINDOWS_MEMORY, WINDOWS_SCREENS)
+    };
+
+    let hardware_concurrency = cores
+        .choose_weighted(&mut rng, |(_, weight)| *weight)
+        .map(|(value, _)| *value)
+        .unwrap_or(8);
+    // Drawn for every engine so a seed yields the same cores and screen either way.
+    let device_memory = memory
+        .choose_weighted(&mut rng, |(_, weight)| *weight)
+        .map(|(value, _)| *value)
+        .unwrap_or(8);
+    let device_memory = preset.machine.device_memory.then_some(device_memory);
+    let (screen_width, screen_height) = screens
+        .choose_weighted(&mut rng, |(_, weight)| *weight)
+        .map(|(value, _)| *value)
+        .unwrap_or((1920, 1080));
+
+    HardwareProfile {
+        hardware_concurrency,
+        device_memory,
+        screen_width,
+        screen_height,
+        seed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browser::preset::builtin_preset;
+
+    #[test]
+    fn same_seed_same_profile() {
+        for name in ["chrome-143", "safari-18"] {
+            let preset = builtin_preset(name);
+            for seed in [0, 1, 42, u64::MAX] {
+                let profile = sample_hardware(&preset, seed);
+                assert_eq!(
+                    profile,
+                    sample_hardware(&preset, seed),
+                    "{} seed {}",
+                    name,
+                    seed
+                );
+                assert_eq!(profile.seed, seed);
+            }
+        }
+    }
+
+    #[test]
+    fn profiles_come_from_the_platform_tables() {
+        let mac = builtin_preset("safari-18");
+        let windows = builtin_preset("chrome-143");
+        for seed in 0..200 {
+            let profile = sample_hardware(&mac, seed);
+            assert!(MAC_CORES
+                .iter()
+                .any(|(v, _)| *v == profile.hardware_concurrency));
+            assert!(MAC_SCREENS
+                .iter()
+                .any(|(v, _)| *v == (profile.screen_width, profile.screen_height)));
+
+            let profile = sample_hardware(&windows, seed);
+            assert!(WINDOWS_CORES
+                .iter()
+                .any(|(v, _)| *v == profile.hardware_concurrency));
+            assert!(WINDOWS_MEMORY
+                .iter()
+                .any(|(v, _)| Some(*v) == profile.device_memory));
+            assert!(WINDOWS_SCREENS
+                .iter()
+                .any(|(v, _)| *v == (profile.screen_width, profile.screen_height)));
+        }
+        let linux = builtin_preset("chrome-143-linux");
+        for seed in 0..200 {
+            let profile = sample_hardware(&linux, seed);
+            assert!(LINUX_CORES
+                .iter()
+                .any(|(v, _)| *v == profile.hardware_concurrency));
+            assert!(LINUX_MEMORY
+                .iter()
+                .any(|(v, _)| Some(*v) == profile.device_memory));
+            assert!(LINUX_SCREENS
+                .iter()
+                .any(|(v, _)| *v == (profile.screen_width, profile.screen_height)));
+        }
+        // Different seeds do not all collapse onto one profile.
+        let distinct: std::collections::HashSet<_> = (0..50)
+            .map(|seed| sample_hardware(&windows, seed).dimensions())
+            .collect();
+        assert!(distinct.len() > 1);
+    }
+
+    #[test]
+    fn device_memory_only_for_chromium() {
+        for (name, exposed) in [
+            ("chrome-143", true),
+            ("chrome-143-linux", true),
+            ("firefox-133", false),
+            ("safari-18", false),
+        ] {
+            let profile = sample_hardware(&builtin_preset(name), 7);
+            assert_eq!(profile.device_memory.is_some(), exposed, "{}", name);
+        }
+        let safari = sample_hardware(&builtin_preset("safari-18"), 7);
+        assert!(!safari.summary().contains("GB"));
+    }
+}
@@ -1,31 +1,151 @@
 use super::preset::Preset;
-use wreq::header::{HeaderMap, HeaderValue};
+use wreq::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT_LANGUAGE};
 
 const PAGE_ORIGIN: &str = "https://proxydetect.live";
 const PAGE_REFERER: &str = "https://proxydetect.live/";
 
 fn is_chrome(preset: &Preset) -> bool {
-    preset.name == "chrome-143" || preset.name == "chrome-131"
+    preset.base == "chrome-143" || preset.base == "chrome-143-linux" || preset.base == "chrome-131"
 }
 
 fn is_firefox(preset: &Preset) -> bool {
-    preset.name == "firefox-133"
+    preset.base == "firefox-133"
 }
 
 fn is_safari(preset: &Preset) -> bool {
-    preset.name == "safari-18"
+    preset.base == "safari-18"
 }
 
-fn chrome_sec_ch_ua(preset: &Preset) -> &'static str {
-    if preset.name == "chrome-143" {
+/// A header a preset file recorded in any context; built-in contexts reuse it so
+/// their client hints match the imported User-Agent.
+fn recorded_value<'a>(preset: &'a Preset, name: &str) -> Option<&'a str> {
+    preset.recorded_headers.as_deref()?.value(name)
+}
+
+fn chrome_sec_ch_ua(preset: &Preset) -> &str {
+    if let Some(recorded) = recorded_value(preset, "sec-ch-ua") {
+        recorded
+    } else if preset.base == "chrome-143" || preset.base == "chrome-143-linux" {
         r#""Chromium";v="143", "Not/A)Brand";v="24", "Google Chrome";v="143""#
     } else {
         r#""Chromium";v="131", "Not/A)Brand";v="24", "Google Chrome";v="131""#
     }
 }
 
+fn chrome_sec_ch_ua_platform(preset: &Preset) -> &str {
+    if let Some(recorded) = recorded_value(preset, "sec-ch-ua-platform") {
+        recorded
+    } else if preset.base == "chrome-143-linux" {
+        "\"Linux\""
+    } else {
+        "\"Windows\""
+    }
+}
+
+/// Add the high-entropy client hints when the preset opts in (Chromium only).
+fn insert_high_entropy_hints(headers: &mut HeaderMap, preset: &Preset) {
+    let hints = match preset.client_hints {
+        Some(ref hints) if preset.high_entropy_hints => hints,
+        _ => return,
+    };
+
+    headers.insert(
+        "Sec-Ch-Ua-Arch",
+        HeaderValue::from_str(&format!("\"{}\"", hints.arch)).unwrap(),
+    );
+    headers.insert(
+        "Sec-Ch-Ua-Full-Version-List",
+        HeaderValue::from_str(&chrome_full_version_list(preset, hints.full_version)).unwrap(),
+    );
+    headers.insert(
+        "Sec-Ch-Ua-Model",
+        HeaderValue::from_str(&format!("\"{}\"", hints.model)).unwrap(),
+    );
+    headers.insert(
+        "Sec-Ch-Ua-Platform-Version",
+        HeaderValue::from_str(&format!("\"{}\"", hints.platform_version)).unwrap(),
+    );
+}
+
+/// Sec-Ch-Ua brand list with full versions; GREASE brands are padded to four components.
+fn chrome_full_version_list(preset: &Preset, full_version: &str) -> String {
+    chrome_sec_ch_ua(preset)
+        .split(", ")
+        .filter_map(|entry| {
+            let (brand, version) = entry.split_once(";v=")?;
+            let version = version.trim_matches('"');
+            let full = match brand.trim_matches('"') {
+                "Chromium" | "Google Chrome" | "Microsoft Edge" => full_version.to_string(),
+                _ => format!("{}.0.0.0", version),
+            };
+            Some(format!("{};v=\"{}\"", brand, full))
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Language preference list the browser reports for the preset's locale.
+///
+/// Safari only lists the UI language itself; Chromium and Firefox add English fallbacks.
+pub(super) fn language_preferences(preset: &Preset) -> Vec<String> {
+    let mut languages = preset.locale.preferences();
+    if is_safari(preset) {
+        languages.retain(|tag| *tag == preset.locale.tag || *tag == preset.locale.language);
+    }
+    languages
+}
+
+/// Accept-Language value with browser-specific q-value spacing.
+fn accept_language(preset: &Preset) -> HeaderValue {
+    let languages = language_preferences(preset);
+    let count = languages.len();
+    let value = languages
+        .iter()
+        .enumerate()
+        .map(|(idx, tag)| {
+            if idx == 0 {
+                return tag.clone();
+            }
+            // Firefox spreads q-values evenly over the list; Chromium and Safari step by 0.1.
+            // Firefox rounds half up (0.25 -> 0.3), unlike `{:.1}`.
+            let q = if is_firefox(preset) {
+                ((1.0 - idx as f64 / count as f64) * 10.0).round() / 10.0
+            } else {
+                (1.0 - idx as f64 / 10.0).max(0.1)
+            };
+            format!("{};q={:.1}", tag, q)
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    HeaderValue::from_str(&value).unwrap()
+}
+
+/// Headers a preset file recorded for a context, with Accept-Language following the locale.
+fn recorded_headers(preset: &Preset, context: &str) -> Option<HeaderMap> {
+    let recorded = preset.recorded_headers.as_ref()?.for_context(context);
+    if recorded.is_empty() {
+        return None;
+    }
+
+    let mut headers = HeaderMap::new();
+    for (name, value) in recorded {
+        let Ok(name) = HeaderName::from_bytes(name.as_bytes()) else {
+            continue;
+        };
+        if name == ACCEPT_LANGUAGE {
+            headers.append(name, accept_language(preset));
+        } else if let Ok(value) = HeaderValue::from_str(value) {
+            headers.append(name, value);
+        }
+    }
+    Some(headers)
+}
+
 /// Headers for GET /pd-lib.js (script loading context).
 pub fn script_headers(preset: &Preset) -> HeaderMap {
+    if let Some(headers) = recorded_headers(preset, "script") {
+        return headers;
+    }
     let mut headers = HeaderMap::new();
 
     if is_chrome(preset) {
@@ -34,21 +154,19 @@ pub fn script_headers(preset: &Preset) -> HeaderMap {
             "Accept-Encoding",
             HeaderValue::from_static("gzip, deflate, br, zstd"),
         );
-        headers.insert(
-            "Accept-Language",
-            HeaderValue::from_static("en-US,en;q=0.9"),
-        );
+        headers.insert("Accept-Language", accept_language(preset));
         headers.insert("Origin", HeaderValue::from_static(PAGE_ORIGIN));
         headers.insert("Referer", HeaderValue::from_static(PAGE_REFERER));
         headers.insert(
             "Sec-Ch-Ua",
-            HeaderValue::from_static(chrome_sec_ch_ua(preset)),
+            HeaderValue::from_str(chrome_sec_ch_ua(preset)).unwrap(),
         );
         headers.insert("Sec-Ch-Ua-Mobile", HeaderValue::from_static("?0"));
         headers.insert(
             "Sec-Ch-Ua-Platform",
-            HeaderValue::from_static("\"Windows\""),
+            HeaderValue::from_str(chrome_sec_ch_ua_platform(preset)).unwrap(),
         );
+        insert_high_entropy_hints(&mut headers, preset);
         headers.insert("Sec-Fetch-Dest", HeaderValue::from_static("script"));
         headers.insert("Sec-Fetch-Mode", HeaderValue::from_static("cors"));
         headers.insert("Sec-Fetch-Site", HeaderValue::from_static("same-site"));
@@ -62,10 +180,7 @@ pub fn script_headers(preset: &Preset) -> HeaderMap {
             "Accept-Encoding",
             HeaderValue::from_static("gzip, deflate, br, zstd"),
         );
-        headers.insert(
-            "Accept-Language",
-            HeaderValue::from_static("en-US,en;q=0.5"),
-        );
+        headers.insert("Accept-Language", accept_language(preset));
         headers.insert("Origin", HeaderValue::from_static(PAGE_ORIGIN));
         headers.insert("Referer", HeaderValue::from_static(PAGE_REFERER));
         headers.insert("Sec-Fetch-Dest", HeaderValue::from_static("script"));
@@ -81,10 +196,7 @@ pub fn script_headers(preset: &Preset) -> HeaderMap {
             "Accept-Encoding",
             HeaderValue::from_static("gzip, deflate, br"),
         );
-        headers.insert(
-            "Accept-Language",
-            HeaderValue::from_static("en-US,en;q=0.9"),
-        );
+        headers.insert("Accept-Language", accept_language(preset));
         headers.insert("Origin", HeaderValue::from_static(PAGE_ORIGIN));
         headers.insert("Referer", HeaderValue::from_static(PAGE_REFERER));
         headers.insert("Sec-Fetch-Dest", HeaderValue::from_static("script"));
@@ -101,6 +213,9 @@ pub fn script_headers(preset: &Preset) -> HeaderMap {
 
 /// Headers for GET /images/small.png (image probe context).
 pub fn image_headers(preset: &Preset) -> HeaderMap {
+    if let Some(headers) = recorded_headers(preset, "image") {
+        return headers;
+    }
     let mut headers = HeaderMap::new();
 
     if is_chrome(preset) {
@@ -114,20 +229,18 @@ pub fn image_headers(preset: &Preset) -> HeaderMap {
             "Accept-Encoding",
             HeaderValue::from_static("gzip, deflate, br, zstd"),
         );
-        headers.insert(
-            "Accept-Language",
-            HeaderValue::from_static("en-US,en;q=0.9"),
-        );
+        headers.insert("Accept-Language", accept_language(preset));
         headers.insert("Referer", HeaderValue::from_static(PAGE_REFERER));
         headers.insert(
             "Sec-Ch-Ua",
-            HeaderValue::from_static(chrome_sec_ch_ua(preset)),
+            HeaderValue::from_str(chrome_sec_ch_ua(preset)).unwrap(),
         );
         headers.insert("Sec-Ch-Ua-Mobile", HeaderValue::from_static("?0"));
         headers.insert(
             "Sec-Ch-Ua-Platform",
-            HeaderValue::from_static("\"Windows\""),
+            HeaderValue::from_str(chrome_sec_ch_ua_platform(preset)).unwrap(),
         );
+        insert_high_entropy_hints(&mut headers, preset);
         headers.insert("Sec-Fetch-Dest", HeaderValue::from_static("image"));
         headers.insert("Sec-Fetch-Mode", HeaderValue::from_static("no-cors"));
         headers.insert("Sec-Fetch-Site", HeaderValue::from_static("same-site"));
@@ -146,10 +259,7 @@ pub fn image_headers(preset: &Preset) -> HeaderMap {
             "Accept-Encoding",
             HeaderValue::from_static("gzip, deflate, br, zstd"),
         );
-        headers.insert(
-            "Accept-Language",
-            HeaderValue::from_static("en-US,en;q=0.5"),
-        );
+        headers.insert("Accept-Language", accept_language(preset));
         headers.insert("Referer", HeaderValue::from_static(PAGE_REFERER));
         headers.insert("Sec-Fetch-Dest", HeaderValue::from_static("image"));
         headers.insert("Sec-Fetch-Mode", HeaderValue::from_static("no-cors"));
@@ -164,10 +274,7 @@ pub fn image_headers(preset: &Preset) -> HeaderMap {
             "Accept-Encoding",
             HeaderValue::from_static("gzip, deflate, br"),
         );
-        headers.insert(
-            "Accept-Language",
-            HeaderValue::from_static("en-US,en;q=0.9"),
-        );
+        headers.insert("Accept-Language", accept_language(preset));
         headers.insert("Referer", HeaderValue::from_static(PAGE_REFERER));
         headers.insert("Sec-Fetch-Dest", HeaderValue::from_static("image"));
         headers.insert("Sec-Fetch-Mode", HeaderValue::from_static("no-cors"));
@@ -183,6 +290,9 @@ pub fn image_headers(preset: &Preset) -> HeaderMap {
 
 /// Headers for POST /s (sendBeacon/fetch telemetry submission).
 pub fn beacon_headers(preset: &Preset) -> HeaderMap {
+    if let Some(headers) = recorded_headers(preset, "beacon") {
+        return headers;
+    }
     let mut headers = HeaderMap::new();
 
     if is_chrome(preset) {
@@ -191,10 +301,7 @@ pub fn beacon_headers(preset: &Preset) -> HeaderMap {
             "Accept-Encoding",
             HeaderValue::from_static("gzip, deflate, br, zstd"),
         );
-        headers.insert(
-            "Accept-Language",
-            HeaderValue::from_static("en-US,en;q=0.9"),
-        );
+        headers.insert("Accept-Language", accept_language(preset));
         headers.insert(
             "Content-Type",
             HeaderValue::from_static("text/plain;charset=UTF-8"),
@@ -203,13 +310,14 @@ pub fn beacon_headers(preset: &Preset) -> HeaderMap {
         headers.insert("Referer", HeaderValue::from_static(PAGE_REFERER));
         headers.insert(
             "Sec-Ch-Ua",
-            HeaderValue::from_static(chrome_sec_ch_ua(preset)),
+            HeaderValue::from_str(chrome_sec_ch_ua(preset)).unwrap(),
         );
         headers.insert("Sec-Ch-Ua-Mobile", HeaderValue::from_static("?0"));
         headers.insert(
             "Sec-Ch-Ua-Platform",
-            HeaderValue::from_static("\"Windows\""),
+            HeaderValue::from_str(chrome_sec_ch_ua_platform(preset)).unwrap(),
         );
+        insert_high_entropy_hints(&mut headers, preset);
         headers.insert("Sec-Fetch-Dest", HeaderValue::from_static("empty"));
         headers.insert("Sec-Fetch-Mode", HeaderValue::from_static("no-cors"));
         headers.insert("Sec-Fetch-Site", HeaderValue::from_static("same-site"));
@@ -223,10 +331,7 @@ pub fn beacon_headers(preset: &Preset) -> HeaderMap {
             "Accept-Encoding",
             HeaderValue::from_static("gzip, deflate, br, zstd"),
         );
-        headers.insert(
-            "Accept-Language",
-            HeaderValue::from_static("en-US,en;q=0.5"),
-        );
+        headers.insert("Accept-Language", accept_language(preset));
         headers.insert(
             "Content-Type",
             HeaderValue::from_static("text/plain;charset=UTF-8"),
@@ -246,10 +351,7 @@ pub fn beacon_headers(preset: &Preset) -> HeaderMap {
             "Accept-Encoding",
             HeaderValue::from_static("gzip, deflate, br"),
         );
-        headers.insert(
-            "Accept-Language",
-            HeaderValue::from_static("en-US,en;q=0.9"),
-        );
+        headers.insert("Accept-Language", accept_language(preset));
         headers.insert(
             "Content-Type",
             HeaderValue::from_static("text/plain;charset=UTF-8"),
@@ -270,12 +372,15 @@ pub fn beacon_headers(preset: &Preset) -> HeaderMap {
 
 /// Headers for GET https://api.ipapi.is/ (ipapi.is frontend fetch context).
 pub fn ipapi_headers(preset: &Preset) -> HeaderMap {
+    if let Some(headers) = recorded_headers(preset, "ipapi") {
+        return headers;
+    }
     let mut headers = HeaderMap::new();
 
     if is_chrome(preset) {
         headers.insert(
             "Sec-Ch-Ua-Platform",
-            HeaderValue::from_static("\"Windows\""),
+            HeaderValue::from_str(chrome_sec_ch_ua_platform(preset)).unwrap(),
         );
         headers.insert(
             "User-Agent",
@@ -283,7 +388,7 @@ pub fn ipapi_headers(preset: &Preset) -> HeaderMap {
         );
         headers.insert(
             "Sec-Ch-Ua",
-            HeaderValue::from_static(chrome_sec_ch_ua(preset)),
+            HeaderValue::from_str(chrome_sec_ch_ua(preset)).unwrap(),
         );
         headers.insert("Sec-Ch-Ua-Mobile", HeaderValue::from_static("?0"));
         headers.insert("Accept", HeaderValue::from_static("*/*"));
@@ -292,12 +397,9 @@ pub fn ipapi_headers(preset: &Preset) -> HeaderMap {
         headers.insert("Sec-Fetch-Mode", HeaderValue::from_static("cors"));
         headers.insert("Sec-Fetch-Dest", HeaderValue::from_static("empty"));
         headers.insert("Referer", HeaderValue::from_static("https://ipapi.is/"));
-        headers.insert(
-            "Accept-Language",
-            HeaderValue::from_static("en-US,en;q=0.9"),
-        );
+        headers.insert("Accept-Language", accept_language(preset));
         headers.insert("Priority", HeaderValue::from_static("u=1, i"));
-    } else if is_firefox(preset) {
+    } else if is_firefox(preset) || is_safari(preset) {
         headers.insert(
             "User-Agent",
             HeaderValue::from_str(preset.user_agent).unwrap(),
@@ -308,25 +410,7 @@ pub fn ipapi_headers(preset: &Preset) -> HeaderMap {
         headers.insert("Sec-Fetch-Mode", HeaderValue::from_static("cors"));
         headers.insert("Sec-Fetch-Dest", HeaderValue::from_static("empty"));
         headers.insert("Referer", HeaderValue::from_static("https://ipapi.is/"));
-        headers.insert(
-            "Accept-Language",
-            HeaderValue::from_static("en-US,en;q=0.5"),
-        );
-    } else if is_safari(preset) {
-        headers.insert(
-            "User-Agent",
-            HeaderValue::from_str(preset.user_agent).unwrap(),
-        );
-        headers.insert("Accept", HeaderValue::from_static("*/*"));
-        headers.insert("Origin", HeaderValue::from_static("https://ipapi.is"));
-        headers.insert("Sec-Fetch-Site", HeaderValue::from_static("same-site"));
-        headers.insert("Sec-Fetch-Mode", HeaderValue::from_static("cors"));
-        headers.insert("Sec-Fetch-Dest", HeaderValue::from_static("empty"));
-        headers.insert("Referer", HeaderValue::from_static("https://ipapi.is/"));
-        headers.insert(
-            "Accept-Language",
-            HeaderValue::from_static("en-US,en;q=0.9"),
-        );
+        headers.insert("Accept-Language", accept_language(preset));
     }
 
     headers
@@ -334,6 +418,9 @@ pub fn ipapi_headers(preset: &Preset) -> HeaderMap {
 
 /// Headers for GET /i?&uuid= (polling context).
 pub fn poll_headers(preset: &Preset) -> HeaderMap {
+    if let Some(headers) = recorded_headers(preset, "poll") {
+        return headers;
+    }
     let mut headers = HeaderMap::new();
 
     if is_chrome(preset) {
@@ -342,23 +429,21 @@ pub fn poll_headers(preset: &Preset) -> HeaderMap {
             "Accept-Encoding",
             HeaderValue::from_static("gzip, deflate, br, zstd"),
         );
-        headers.insert(
-            "Accept-Language",
-            HeaderValue::from_static("en-US,en;q=0.9"),
-        );
+        headers.insert("Accept-Language", accept_language(preset));
         headers.insert("Cache-Control", HeaderValue::from_static("no-cache"));
         headers.insert("Origin", HeaderValue::from_static(PAGE_ORIGIN));
         headers.insert("Pragma", HeaderValue::from_static("no-cache"));
         headers.insert("Referer", HeaderValue::from_static(PAGE_REFERER));
         headers.insert(
             "Sec-Ch-Ua",
-            HeaderValue::from_static(chrome_sec_ch_ua(preset)),
+            HeaderValue::from_str(chrome_sec_ch_ua(preset)).unwrap(),
         );
         headers.insert("Sec-Ch-Ua-Mobile", HeaderValue::from_static("?0"));
         headers.insert(
             "Sec-Ch-Ua-Platform",
-            HeaderValue::from_static("\"Windows\""),
+            HeaderValue::from_str(chrome_sec_ch_ua_platform(preset)).unwrap(),
         );
+        insert_high_entropy_hints(&mut headers, preset);
         headers.insert("Sec-Fetch-Dest", HeaderValue::from_static("empty"));
         headers.insert("Sec-Fetch-Mode", HeaderValue::from_static("cors"));
         headers.insert("Sec-Fetch-Site", HeaderValue::from_static("same-site"));
@@ -372,10 +457,7 @@ pub fn poll_headers(preset: &Preset) -> HeaderMap {
             "Accept-Encoding",
             HeaderValue::from_static("gzip, deflate, br, zstd"),
         );
-        headers.insert(
-            "Accept-Language",
-            HeaderValue::from_static("en-US,en;q=0.5"),
-        );
+        headers.insert("Accept-Language", accept_language(preset));
         headers.insert("Cache-Control", HeaderValue::from_static("no-cache"));
         headers.insert("Origin", HeaderValue::from_static(PAGE_ORIGIN));
         headers.insert("Pragma", HeaderValue::from_static("no-cache"));
@@ -393,10 +475,7 @@ pub fn poll_headers(preset: &Preset) -> HeaderMap {
             "Accept-Encoding",
             HeaderValue::from_static("gzip, deflate, br"),
         );
-        headers.insert(
-            "Accept-Language",
-            HeaderValue::from_static("en-US,en;q=0.9"),
-        );
+        headers.insert("Accept-Language", accept_language(preset));
         headers.insert("Cache-Control", HeaderValue::from_static("no-cache"));
         headers.insert("Origin", HeaderValue::from_static(PAGE_ORIGIN));
         headers.insert("Pragma", HeaderValue::from_static("no-cache"));
@@ -412,3 +491,37 @@ pub fn poll_headers(preset: &Preset) -> HeaderMap {
 
     headers
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browser::preset::builtin_preset;
+    use crate::locale::Locale;
+
+    fn accept_language_for(name: &str, tag: &str) -> String {
+        let preset = Preset {
+            locale: Locale::parse(tag).unwrap(),
+            ..builtin_preset(name)
+        };
+        accept_language(&preset).to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn accept_language_q_values() {
+        assert_eq!(
+            accept_language_for("chrome-143", "de-DE"),
+            "de-DE,de;q=0.9,en-US;q=0.8,en;q=0.7"
+        );
+        assert_eq!(accept_language_for("chrome-143", "en-US"), "en-US,en;q=0.9");
+        assert_eq!(
+            accept_language_for("firefox-133", "de-DE"),
+            "de-DE,de;q=0.8,en-US;q=0.5,en;q=0.3"
+        );
+        assert_eq!(
+            accept_language_for("firefox-133", "en-US"),
+            "en-US,en;q=0.5"
+        );
+        assert_eq!(accept_language_for("safari-18", "de-DE"), "de-DE,de;q=0.9");
+        assert_eq!(accept_language_for("safari-18", "en-US"), "en-US,en;q=0.9");
+    }
+}
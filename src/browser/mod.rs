@@ -1,9 +1,19 @@
+mod check;
 mod fingerprint;
+mod har;
+mod hardware;
 mod headers;
 mod preset;
+mod preset_file;
 mod websocket;
 
-pub use fingerprint::compute_fingerprint;
+pub use check::{check_preset, preset_matches_os, presets_matching_os, Mismatch};
+pub use fingerprint::{
+    compute_fingerprint, fingerprint_keys, fingerprint_properties, fingerprint_string,
+};
+pub use har::{har_requests, import_har, HAR_CONTEXTS};
+pub use hardware::{sample_hardware, HardwareProfile};
 pub use headers::{beacon_headers, image_headers, ipapi_headers, poll_headers, script_headers};
-pub use preset::{get_preset, Preset};
-pub use websocket::{websocket_ping_pong, WsLatencyResult};
+pub use preset::{get_preset, register_preset, MachineCapabilities, Preset, PRESET_NAMES};
+pub use preset_file::load_preset_file;
+pub use websocket::{websocket_ping_pong, WsLatencyResult, WS_ROUNDS};
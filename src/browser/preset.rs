@@ -1,30 +1,216 @@
+use super::hardware::HardwareProfile;
+use super::preset_file::RecordedHeaders;
+use crate::locale::Locale;
+use std::sync::{Arc, RwLock};
 use wreq_util::Emulation;
 
 /// Browser identity preset for TLS fingerprinting and header generation.
 #[derive(Clone)]
 pub struct Preset {
     pub name: &'static str,
+    /// Built-in preset supplying navigator properties and header layout
+    /// (same as `name` for built-ins).
+    pub base: &'static str,
     pub user_agent: &'static str,
     pub emulation: Emulation,
+    pub locale: Locale,
+    /// Sampled hardware profile; `None` keeps the preset's built-in values.
+    pub hardware: Option<HardwareProfile>,
+    /// JS API availability reported in the telemetry `machine` block.
+    pub machine: MachineCapabilities,
+    /// High-entropy User-Agent client hint values (Chromium presets only).
+    pub client_hints: Option<ClientHints>,
+    /// Send the high-entropy `Sec-Ch-Ua-*` headers alongside the default hints.
+    pub high_entropy_hints: bool,
+    /// Fingerprint property overrides (`key`, `value`) applied on top of the preset values.
+    pub fingerprint_overrides: Vec<(String, String)>,
+    /// Per-context headers recorded from a real browser (preset files only).
+    pub recorded_headers: Option<Arc<RecordedHeaders>>,
+    /// Network fingerprints of the real browser, verified by `selftest`.
+    pub tls_fingerprints: ExpectedFingerprints,
+}
+
+/// Reference fingerprints; `None` means the value has not been pinned yet.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ExpectedFingerprints {
+    /// JA3 hash with sorted extensions (JA3N).
+    pub ja3n: Option<&'static str>,
+    pub ja4: Option<&'static str>,
+    /// Akamai HTTP/2 fingerprint.
+    pub akamai: Option<&'static str>,
+}
+
+/// Browser APIs a real instance of the preset exposes.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MachineCapabilities {
+    /// navigator.userAgentData
+    pub ua_identifiers: bool,
+    pub core: bool,
+    pub system: bool,
+    pub device: bool,
+    pub platform: bool,
+    pub speech_synthesis: bool,
+    pub device_memory: bool,
+    pub hardware_concurrency: bool,
+    /// navigator.gpu (WebGPU)
+    pub gpu: bool,
+}
+
+/// Values for `Sec-Ch-Ua-Full-Version-List`, `-Arch`, `-Platform-Version` and `-Model`.
+#[derive(Clone, Debug)]
+pub struct ClientHints {
+    pub full_version: &'static str,
+    pub platform_version: &'static str,
+    pub arch: &'static str,
+    pub model: &'static str,
+}
+
+/// Names of all built-in presets.
+pub const PRESET_NAMES: &[&str] = &["chrome-143", "chrome-143-linux", "firefox-133", "safari-18"];
+
+/// TLS/HTTP2 emulation profiles a preset file can select by name.
+pub const EMULATIONS: &[(&str, Emulation)] = &[
+    ("chrome-131", Emulation::Chrome131),
+    ("chrome-143", Emulation::Chrome143),
+    ("edge-134", Emulation::Edge134),
+    ("firefox-133", Emulation::Firefox133),
+    ("firefox-136", Emulation::Firefox136),
+    ("safari-18", Emulation::Safari18),
+    ("safari-18.5", Emulation::Safari18_5),
+];
+
+/// Presets loaded from files, looked up by name before the built-ins.
+static FILE_PRESETS: RwLock<Vec<Preset>> = RwLock::new(Vec::new());
+
+pub fn emulation_by_name(name: &str) -> Option<Emulation> {
+    EMULATIONS
+        .iter()
+        .find(|(known, _)| *known == name)
+        .map(|(_, emulation)| *emulation)
+}
+
+/// Make a file-loaded preset available to `get_preset`, replacing one of the same name.
+pub fn register_preset(preset: Preset) {
+    let mut presets = FILE_PRESETS.write().unwrap();
+    presets.retain(|existing| existing.name != preset.name);
+    presets.push(preset);
 }
 
 pub fn get_preset(name: &str) -> Preset {
+    if let Some(preset) = FILE_PRESETS
+        .read()
+        .unwrap()
+        .iter()
+        .find(|preset| preset.name == name)
+    {
+        return preset.clone();
+    }
+    builtin_preset(name)
+}
+
+pub(super) fn builtin_preset(name: &str) -> Preset {
     match name {
         "chrome-143" => Preset {
             name: "chrome-143",
+            base: "chrome-143",
             user_agent: "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/143.0.0.0 Safari/537.36",
             emulation: Emulation::Chrome143,
+            locale: Locale::default(),
+            hardware: None,
+            machine: MachineCapabilities {
+                ua_identifiers: true,
+                core: true,
+                system: true,
+                device: true,
+                platform: true,
+                speech_synthesis: true,
+                device_memory: true,
+                hardware_concurrency: true,
+                gpu: true,
+            },
+            client_hints: Some(ClientHints {
+                full_version: "143.0.7499.110",
+                platform_version: "19.0.0",
+                arch: "x86",
+                model: "",
+            }),
+            high_entropy_hints: false,
+            fingerprint_overrides: Vec::new(),
+            recorded_headers: None,
+            tls_fingerprints: ExpectedFingerprints {
+                ja3n: Some("8e19337e7524d2573be54efb2b0784c9"),
+                ja4: Some("t13d1516h2_8daaf6152771_02713d6af862"),
+                akamai: Some("1:65536;2:0;4:6291456;6:262144|15663105|0|m,a,s,p"),
+            },
+        },
+        "chrome-143-linux" => Preset {
+            name: "chrome-143-linux",
+            base: "chrome-143-linux",
+            user_agent: "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/143.0.0.0 Safari/537.36",
+            client_hints: Some(ClientHints {
+                full_version: "143.0.7499.110",
+                platform_version: "6.8.0",
+                arch: "x86",
+                model: "",
+            }),
+            ..builtin_preset("chrome-143")
         },
         "firefox-133" => Preset {
             name: "firefox-133",
+            base: "firefox-133",
             user_agent: "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:133.0) Gecko/20100101 Firefox/133.0",
             emulation: Emulation::Firefox133,
+            locale: Locale::default(),
+            hardware: None,
+            machine: MachineCapabilities {
+                ua_identifiers: false,
+                core: true,
+                system: true,
+                device: true,
+                platform: true,
+                speech_synthesis: true,
+                device_memory: false,
+                hardware_concurrency: true,
+                gpu: false,
+            },
+            client_hints: None,
+            high_entropy_hints: false,
+            fingerprint_overrides: Vec::new(),
+            recorded_headers: None,
+            tls_fingerprints: ExpectedFingerprints {
+                ja3n: Some("e4147a4860c1f347354f0a84d8787c02"),
+                ja4: Some("t13d1717h2_5b57614c22b0_3cbfd9057e0d"),
+                akamai: Some("1:65536;2:0;4:131072;5:16384|12517377|0|m,p,a,s"),
+            },
         },
         "safari-18" => Preset {
             name: "safari-18",
+            base: "safari-18",
             user_agent: "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/18.0 Safari/605.1.15",
             emulation: Emulation::Safari18,
+            locale: Locale::default(),
+            hardware: None,
+            machine: MachineCapabilities {
+                ua_identifiers: false,
+                core: true,
+                system: true,
+                device: true,
+                platform: true,
+                speech_synthesis: true,
+                device_memory: false,
+                hardware_concurrency: true,
+                gpu: false,
+            },
+            client_hints: None,
+            high_entropy_hints: false,
+            fingerprint_overrides: Vec::new(),
+            recorded_headers: None,
+            tls_fingerprints: ExpectedFingerprints {
+                ja3n: Some("773906b0efdefa24a7f2b8eb6985bf37"),
+                ja4: Some("t13d2014h2_a09f3c656075_14788d8d241b"),
+                akamai: Some("2:0;3:100;4:2097152;9:1|10420225|0|m,s,a,p"),
+            },
         },
-        _ => get_preset("chrome-143"),
+        _ => builtin_preset("chrome-143"),
     }
 }
@@ -0,0 +1,293 @@
+use super::preset::{
+    builtin_preset, emulation_by_name, ClientHints, ExpectedFingerprints, Preset, PRESET_NAMES,
+};
+use crate::locale::Locale;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use wreq::header::{HeaderName, HeaderValue};
+
+/// Preset stored as JSON, e.g. generated by `presets import-har`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PresetFile {
+    pub name: String,
+    /// Built-in preset supplying navigator properties and headers for unrecorded contexts.
+    pub base: String,
+    /// TLS/HTTP2 emulation profile (see `EMULATIONS`).
+    pub emulation: String,
+    pub user_agent: String,
+    #[serde(default)]
+    pub client_hints: Option<ClientHintsFile>,
+    #[serde(default)]
+    pub headers: RecordedHeaders,
+    #[serde(default)]
+    pub tls_fingerprints: Option<FingerprintsFile>,
+}
+
+/// Expected JA3N, JA4 and Akamai HTTP/2 fingerprints.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FingerprintsFile {
+    #[serde(default)]
+    pub ja3n: Option<String>,
+    #[serde(default)]
+    pub ja4: Option<String>,
+    #[serde(default)]
+    pub akamai: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClientHintsFile {
+    pub full_version: String,
+    pub platform_version: String,
+    pub arch: String,
+    pub model: String,
+}
+
+/// Headers per request context in browser order; an empty list keeps the built-in headers.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RecordedHeaders {
+    #[serde(default)]
+    pub script: Vec<(String, String)>,
+    #[serde(default)]
+    pub image: Vec<(String, String)>,
+    #[serde(default)]
+    pub beacon: Vec<(String, String)>,
+    #[serde(default)]
+    pub poll: Vec<(String, String)>,
+    #[serde(default)]
+    pub ipapi: Vec<(String, String)>,
+}
+
+impl RecordedHeaders {
+    pub fn for_context(&self, context: &str) -> &[(String, String)] {
+        match context {
+            "script" => &self.script,
+            "image" => &self.image,
+            "beacon" => &self.beacon,
+            "poll" => &self.poll,
+            "ipapi" => &self.ipapi,
+            _ => &[],
+        }
+    }
+
+    /// First recorded value of a header in any context, e.g. `Sec-Ch-Ua`.
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.contexts()
+            .into_iter()
+            .flatten()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn contexts(&self) -> [&[(String, String)]; 5] {
+        [
+            &self.script,
+            &self.image,
+            &self.beacon,
+            &self.poll,
+            &self.ipapi,
+        ]
+    }
+}
+
+impl PresetFile {
+    pub fn into_preset(self) -> Result<Preset, Box<dyn std::error::Error + Send + Sync>> {
+        if !PRESET_NAMES.contains(&self.base.as_str()) {
+            return Err(format!(
+                "unknown base preset '{}' (expected one of {})",
+                self.base,
+                PRESET_NAMES.join(", ")
+            )
+            .into());
+        }
+        let emulation = emulation_by_name(&self.emulation)
+            .ok_or_else(|| format!("unknown emulation '{}'", self.emulation))?;
+        let base = builtin_preset(&self.base);
+        self.validate(&base)?;
+
+        let client_hints = match self.client_hints {
+            Some(hints) if base.client_hints.is_some() => Some(ClientHints {
+                full_version: leak(hints.full_version),
+                platform_version: leak(hints.platform_version),
+                arch: leak(hints.arch),
+                model: leak(hints.model),
+            }),
+            // The base's hint values only describe the base's own browser version.
+            _ if self.user_agent == base.user_agent => base.client_hints,
+            _ => None,
+        };
+
+        let tls_fingerprints = match self.tls_fingerprints {
+            Some(fingerprints) => ExpectedFingerprints {
+                ja3n: fingerprints.ja3n.map(leak),
+                ja4: fingerprints.ja4.map(leak),
+                akamai: fingerprints.akamai.map(leak),
+            },
+            // Same emulation as the base: the base's pinned values still apply.
+            None if self.emulation == self.base => base.tls_fingerprints,
+            None => ExpectedFingerprints::default(),
+        };
+
+        Ok(Preset {
+            name: leak(self.name),
+            base: base.name,
+            user_agent: leak(self.user_agent),
+            emulation,
+            locale: Locale::default(),
+            hardware: None,
+            machine: base.machine,
+            client_hints,
+            high_entropy_hints: false,
+            fingerprint_overrides: Vec::new(),
+            recorded_headers: Some(Arc::new(self.headers)),
+            tls_fingerprints,
+        })
+    }
+
+    /// Reject values no request can carry, and a Chromium file whose built-in
+    /// headers for unrecorded contexts would contradict its User-Agent.
+    fn validate(&self, base: &Preset) -> Result<(), String> {
+        let invalid = |value: &str| HeaderValue::from_str(value).is_err();
+        if invalid(&self.user_agent) {
+            return Err(format!("invalid user_agent {:?}", self.user_agent));
+        }
+        if let Some(ref hints) = self.client_hints {
+            for (field, value) in [
+                ("full_version", &hints.full_version),
+                ("platform_version", &hints.platform_version),
+                ("arch", &hints.arch),
+                ("model", &hints.model),
+            ] {
+                if invalid(value) {
+                    return Err(format!("invalid client_hints.{} {:?}", field, value));
+                }
+            }
+        }
+        for (name, value) in self.headers.contexts().into_iter().flatten() {
+            if HeaderName::from_bytes(name.as_bytes()).is_err() || invalid(value) {
+                return Err(format!("invalid recorded header {:?}: {:?}", name, value));
+            }
+        }
+
+        let unrecorded = self
+            .headers
+            .contexts()
+            .iter()
+            .any(|headers| headers.is_empty());
+        if base.client_hints.is_some() && unrecorded && self.user_agent != base.user_agent {
+            for hint in ["Sec-Ch-Ua", "Sec-Ch-Ua-Platform"] {
+                if self.headers.value(hint).is_none() {
+                    return Err(format!(
+                        "{} not recorded, so headers of unrecorded contexts would not match the User-Agent",
+                        hint
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Load a preset file.
+pub fn load_preset_file(path: &str) -> Result<Preset, Box<dyn std::error::Error + Send + Sync>> {
+    let content =
+        std::fs::read_to_string(path).map_err(|err| format!("reading {}: {}", path, err))?;
+    let file: PresetFile =
+        serde_json::from_str(&content).map_err(|err| format!("parsing {}: {}", path, err))?;
+    file.into_preset()
+}
+
+/// Preset files are loaded once per run, so their strings can live for the whole process.
+fn leak(value: String) -> &'static str {
+    Box::leak(value.into_boxed_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browser::headers::image_headers;
+
+    const MAC_CHROME: &str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/138.0.0.0 Safari/537.36";
+    const SEC_CH_UA: &str = r#""Not)A;Brand";v="8", "Chromium";v="138", "Google Chrome";v="138""#;
+
+    fn file(base: &str, emulation: &str, user_agent: &str) -> PresetFile {
+        PresetFile {
+            name: "test".to_string(),
+            base: base.to_string(),
+            emulation: emulation.to_string(),
+            user_agent: user_agent.to_string(),
+            client_hints: None,
+            headers: RecordedHeaders::default(),
+            tls_fingerprints: None,
+        }
+    }
+
+    fn error(file: PresetFile) -> String {
+        file.into_preset()
+            .err()
+            .expect("preset file should be rejected")
+            .to_string()
+    }
+
+    fn header(name: &str, value: &str) -> (String, String) {
+        (name.to_string(), value.to_string())
+    }
+
+    #[test]
+    fn rejects_unknown_base_and_emulation() {
+        assert!(error(file("chrome-999", "chrome-143", MAC_CHROME)).contains("chrome-999"));
+        assert!(error(file("chrome-143", "chrome-999", MAC_CHROME)).contains("chrome-999"));
+    }
+
+    #[test]
+    fn rejects_control_characters() {
+        let firefox = builtin_preset("firefox-133").user_agent;
+        assert!(
+            error(file("firefox-133", "firefox-133", "Mozilla/5.0\r\nX: 1")).contains("user_agent")
+        );
+
+        let mut hints = file(
+            "chrome-143",
+            "chrome-143",
+            builtin_preset("chrome-143").user_agent,
+        );
+        hints.client_hints = Some(ClientHintsFile {
+            full_version: "143.0.7499.110".to_string(),
+            platform_version: "19.0.0".to_string(),
+            arch: "x86".to_string(),
+            model: "Pixel\u{7}".to_string(),
+        });
+        assert!(error(hints).contains("client_hints.model"));
+
+        let mut recorded = file("firefox-133", "firefox-133", firefox);
+        recorded.headers.poll = vec![header("Accept", "*/*\n")];
+        assert!(error(recorded).contains("Accept"));
+    }
+
+    #[test]
+    fn chromium_file_with_unrecorded_contexts_needs_recorded_client_hints() {
+        // Built-in headers would send Windows Chrome 143 hints next to this UA.
+        let mut partial = file("chrome-143", "chrome-131", MAC_CHROME);
+        partial.headers.script = vec![header("sec-ch-ua", SEC_CH_UA)];
+        assert!(error(partial).contains("Sec-Ch-Ua-Platform"));
+
+        let mut partial = file("chrome-143", "chrome-131", MAC_CHROME);
+        partial.headers.script = vec![
+            header("sec-ch-ua", SEC_CH_UA),
+            header("sec-ch-ua-platform", "\"macOS\""),
+            header("user-agent", MAC_CHROME),
+        ];
+        let preset = partial.into_preset().unwrap();
+        let headers = image_headers(&preset);
+        assert_eq!(headers["sec-ch-ua"], SEC_CH_UA);
+        assert_eq!(headers["sec-ch-ua-platform"], "\"macOS\"");
+        assert_eq!(headers["user-agent"], MAC_CHROME);
+
+        // The base's own User-Agent matches the built-in hints.
+        let same = file(
+            "chrome-143",
+            "chrome-143",
+            builtin_preset("chrome-143").user_agent,
+        );
+        assert!(same.into_preset().is_ok());
+    }
+}
@@ -0,0 +1,327 @@
+use super::proxy::LocalProxy;
+use crate::browser::Preset;
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tokio::net::{TcpListener, TcpStream};
+
+const EXT_SERVER_NAME: u16 = 0x0000;
+const EXT_SUPPORTED_GROUPS: u16 = 0x000a;
+const EXT_EC_POINT_FORMATS: u16 = 0x000b;
+const EXT_SIGNATURE_ALGORITHMS: u16 = 0x000d;
+const EXT_ALPN: u16 = 0x0010;
+const EXT_SUPPORTED_VERSIONS: u16 = 0x002b;
+
+/// Fields of a TLS ClientHello that feed JA3 and JA4, in the order sent.
+#[derive(Clone, Debug, Default)]
+pub struct ClientHello {
+    pub version: u16,
+    pub ciphers: Vec<u16>,
+    pub extensions: Vec<u16>,
+    pub groups: Vec<u16>,
+    pub point_formats: Vec<u8>,
+    pub signature_algorithms: Vec<u16>,
+    pub alpn: Vec<String>,
+    pub supported_versions: Vec<u16>,
+    pub server_name: Option<String>,
+}
+
+/// Connect to a local listener with the preset's emulation, through `proxy`, and
+/// record the ClientHello.
+///
+/// The listener never answers the handshake, so the client's request fails once
+/// the hello has been read.
+pub async fn capture_client_hello(
+    preset: &Preset,
+    proxy: &LocalProxy,
+    wait: Duration,
+) -> Result<ClientHello, Box<dyn std::error::Error + Send + Sync>> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    // A hostname, not an IP literal, so the client sends SNI like a browser would.
+    let url = format!("https://localhost:{}/", listener.local_addr()?.port());
+    let client = proxy
+        .apply(wreq::Client::builder().emulation(preset.emulation))?
+        .build()?;
+    let request = tokio::spawn(async move {
+        let _ = client.get(&url).send().await;
+    });
+
+    let hello = async {
+        let (mut stream, _) = listener.accept().await?;
+        read_client_hello(&mut stream).await
+    };
+    let result = tokio::time::timeout(wait, hello)
+        .await
+        .map_err(|_| "no TLS connection received")?;
+    request.abort();
+
+    ClientHello::parse(&result?).ok_or_else(|| "malformed ClientHello".into())
+}
+
+/// Body of the first handshake message, reassembled across TLS records.
+async fn read_client_hello(
+    stream: &mut TcpStream,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut buf = Vec::new();
+    let mut handshake = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    loop {
+        while buf.len() >= 5 {
+            if buf[0] != 0x16 {
+                return Err("client did not start a TLS handshake".into());
+            }
+            let record_len = u16::from_be_bytes([buf[3], buf[4]]) as usize;
+            if buf.len() < 5 + record_len {
+                break;
+            }
+            handshake.extend_from_slice(&buf[5..5 + record_len]);
+            buf.drain(..5 + record_len);
+        }
+
+        if handshake.len() >= 4 {
+            if handshake[0] != 0x01 {
+                return Err("first handshake message is not a ClientHello".into());
+            }
+            let hello_len = u32::from_be_bytes([0, handshake[1], handshake[2], handshake[3]]);
+            let hello_end = 4 + hello_len as usize;
+            if handshake.len() >= hello_end {
+                return Ok(handshake[4..hello_end].to_vec());
+            }
+        }
+
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Err("connection closed before the ClientHello completed".into());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+}
+
+/// Big-endian reader over a handshake message.
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.data.len() < len {
+            return None;
+        }
+        let (head, rest) = self.data.split_at(len);
+        self.data = rest;
+        Some(head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.bytes(1).map(|b| b[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.bytes(2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    /// A length-prefixed vector with a 1- or 2-byte length.
+    fn vector(&mut self, len_bytes: usize) -> Option<&'a [u8]> {
+        let len = match len_bytes {
+            1 => self.u8()? as usize,
+            _ => self.u16()? as usize,
+        };
+        self.bytes(len)
+    }
+}
+
+fn u16_list(data: &[u8]) -> Vec<u16> {
+    data.chunks_exact(2)
+        .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+        .collect()
+}
+
+/// GREASE values (RFC 8701) are random placeholders and excluded from fingerprints.
+fn is_grease(value: u16) -> bool {
+    value & 0x0f0f == 0x0a0a && value >> 8 == value & 0xff
+}
+
+fn hex_list(values: &[u16]) -> String {
+    values
+        .iter()
+        .map(|value| format!("{:04x}", value))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// First 12 hex characters of the SHA-256 digest, as JA4 truncates them.
+fn sha256_prefix(input: &str) -> String {
+    Sha256::digest(input.as_bytes())
+        .iter()
+        .take(6)
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+impl ClientHello {
+    /// Parse a ClientHello handshake body (without the 4-byte handshake header).
+    pub fn parse(body: &[u8]) -> Option<ClientHello> {
+        let mut reader = Reader { data: body };
+        let mut hello = ClientHello {
+            version: reader.u16()?,
+            ..ClientHello::default()
+        };
+        reader.bytes(32)?; // random
+        reader.vector(1)?; // legacy session id
+        hello.ciphers = u16_list(reader.vector(2)?);
+        reader.vector(1)?; // compression methods
+
+        let mut extensions = Reader {
+            data: reader.vector(2).unwrap_or_default(),
+        };
+        while let (Some(kind), Some(data)) = (extensions.u16(), extensions.vector(2)) {
+            hello.extensions.push(kind);
+            let mut ext = Reader { data };
+            match kind {
+                EXT_SERVER_NAME => {
+                    let mut list = Reader {
+                        data: ext.vector(2)?,
+                    };
+                    if list.u8()? == 0 {
+                        hello.server_name =
+                            Some(String::from_utf8_lossy(list.vector(2)?).into_owned());
+                    }
+                }
+                EXT_SUPPORTED_GROUPS => hello.groups = u16_list(ext.vector(2)?),
+                EXT_EC_POINT_FORMATS => hello.point_formats = ext.vector(1)?.to_vec(),
+                EXT_SIGNATURE_ALGORITHMS => hello.signature_algorithms = u16_list(ext.vector(2)?),
+                EXT_ALPN => {
+                    let mut list = Reader {
+                        data: ext.vector(2)?,
+                    };
+                    while let Some(protocol) = list.vector(1) {
+                        hello
+                            .alpn
+                            .push(String::from_utf8_lossy(protocol).into_owned());
+                    }
+                }
+                EXT_SUPPORTED_VERSIONS => hello.supported_versions = u16_list(ext.vector(1)?),
+                _ => {}
+            }
+        }
+
+        Some(hello)
+    }
+
+    /// JA3 string; `sorted` orders the extensions (JA3N), since Chromium
+    /// randomizes extension order on every connection.
+    pub fn ja3_string(&self, sorted: bool) -> String {
+        let join = |values: Vec<u16>| {
+            values
+                .iter()
+                .map(u16::to_string)
+                .collect::<Vec<_>>()
+                .join("-")
+        };
+        let non_grease = |values: &[u16]| -> Vec<u16> {
+            values.iter().copied().filter(|v| !is_grease(*v)).collect()
+        };
+
+        let mut extensions = non_grease(&self.extensions);
+        if sorted {
+            extensions.sort_unstable();
+        }
+        let point_formats = self
+            .point_formats
+            .iter()
+            .map(u8::to_string)
+            .collect::<Vec<_>>()
+            .join("-");
+
+        format!(
+            "{},{},{},{},{}",
+            self.version,
+            join(non_grease(&self.ciphers)),
+            join(extensions),
+            join(non_grease(&self.groups)),
+            point_formats
+        )
+    }
+
+    /// MD5 of the JA3 string.
+    pub fn ja3_hash(&self, sorted: bool) -> String {
+        format!("{:x}", md5::compute(self.ja3_string(sorted)))
+    }
+
+    /// JA4 TLS client fingerprint (`a_b_c`).
+    pub fn ja4(&self) -> String {
+        let ciphers: Vec<u16> = self
+            .ciphers
+            .iter()
+            .copied()
+            .filter(|v| !is_grease(*v))
+            .collect();
+        let extensions: Vec<u16> = self
+            .extensions
+            .iter()
+            .copied()
+            .filter(|v| !is_grease(*v))
+            .collect();
+
+        let version = self
+            .supported_versions
+            .iter()
+            .copied()
+            .filter(|v| !is_grease(*v))
+            .max()
+            .unwrap_or(self.version);
+        let version = match version {
+            0x0304 => "13",
+            0x0303 => "12",
+            0x0302 => "11",
+            0x0301 => "10",
+            0x0300 => "s3",
+            _ => "00",
+        };
+        let sni = if self.server_name.is_some() { 'd' } else { 'i' };
+        let alpn = match self.alpn.first() {
+            Some(protocol) if !protocol.is_empty() => {
+                let first = protocol.chars().next().unwrap_or('0');
+                let last = protocol.chars().last().unwrap_or('0');
+                format!("{}{}", first, last)
+            }
+            _ => "00".to_string(),
+        };
+        let part_a = format!(
+            "t{}{}{:02}{:02}{}",
+            version,
+            sni,
+            ciphers.len().min(99),
+            extensions.len().min(99),
+            alpn
+        );
+
+        let mut sorted_ciphers = ciphers.clone();
+        sorted_ciphers.sort_unstable();
+        let part_b = if sorted_ciphers.is_empty() {
+            "000000000000".to_string()
+        } else {
+            sha256_prefix(&hex_list(&sorted_ciphers))
+        };
+
+        let mut sorted_extensions: Vec<u16> = extensions
+            .into_iter()
+            .filter(|ext| *ext != EXT_SERVER_NAME && *ext != EXT_ALPN)
+            .collect();
+        sorted_extensions.sort_unstable();
+        let part_c = if sorted_extensions.is_empty() {
+            "000000000000".to_string()
+        } else if self.signature_algorithms.is_empty() {
+            sha256_prefix(&hex_list(&sorted_extensions))
+        } else {
+            sha256_prefix(&format!(
+                "{}_{}",
+                hex_list(&sorted_extensions),
+                hex_list(&self.signature_algorithms)
+            ))
+        };
+
+        format!("{}_{}_{}", part_a, part_b, part_c)
+    }
+}
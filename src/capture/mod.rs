@@ -0,0 +1,390 @@
+mod client_hello;
+mod proxy;
+mod reference;
+mod server;
+
+use crate::browser::{
+    beacon_headers, har_requests, image_headers, ipapi_headers, poll_headers, script_headers,
+    Preset,
+};
+use std::path::Path;
+use std::time::Duration;
+use wreq::header::HeaderMap;
+
+pub use proxy::Route;
+pub use server::{CaptureServer, CapturedRequest, Protocol};
+
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(5);
+
+type HeaderBuilder = fn(&Preset) -> HeaderMap;
+
+/// Request contexts the scanner sends, with their header builders.
+/// Beacons are the only POSTs.
+const CONTEXTS: &[(&str, HeaderBuilder, bool)] = &[
+    ("script", script_headers, false),
+    ("image", image_headers, false),
+    ("beacon", beacon_headers, true),
+    ("poll", poll_headers, false),
+    ("ipapi", ipapi_headers, false),
+];
+
+/// Outcome of comparing one captured context against its reference.
+pub enum WireOutcome {
+    Match,
+    /// `diff_lines` output against the reference.
+    Differs(Vec<String>),
+    NoReference,
+    /// Browser capture written as the new reference.
+    Recorded,
+    Failed(String),
+}
+
+pub struct WireReport {
+    pub context: &'static str,
+    pub protocol: Protocol,
+    pub outcome: WireOutcome,
+}
+
+/// Send every request context of a preset over HTTP/1.1 and HTTP/2 to a local
+/// capture server and diff the on-the-wire headers against `references/<preset>/`.
+pub async fn check_wire_headers(
+    preset: &Preset,
+    references: &Path,
+) -> Result<Vec<WireReport>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut server = CaptureServer::start().await?;
+    let mut reports = Vec::new();
+
+    for protocol in [Protocol::Http1, Protocol::Http2] {
+        let builder = wreq::Client::builder().emulation(preset.emulation);
+        let client = match protocol {
+            Protocol::Http1 => builder.http1_only(),
+            Protocol::Http2 => builder.http2_only(),
+        }
+        .build()?;
+
+        for &(context, headers, is_post) in CONTEXTS {
+            let path = format!("/{}", context);
+            let url = server.url(&path);
+            let request = if is_post {
+                client.post(&url).headers(headers(preset)).body("{}")
+            } else {
+                client.get(&url).headers(headers(preset))
+            };
+            let sent = request.send().await;
+
+            let outcome = match server.next_capture(&path, CAPTURE_TIMEOUT).await {
+                Some(capture) => compare(preset, references, context, &capture),
+                None => WireOutcome::Failed(match sent {
+                    Err(err) => format!("request failed: {}", err),
+                    Ok(_) => "no request captured".to_string(),
+                }),
+            };
+            reports.push(WireReport {
+                context,
+                protocol,
+                outcome,
+            });
+        }
+    }
+
+    Ok(reports)
+}
+
+fn compare(
+    preset: &Preset,
+    references: &Path,
+    context: &str,
+    capture: &CapturedRequest,
+) -> WireOutcome {
+    let path = reference::reference_path(references, preset.name, context, capture.protocol);
+    let actual = reference::normalized_lines(&capture.headers);
+
+    match reference::load_reference(&path) {
+        None => WireOutcome::NoReference,
+        Some(expected) => {
+            let diff = reference::diff_lines(&expected, &actual);
+            if diff.is_empty() {
+                WireOutcome::Match
+            } else {
+                WireOutcome::Differs(diff)
+            }
+        }
+    }
+}
+
+/// Write the references of a preset from a HAR recorded in a real browser.
+///
+/// The first request of each context and protocol wins; HTTP/3 requests are skipped.
+/// References are never taken from this tool's own requests.
+pub fn record_references(
+    preset: &str,
+    har: &str,
+    references: &Path,
+) -> Result<Vec<WireReport>, Box<dyn std::error::Error + Send + Sync>> {
+    let source = Path::new(har)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| har.to_string());
+    let mut reports: Vec<WireReport> = Vec::new();
+
+    for request in har_requests(har)? {
+        let Some(protocol) = Protocol::from_har_version(&request.http_version) else {
+            continue;
+        };
+        if reports
+            .iter()
+            .any(|report| report.context == request.context && report.protocol == protocol)
+        {
+            continue;
+        }
+
+        let path = reference::reference_path(references, preset, request.context, protocol);
+        let user_agent = request
+            .headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("user-agent"))
+            .map(|(_, value)| value.as_str())
+            .unwrap_or("unknown User-Agent");
+        let comment = format!("# {} from {} ({})", request.context, source, user_agent);
+        let lines = reference::normalized_lines(&request.headers);
+        let outcome = match reference::save_reference(&path, &comment, &lines) {
+            Ok(()) => WireOutcome::Recorded,
+            Err(err) => WireOutcome::Failed(format!("writing {}: {}", path.display(), err)),
+        };
+        reports.push(WireReport {
+            context: request.context,
+            protocol,
+            outcome,
+        });
+    }
+
+    if reports.is_empty() {
+        return Err(format!(
+            "{} has no HTTP/1.1 or HTTP/2 requests to proxydetect.live or ipapi.is",
+            har
+        )
+        .into());
+    }
+    Ok(reports)
+}
+
+/// A fingerprint computed from local captures and the value the preset expects.
+pub struct FingerprintCheck {
+    pub label: &'static str,
+    pub route: Route,
+    pub actual: Result<String, String>,
+    pub expected: Option<&'static str>,
+    /// Extra context, e.g. the raw JA3 string.
+    pub detail: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    Mismatch,
+    /// Captured, but the preset pins no value to verify it against.
+    Unpinned,
+    Error,
+}
+
+impl FingerprintCheck {
+    pub fn status(&self) -> CheckStatus {
+        match (&self.actual, self.expected) {
+            (Err(_), _) => CheckStatus::Error,
+            (Ok(_), None) => CheckStatus::Unpinned,
+            (Ok(actual), Some(expected)) if actual == expected => CheckStatus::Pass,
+            (Ok(_), Some(_)) => CheckStatus::Mismatch,
+        }
+    }
+}
+
+/// Capture the preset's TLS ClientHello and HTTP/2 connection preamble locally and
+/// compare JA3N, JA4 and the Akamai HTTP/2 fingerprint with the pinned values.
+///
+/// Every capture is taken directly and again through a local HTTP CONNECT and SOCKS5
+/// proxy, so a proxy type that alters the fingerprint shows up as a mismatch. HTTP/2 is
+/// captured over TLS with ALPN, like a browser negotiates it.
+pub async fn selftest(preset: &Preset) -> Vec<FingerprintCheck> {
+    let expected = preset.tls_fingerprints;
+    let mut checks = Vec::new();
+
+    for route in Route::ALL {
+        let check = |label, actual, expected, detail| FingerprintCheck {
+            label,
+            route,
+            actual,
+            expected,
+            detail,
+        };
+        let proxy = match proxy::LocalProxy::start(route).await {
+            Ok(proxy) => proxy,
+            Err(err) => {
+                let err = format!("starting local {}: {}", route.label(), err);
+                for (label, expected) in [
+                    ("JA3N", expected.ja3n),
+                    ("JA4", expected.ja4),
+                    ("Akamai HTTP/2", expected.akamai),
+                ] {
+                    checks.push(check(label, Err(err.clone()), expected, None));
+                }
+                continue;
+            }
+        };
+
+        match client_hello::capture_client_hello(preset, &proxy, CAPTURE_TIMEOUT).await {
+            Ok(hello) => {
+                let detail = format!(
+                    "ja3 {} ({})",
+                    hello.ja3_hash(false),
+                    hello.ja3_string(false)
+                );
+                checks.push(check(
+                    "JA3N",
+                    Ok(hello.ja3_hash(true)),
+                    expected.ja3n,
+                    Some(detail),
+                ));
+                checks.push(check("JA4", Ok(hello.ja4()), expected.ja4, None));
+            }
+            Err(err) => {
+                for (label, expected) in [("JA3N", expected.ja3n), ("JA4", expected.ja4)] {
+                    checks.push(check(label, Err(err.to_string()), expected, None));
+                }
+            }
+        }
+
+        checks.push(check(
+            "Akamai HTTP/2",
+            capture_akamai(preset, &proxy)
+                .await
+                .map_err(|err| err.to_string()),
+            expected.akamai,
+            None,
+        ));
+    }
+
+    checks
+}
+
+async fn capture_akamai(
+    preset: &Preset,
+    proxy: &proxy::LocalProxy,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let mut server = CaptureServer::start_tls().await?;
+    // No http2_only: the protocol comes from ALPN, as in a browser.
+    let client = proxy
+        .apply(
+            wreq::Client::builder()
+                .emulation(preset.emulation)
+                .cert_verification(false),
+        )?
+        .build()?;
+    let path = "/selftest";
+    let sent = client
+        .get(server.url(path))
+        .headers(script_headers(preset))
+        .send()
+        .await;
+
+    let capture = match server.next_capture(path, CAPTURE_TIMEOUT).await {
+        Some(capture) => capture,
+        None => {
+            return Err(match sent {
+                Err(err) => format!("HTTPS request failed: {}", err),
+                Ok(_) => "no HTTPS request captured".to_string(),
+            }
+            .into())
+        }
+    };
+    match capture.http2 {
+        Some(preamble) => Ok(preamble.akamai_fingerprint(&capture.headers)),
+        None => Err("ALPN negotiated HTTP/1.1 instead of h2".into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browser::{get_preset, PRESET_NAMES};
+
+    #[test]
+    fn unpinned_values_do_not_pass() {
+        let check = |actual: Result<&str, &str>, expected| FingerprintCheck {
+            label: "JA4",
+            route: Route::Direct,
+            actual: actual.map(str::to_string).map_err(str::to_string),
+            expected,
+            detail: None,
+        };
+        assert_eq!(check(Ok("a"), Some("a")).status(), CheckStatus::Pass);
+        assert_eq!(check(Ok("a"), Some("b")).status(), CheckStatus::Mismatch);
+        assert_eq!(check(Ok("a"), None).status(), CheckStatus::Unpinned);
+        assert_eq!(
+            check(Err("timeout"), Some("a")).status(),
+            CheckStatus::Error
+        );
+    }
+
+    #[tokio::test]
+    async fn tls_capture_server_loads_its_certificate() {
+        let server = CaptureServer::start_tls().await.unwrap();
+        assert!(server.url("/x").starts_with("https://localhost:"));
+    }
+
+    #[test]
+    fn builtin_presets_pin_every_fingerprint() {
+        for name in PRESET_NAMES {
+            let expected = get_preset(name).tls_fingerprints;
+            assert!(expected.ja3n.is_some(), "{} JA3N", name);
+            assert!(expected.ja4.is_some(), "{} JA4", name);
+            assert!(expected.akamai.is_some(), "{} Akamai", name);
+        }
+    }
+
+    #[test]
+    fn records_references_from_a_har() {
+        let dir = std::env::temp_dir().join(format!("wire-record-{}", std::process::id()));
+        let har = dir.join("chrome.har");
+        std::fs::create_dir_all(&dir).unwrap();
+        let entry = |url: &str, version: &str, referer: &str| {
+            serde_json::json!({"request": {
+                "method": "GET",
+                "url": url,
+                "httpVersion": version,
+                "headers": [
+                    {"name": ":method", "value": "GET"},
+                    {"name": ":path", "value": "/pd-lib.js"},
+                    {"name": "user-agent", "value": "Mozilla/5.0 Test"},
+                    {"name": "referer", "value": referer},
+                ],
+            }})
+        };
+        let content = serde_json::json!({"log": {"entries": [
+            entry("https://proxydetect.live/pd-lib.js", "h2", "https://proxydetect.live/"),
+            entry("https://proxydetect.live/pd-lib.js", "h2", "https://other.example/"),
+            entry("https://proxydetect.live/pd-lib.js", "h3", "https://proxydetect.live/"),
+            entry("https://example.com/pd-lib.js", "HTTP/1.1", "https://proxydetect.live/"),
+        ]}});
+        std::fs::write(&har, content.to_string()).unwrap();
+
+        let reports = record_references("chrome-143", har.to_str().unwrap(), &dir).unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].context, "script");
+        assert_eq!(reports[0].protocol, Protocol::Http2);
+
+        let path = reference::reference_path(&dir, "chrome-143", "script", Protocol::Http2);
+        assert_eq!(
+            reference::load_reference(&path).unwrap(),
+            [
+                ":method: GET",
+                ":path: *",
+                "user-agent: Mozilla/5.0 Test",
+                "referer: *"
+            ]
+        );
+        let empty = serde_json::json!({"log": {"entries": []}});
+        std::fs::write(&har, empty.to_string()).unwrap();
+        assert!(record_references("chrome-143", har.to_str().unwrap(), &dir).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
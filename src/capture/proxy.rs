@@ -0,0 +1,175 @@
+use crate::meter::Relay;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// How a self-test client reaches the local capture listener.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Route {
+    Direct,
+    HttpProxy,
+    Socks5Proxy,
+}
+
+impl Route {
+    pub const ALL: [Route; 3] = [Route::Direct, Route::HttpProxy, Route::Socks5Proxy];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Route::Direct => "direct",
+            Route::HttpProxy => "http proxy",
+            Route::Socks5Proxy => "socks5 proxy",
+        }
+    }
+}
+
+/// Local proxy for one route, kept alive while the capture runs.
+pub enum LocalProxy {
+    Direct,
+    /// CONNECT proxy (the bandwidth relay without an upstream).
+    Http(Relay),
+    Socks5(Socks5Server),
+}
+
+impl LocalProxy {
+    pub async fn start(
+        route: Route,
+    ) -> Result<LocalProxy, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(match route {
+            Route::Direct => LocalProxy::Direct,
+            Route::HttpProxy => LocalProxy::Http(Relay::start(None).await?),
+            Route::Socks5Proxy => LocalProxy::Socks5(Socks5Server::start().await?),
+        })
+    }
+
+    /// Send the client's requests through this proxy.
+    pub fn apply(
+        &self,
+        builder: wreq::ClientBuilder,
+    ) -> Result<wreq::ClientBuilder, Box<dyn std::error::Error + Send + Sync>> {
+        let url = match self {
+            LocalProxy::Direct => return Ok(builder),
+            LocalProxy::Http(relay) => relay.proxy_url().to_string(),
+            LocalProxy::Socks5(server) => format!("socks5h://{}", server.addr),
+        };
+        Ok(builder.proxy(wreq::Proxy::all(url)?))
+    }
+}
+
+/// Minimal SOCKS5 proxy: no authentication, CONNECT only.
+pub struct Socks5Server {
+    addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl Socks5Server {
+    async fn start() -> std::io::Result<Socks5Server> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let task = tokio::spawn(async move {
+            while let Ok((inbound, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let _ = socks5_connect(inbound).await;
+                });
+            }
+        });
+        Ok(Socks5Server { addr, task })
+    }
+}
+
+impl Drop for Socks5Server {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn socks5_connect(mut inbound: TcpStream) -> std::io::Result<()> {
+    let invalid = |msg: &str| std::io::Error::new(std::io::ErrorKind::InvalidData, msg.to_string());
+
+    let mut greeting = [0u8; 2];
+    inbound.read_exact(&mut greeting).await?;
+    let mut methods = vec![0u8; greeting[1] as usize];
+    inbound.read_exact(&mut methods).await?;
+    if greeting[0] != 5 || !methods.contains(&0) {
+        inbound.write_all(&[5, 0xff]).await?;
+        return Err(invalid("SOCKS5 client does not offer no-auth"));
+    }
+    inbound.write_all(&[5, 0]).await?;
+
+    let mut request = [0u8; 4];
+    inbound.read_exact(&mut request).await?;
+    if request[..3] != [5, 1, 0] {
+        // Command not supported.
+        inbound.write_all(&[5, 7, 0, 1, 0, 0, 0, 0, 0, 0]).await?;
+        return Err(invalid("only SOCKS5 CONNECT is supported"));
+    }
+    let host = match request[3] {
+        1 => {
+            let mut ip = [0u8; 4];
+            inbound.read_exact(&mut ip).await?;
+            Ipv4Addr::from(ip).to_string()
+        }
+        3 => {
+            let mut name = vec![0u8; inbound.read_u8().await? as usize];
+            inbound.read_exact(&mut name).await?;
+            String::from_utf8_lossy(&name).into_owned()
+        }
+        4 => {
+            let mut ip = [0u8; 16];
+            inbound.read_exact(&mut ip).await?;
+            format!("[{}]", Ipv6Addr::from(ip))
+        }
+        _ => return Err(invalid("unknown SOCKS5 address type")),
+    };
+    let port = inbound.read_u16().await?;
+
+    let mut outbound = match TcpStream::connect(format!("{}:{}", host, port)).await {
+        Ok(stream) => stream,
+        Err(err) => {
+            // Host unreachable.
+            inbound.write_all(&[5, 4, 0, 1, 0, 0, 0, 0, 0, 0]).await?;
+            return Err(err);
+        }
+    };
+    inbound.write_all(&[5, 0, 0, 1, 0, 0, 0, 0, 0, 0]).await?;
+    tokio::io::copy_bidirectional(&mut inbound, &mut outbound).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn socks5_tunnels_to_a_domain_target() {
+        let target = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = target.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut stream, _) = target.accept().await.unwrap();
+            let mut buf = [0u8; 4];
+            stream.read_exact(&mut buf).await.unwrap();
+            stream.write_all(&buf).await.unwrap();
+        });
+
+        let server = Socks5Server::start().await.unwrap();
+        let mut client = TcpStream::connect(server.addr).await.unwrap();
+        client.write_all(&[5, 1, 0]).await.unwrap();
+        let mut reply = [0u8; 2];
+        client.read_exact(&mut reply).await.unwrap();
+        assert_eq!(reply, [5, 0]);
+
+        let mut request = vec![5, 1, 0, 3, 9];
+        request.extend_from_slice(b"localhost");
+        request.extend_from_slice(&port.to_be_bytes());
+        client.write_all(&request).await.unwrap();
+        let mut reply = [0u8; 10];
+        client.read_exact(&mut reply).await.unwrap();
+        assert_eq!(reply[..2], [5, 0]);
+
+        client.write_all(b"ping").await.unwrap();
+        let mut echo = [0u8; 4];
+        client.read_exact(&mut echo).await.unwrap();
+        assert_eq!(&echo, b"ping");
+    }
+}
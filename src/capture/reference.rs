@@ -0,0 +1,143 @@
+use super::server::Protocol;
+use std::path::{Path, PathBuf};
+
+/// Headers whose values depend on the capture target rather than the browser.
+/// Their names and positions are still compared.
+const VOLATILE_HEADERS: &[&str] = &[
+    ":authority",
+    ":path",
+    "host",
+    "content-length",
+    "cookie",
+    "origin",
+    "referer",
+    "sec-fetch-site",
+];
+
+/// Reference file for one preset, request context and protocol,
+/// e.g. `references/chrome-143/script.h2.txt`.
+pub fn reference_path(dir: &Path, preset: &str, context: &str, protocol: Protocol) -> PathBuf {
+    dir.join(preset)
+        .join(format!("{}.{}.txt", context, protocol.label()))
+}
+
+/// Comparable `name: value` lines, with volatile values masked as `*`.
+pub fn normalized_lines(headers: &[(String, String)]) -> Vec<String> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            if VOLATILE_HEADERS
+                .iter()
+                .any(|volatile| name.eq_ignore_ascii_case(volatile))
+            {
+                format!("{}: *", name)
+            } else {
+                format!("{}: {}", name, value)
+            }
+        })
+        .collect()
+}
+
+/// Read a reference capture (one `name: value` per line, `#` comments allowed).
+/// `None` when no reference has been recorded.
+pub fn load_reference(path: &Path) -> Option<Vec<String>> {
+    let content = std::fs::read_to_string(path).ok()?;
+    Some(parse_reference(&content))
+}
+
+fn parse_reference(content: &str) -> Vec<String> {
+    let headers: Vec<(String, String)> = content
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            // Pseudo-headers start with ':', so split after the name.
+            let (prefix, rest) = match line.strip_prefix(':') {
+                Some(rest) => (":", rest),
+                None => ("", line),
+            };
+            let (name, value) = rest.split_once(':').unwrap_or((rest, ""));
+            (format!("{}{}", prefix, name), value.trim().to_string())
+        })
+        .collect();
+    normalized_lines(&headers)
+}
+
+/// Write a reference capture with a leading `#` comment naming its source.
+pub fn save_reference(path: &Path, comment: &str, lines: &[String]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, format!("{}\n{}\n", comment, lines.join("\n")))
+}
+
+/// Line diff of `actual` against `expected`: `- ` lines are missing or moved,
+/// `+ ` lines are unexpected. Empty when both match exactly, order and casing included.
+pub fn diff_lines(expected: &[String], actual: &[String]) -> Vec<String> {
+    // Longest common subsequence table, filled from the end.
+    let (n, m) = (expected.len(), actual.len());
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if expected[i] == actual[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && expected[i] == actual[j] {
+            i += 1;
+            j += 1;
+        } else if j < m && (i == n || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            diff.push(format!("+ {}", actual[j]));
+            j += 1;
+        } else {
+            diff.push(format!("- {}", expected[i]));
+            i += 1;
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pseudo_headers_and_masks_volatile_values() {
+        let content = "# chrome-143 script\n:method: GET\n:authority: example.com\n\nuser-agent: Mozilla/5.0\nreferer: https://example.com/\n";
+        assert_eq!(
+            parse_reference(content),
+            [
+                ":method: GET",
+                ":authority: *",
+                "user-agent: Mozilla/5.0",
+                "referer: *",
+            ]
+        );
+    }
+
+    #[test]
+    fn tolerates_malformed_lines() {
+        // A multibyte first character and lines without a value must not panic.
+        assert_eq!(
+            parse_reference("\u{e9}t\u{e9}: oui\n\u{e9}\n:\naccept"),
+            ["\u{e9}t\u{e9}: oui", "\u{e9}: ", ":: ", "accept: "]
+        );
+    }
+
+    #[test]
+    fn diff_reports_moved_and_unexpected_lines() {
+        let lines = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let expected = lines(&["a: 1", "b: 2", "c: 3"]);
+        assert!(diff_lines(&expected, &expected).is_empty());
+        assert_eq!(
+            diff_lines(&expected, &lines(&["b: 2", "a: 1", "c: 3", "d: 4"])),
+            ["+ b: 2", "- b: 2", "+ d: 4"]
+        );
+    }
+}
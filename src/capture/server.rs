@@ -0,0 +1,440 @@
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Self-signed certificate for `localhost` and 127.0.0.1; clients skip verification.
+const TLS_CERT: &[u8] = include_bytes!("localhost.crt");
+const TLS_KEY: &[u8] = include_bytes!("localhost.key");
+
+/// HTTP/2 client connection preface.
+const H2_PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+const FRAME_DATA: u8 = 0x0;
+const FRAME_HEADERS: u8 = 0x1;
+const FRAME_PRIORITY: u8 = 0x2;
+const FRAME_SETTINGS: u8 = 0x4;
+const FRAME_PING: u8 = 0x6;
+const FRAME_GOAWAY: u8 = 0x7;
+const FRAME_WINDOW_UPDATE: u8 = 0x8;
+const FRAME_CONTINUATION: u8 = 0x9;
+
+const FLAG_END_STREAM: u8 = 0x1;
+const FLAG_ACK: u8 = 0x1;
+const FLAG_END_HEADERS: u8 = 0x4;
+const FLAG_PADDED: u8 = 0x8;
+const FLAG_PRIORITY: u8 = 0x20;
+
+/// Wire protocol a request arrived over.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Protocol {
+    Http1,
+    Http2,
+}
+
+impl Protocol {
+    /// Short label used in reference file names and output.
+    pub fn label(self) -> &'static str {
+        match self {
+            Protocol::Http1 => "h1",
+            Protocol::Http2 => "h2",
+        }
+    }
+
+    /// Protocol of a HAR `httpVersion` ("HTTP/1.1", "http/2.0", "h2", ...); `None` for HTTP/3.
+    pub fn from_har_version(version: &str) -> Option<Protocol> {
+        match version.to_ascii_lowercase().as_str() {
+            "http/1.1" | "http/1.0" => Some(Protocol::Http1),
+            "h2" | "http/2" | "http/2.0" => Some(Protocol::Http2),
+            _ => None,
+        }
+    }
+}
+
+/// One request as it appeared on the wire, headers in arrival order with original casing.
+#[derive(Clone, Debug)]
+pub struct CapturedRequest {
+    pub protocol: Protocol,
+    pub path: String,
+    /// HTTP/2 captures include the pseudo-headers (`:method`, `:authority`, ...).
+    pub headers: Vec<(String, String)>,
+    /// Connection-level frames sent before the first request (HTTP/2 only).
+    pub http2: Option<Http2Preamble>,
+}
+
+/// SETTINGS, WINDOW_UPDATE and PRIORITY frames a client opens an HTTP/2 connection with.
+#[derive(Clone, Debug, Default)]
+pub struct Http2Preamble {
+    /// (identifier, value) in frame order.
+    pub settings: Vec<(u16, u32)>,
+    /// Connection window increment, if sent.
+    pub window_update: Option<u32>,
+    /// (stream, exclusive, depends on, weight 1-256).
+    pub priorities: Vec<(u32, bool, u32, u16)>,
+}
+
+impl Http2Preamble {
+    /// Akamai HTTP/2 fingerprint: `SETTINGS|WINDOW_UPDATE|PRIORITY|pseudo-header order`.
+    pub fn akamai_fingerprint(&self, headers: &[(String, String)]) -> String {
+        let settings = self
+            .settings
+            .iter()
+            .map(|(id, value)| format!("{}:{}", id, value))
+            .collect::<Vec<_>>()
+            .join(";");
+        let window_update = self
+            .window_update
+            .map(|increment| increment.to_string())
+            .unwrap_or_else(|| "00".to_string());
+        let priorities = if self.priorities.is_empty() {
+            "0".to_string()
+        } else {
+            self.priorities
+                .iter()
+                .map(|(stream, exclusive, depends_on, weight)| {
+                    format!("{}:{}:{}:{}", stream, *exclusive as u8, depends_on, weight)
+                })
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let pseudo_headers = headers
+            .iter()
+            .filter_map(|(name, _)| name.strip_prefix(':')?.get(..1))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        format!(
+            "{}|{}|{}|{}",
+            settings, window_update, priorities, pseudo_headers
+        )
+    }
+}
+
+/// Local server recording HTTP/1.1 and HTTP/2 requests, over cleartext (HTTP/2 by
+/// prior knowledge) or over TLS with the protocol negotiated by ALPN.
+pub struct CaptureServer {
+    addr: SocketAddr,
+    tls: bool,
+    captures: mpsc::UnboundedReceiver<CapturedRequest>,
+    task: JoinHandle<()>,
+}
+
+impl CaptureServer {
+    /// Bind an ephemeral port on 127.0.0.1 and start accepting cleartext connections.
+    pub async fn start() -> std::io::Result<CaptureServer> {
+        Self::bind(None).await
+    }
+
+    /// Like `start`, but terminate TLS first and offer `h2` and `http/1.1` over ALPN.
+    pub async fn start_tls() -> Result<CaptureServer, Box<dyn std::error::Error + Send + Sync>> {
+        let identity = native_tls::Identity::from_pkcs8(TLS_CERT, TLS_KEY)?;
+        let acceptor = native_tls::TlsAcceptor::builder(identity)
+            .accept_alpn(&["h2", "http/1.1"])
+            .build()?;
+        Ok(Self::bind(Some(tokio_native_tls::TlsAcceptor::from(acceptor))).await?)
+    }
+
+    async fn bind(tls: Option<tokio_native_tls::TlsAcceptor>) -> std::io::Result<CaptureServer> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let (tx, captures) = mpsc::unbounded_channel();
+        let is_tls = tls.is_some();
+
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let tx = tx.clone();
+                let tls = tls.clone();
+                tokio::spawn(async move {
+                    let _ = match tls {
+                        Some(acceptor) => match acceptor.accept(stream).await {
+                            Ok(stream) => serve_connection(stream, tx).await,
+                            Err(_) => Ok(()),
+                        },
+                        None => serve_connection(stream, tx).await,
+                    };
+                });
+            }
+        });
+
+        Ok(CaptureServer {
+            addr,
+            tls: is_tls,
+            captures,
+            task,
+        })
+    }
+
+    /// Absolute URL for a path on this server; TLS servers are addressed as
+    /// `localhost` so clients send SNI.
+    pub fn url(&self, path: &str) -> String {
+        if self.tls {
+            format!("https://localhost:{}{}", self.addr.port(), path)
+        } else {
+            format!("http://{}{}", self.addr, path)
+        }
+    }
+
+    /// Wait for the next request to `path`, discarding captures for other paths.
+    pub async fn next_capture(&mut self, path: &str, wait: Duration) -> Option<CapturedRequest> {
+        let deadline = tokio::time::Instant::now() + wait;
+        loop {
+            let capture = tokio::time::timeout_at(deadline, self.captures.recv())
+                .await
+                .ok()??;
+            if capture.path == path {
+                return Some(capture);
+            }
+        }
+    }
+}
+
+impl Drop for CaptureServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn serve_connection<S: AsyncRead + AsyncWrite + Unpin>(
+    mut stream: S,
+    tx: mpsc::UnboundedSender<CapturedRequest>,
+) -> std::io::Result<()> {
+    let mut buf = Vec::with_capacity(4096);
+    while buf.len() < H2_PREFACE.len() && H2_PREFACE.starts_with(&buf) {
+        if !read_more(&mut stream, &mut buf).await? {
+            return Ok(());
+        }
+    }
+
+    if buf.starts_with(H2_PREFACE) {
+        buf.drain(..H2_PREFACE.len());
+        serve_http2(stream, buf, tx).await
+    } else {
+        serve_http1(stream, buf, tx).await
+    }
+}
+
+/// Append whatever the peer sent next; `false` on EOF.
+async fn read_more<S: AsyncRead + Unpin>(
+    stream: &mut S,
+    buf: &mut Vec<u8>,
+) -> std::io::Result<bool> {
+    let mut chunk = [0u8; 4096];
+    let n = stream.read(&mut chunk).await?;
+    buf.extend_from_slice(&chunk[..n]);
+    Ok(n > 0)
+}
+
+async fn serve_http1<S: AsyncRead + AsyncWrite + Unpin>(
+    mut stream: S,
+    mut buf: Vec<u8>,
+    tx: mpsc::UnboundedSender<CapturedRequest>,
+) -> std::io::Result<()> {
+    loop {
+        let head_end = loop {
+            if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                break pos;
+            }
+            if !read_more(&mut stream, &mut buf).await? {
+                return Ok(());
+            }
+        };
+
+        let head = String::from_utf8_lossy(&buf[..head_end]).into_owned();
+        let mut lines = head.split("\r\n");
+        let path = lines
+            .next()
+            .and_then(|line| line.split(' ').nth(1))
+            .unwrap_or("")
+            .to_string();
+        let headers: Vec<(String, String)> = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.to_string(), value.trim().to_string()))
+            .collect();
+
+        let body_len = headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+            .and_then(|(_, value)| value.parse::<usize>().ok())
+            .unwrap_or(0);
+        let request_end = head_end + 4 + body_len;
+        while buf.len() < request_end {
+            if !read_more(&mut stream, &mut buf).await? {
+                return Ok(());
+            }
+        }
+        buf.drain(..request_end);
+
+        let _ = tx.send(CapturedRequest {
+            protocol: Protocol::Http1,
+            path,
+            headers,
+            http2: None,
+        });
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+            .await?;
+    }
+}
+
+async fn serve_http2<S: AsyncRead + AsyncWrite + Unpin>(
+    mut stream: S,
+    mut buf: Vec<u8>,
+    tx: mpsc::UnboundedSender<CapturedRequest>,
+) -> std::io::Result<()> {
+    write_frame(&mut stream, FRAME_SETTINGS, 0, 0, &[]).await?;
+
+    let mut decoder = hpack::Decoder::new();
+    // Header block being assembled from HEADERS + CONTINUATION frames.
+    let mut block: Vec<u8> = Vec::new();
+    let mut block_stream: u32 = 0;
+    let mut block_end_stream = false;
+    // Frames before the first HEADERS; later ones belong to the connection's lifetime.
+    let mut preamble = Http2Preamble::default();
+    let mut preamble_done = false;
+
+    loop {
+        while buf.len() < 9 {
+            if !read_more(&mut stream, &mut buf).await? {
+                return Ok(());
+            }
+        }
+        let len = u32::from_be_bytes([0, buf[0], buf[1], buf[2]]) as usize;
+        let kind = buf[3];
+        let flags = buf[4];
+        let stream_id = u32::from_be_bytes([buf[5], buf[6], buf[7], buf[8]]) & 0x7fff_ffff;
+        while buf.len() < 9 + len {
+            if !read_more(&mut stream, &mut buf).await? {
+                return Ok(());
+            }
+        }
+        let payload: Vec<u8> = buf.drain(..9 + len).skip(9).collect();
+
+        match kind {
+            FRAME_SETTINGS if flags & FLAG_ACK == 0 => {
+                if !preamble_done {
+                    preamble
+                        .settings
+                        .extend(payload.chunks_exact(6).map(|entry| {
+                            (
+                                u16::from_be_bytes([entry[0], entry[1]]),
+                                u32::from_be_bytes([entry[2], entry[3], entry[4], entry[5]]),
+                            )
+                        }));
+                }
+                write_frame(&mut stream, FRAME_SETTINGS, FLAG_ACK, 0, &[]).await?;
+            }
+            FRAME_WINDOW_UPDATE if stream_id == 0 && payload.len() == 4 && !preamble_done => {
+                preamble.window_update = Some(
+                    u32::from_be_bytes([payload[0], payload[1], payload[2], payload[3]])
+                        & 0x7fff_ffff,
+                );
+            }
+            FRAME_PRIORITY if payload.len() == 5 && !preamble_done => {
+                let dependency =
+                    u32::from_be_bytes([payload[0], payload[1], payload[2], payload[3]]);
+                preamble.priorities.push((
+                    stream_id,
+                    dependency & 0x8000_0000 != 0,
+                    dependency & 0x7fff_ffff,
+                    payload[4] as u16 + 1,
+                ));
+            }
+            FRAME_PING if flags & FLAG_ACK == 0 => {
+                write_frame(&mut stream, FRAME_PING, FLAG_ACK, 0, &payload).await?;
+            }
+            FRAME_HEADERS => {
+                let mut fragment = payload.as_slice();
+                let mut pad = 0;
+                if flags & FLAG_PADDED != 0 && !fragment.is_empty() {
+                    pad = fragment[0] as usize;
+                    fragment = &fragment[1..];
+                }
+                if flags & FLAG_PRIORITY != 0 && fragment.len() >= 5 {
+                    fragment = &fragment[5..];
+                }
+                fragment = &fragment[..fragment.len().saturating_sub(pad)];
+                block = fragment.to_vec();
+                block_stream = stream_id;
+                block_end_stream = flags & FLAG_END_STREAM != 0;
+            }
+            FRAME_CONTINUATION => block.extend_from_slice(&payload),
+            FRAME_DATA if flags & FLAG_END_STREAM != 0 => {
+                respond_http2(&mut stream, stream_id).await?;
+            }
+            FRAME_GOAWAY => return Ok(()),
+            _ => {}
+        }
+
+        let header_block_done =
+            matches!(kind, FRAME_HEADERS | FRAME_CONTINUATION) && flags & FLAG_END_HEADERS != 0;
+        if header_block_done {
+            let headers: Vec<(String, String)> = decoder
+                .decode(&block)
+                .map_err(|err| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("HPACK decode failed: {:?}", err),
+                    )
+                })?
+                .into_iter()
+                .map(|(name, value)| {
+                    (
+                        String::from_utf8_lossy(&name).into_owned(),
+                        String::from_utf8_lossy(&value).into_owned(),
+                    )
+                })
+                .collect();
+            let path = headers
+                .iter()
+                .find(|(name, _)| name == ":path")
+                .map(|(_, value)| value.clone())
+                .unwrap_or_default();
+
+            let _ = tx.send(CapturedRequest {
+                protocol: Protocol::Http2,
+                path,
+                headers,
+                http2: Some(preamble.clone()),
+            });
+            preamble_done = true;
+            if block_end_stream {
+                respond_http2(&mut stream, block_stream).await?;
+            }
+        }
+    }
+}
+
+/// Empty `:status 200` response closing the stream.
+async fn respond_http2<S: AsyncWrite + Unpin>(
+    stream: &mut S,
+    stream_id: u32,
+) -> std::io::Result<()> {
+    // 0x88 = indexed static table entry 8 (":status: 200").
+    write_frame(
+        stream,
+        FRAME_HEADERS,
+        FLAG_END_HEADERS | FLAG_END_STREAM,
+        stream_id,
+        &[0x88],
+    )
+    .await
+}
+
+async fn write_frame<S: AsyncWrite + Unpin>(
+    stream: &mut S,
+    kind: u8,
+    flags: u8,
+    stream_id: u32,
+    payload: &[u8],
+) -> std::io::Result<()> {
+    let len = (payload.len() as u32).to_be_bytes();
+    let mut frame = Vec::with_capacity(9 + payload.len());
+    frame.extend_from_slice(&len[1..]);
+    frame.push(kind);
+    frame.push(flags);
+    frame.extend_from_slice(&stream_id.to_be_bytes());
+    frame.extend_from_slice(payload);
+    stream.write_all(&frame).await
+}
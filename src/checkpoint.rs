@@ -0,0 +1,236 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+
+/// One completed bulk scan as recorded in the checkpoint journal.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub proxy: String,
+    /// "clean", "detected", "filtered" or "error".
+    pub outcome: String,
+    #[serde(default)]
+    pub exit_ip: String,
+    #[serde(default)]
+    pub bandwidth_bytes: u64,
+    /// Bandwidth came from byte counters rather than the estimate.
+    #[serde(default)]
+    pub measured: bool,
+    #[serde(default)]
+    pub abuser_score: Option<f64>,
+}
+
+/// Append-only NDJSON journal of completed bulk scans. Each entry is written
+/// after the scan's CSV row, so a resumed run never drops a row.
+pub struct Journal {
+    file: File,
+    /// Entries of the interrupted run when resuming; empty for a fresh run.
+    pub previous: Vec<JournalEntry>,
+}
+
+impl Journal {
+    /// Start a new journal, or with `resume` read the existing one (a missing file
+    /// resumes nothing) and append to it.
+    pub fn open(
+        path: &str,
+        resume: bool,
+    ) -> Result<Journal, Box<dyn std::error::Error + Send + Sync>> {
+        let content = if resume {
+            match std::fs::read_to_string(path) {
+                Ok(content) => content,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+                Err(e) => return Err(format!("cannot read checkpoint {}: {}", path, e).into()),
+            }
+        } else {
+            String::new()
+        };
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(resume)
+            .write(true)
+            .truncate(!resume)
+            .open(path)
+            .map_err(|e| format!("cannot open checkpoint {}: {}", path, e))?;
+        // Terminate a line torn by the interruption so the next entry starts clean.
+        if !content.is_empty() && !content.ends_with('\n') {
+            writeln!(file)?;
+        }
+        Ok(Journal {
+            file,
+            previous: parse_entries(&content),
+        })
+    }
+
+    /// Proxies completed by the interrupted run.
+    pub fn completed(&self) -> HashSet<String> {
+        self.previous
+            .iter()
+            .map(|entry| entry.proxy.clone())
+            .collect()
+    }
+
+    /// The last entry of each proxy in `proxies`, in input order. A proxy scanned
+    /// again after a torn entry counts once, and proxies no longer in the input
+    /// are left out.
+    pub fn resumed<'a>(&'a self, proxies: &[String]) -> Vec<&'a JournalEntry> {
+        let mut last: HashMap<&str, &JournalEntry> = HashMap::new();
+        for entry in &self.previous {
+            last.insert(entry.proxy.as_str(), entry);
+        }
+        let mut seen = HashSet::new();
+        proxies
+            .iter()
+            .filter(|proxy| seen.insert(proxy.as_str()))
+            .filter_map(|proxy| last.get(proxy.as_str()).copied())
+            .collect()
+    }
+
+    pub fn record(&mut self, entry: &JournalEntry) -> std::io::Result<()> {
+        let line = serde_json::to_string(entry).map_err(std::io::Error::other)?;
+        writeln!(self.file, "{}", line)?;
+        self.file.flush()
+    }
+}
+
+/// Parse journal lines; a line torn by the interruption is ignored, so its
+/// proxy is scanned again.
+fn parse_entries(content: &str) -> Vec<JournalEntry> {
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line.trim()).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(proxy: &str, outcome: &str) -> JournalEntry {
+        JournalEntry {
+            proxy: proxy.to_string(),
+            outcome: outcome.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn journal_path(name: &str) -> String {
+        let path =
+            std::env::temp_dir().join(format!("checkpoint-{}-{}.ndjson", name, std::process::id()));
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn parses_entries_and_skips_a_torn_line() {
+        let content = concat!(
+            r#"{"proxy":"http://a:1","outcome":"clean","bandwidth_bytes":1200,"measured":true}"#,
+            "\n",
+            "\n",
+            r#"{"proxy":"http://b:1","outcome":"error"}"#,
+            "\n",
+            r#"{"proxy":"http://c:1","outcome":"det"#,
+        );
+        let entries = parse_entries(content);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].proxy, "http://a:1");
+        assert_eq!(entries[0].bandwidth_bytes, 1200);
+        assert!(entries[0].measured);
+        // Fields added later default for older journals.
+        assert_eq!(entries[1].bandwidth_bytes, 0);
+        assert_eq!(entries[1].abuser_score, None);
+    }
+
+    #[test]
+    fn resume_terminates_a_torn_line_and_appends() {
+        let path = journal_path("torn");
+        let mut journal = Journal::open(&path, false).unwrap();
+        journal.record(&entry("http://a:1", "clean")).unwrap();
+        drop(journal);
+        // Interrupted while writing the second entry.
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        write!(file, r#"{{"proxy":"http://b:1","outc"#).unwrap();
+        drop(file);
+
+        let mut journal = Journal::open(&path, true).unwrap();
+        let completed = journal.completed();
+        assert!(completed.contains("http://a:1"));
+        // The torn proxy is scanned again.
+        assert!(!completed.contains("http://b:1"));
+        journal.record(&entry("http://b:1", "detected")).unwrap();
+        drop(journal);
+
+        let journal = Journal::open(&path, true).unwrap();
+        let proxies: Vec<_> = journal.previous.iter().map(|e| e.proxy.as_str()).collect();
+        assert_eq!(proxies, ["http://a:1", "http://b:1"]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn rescanned_proxy_is_completed_once() {
+        // A proxy whose CSV row was written but whose journal entry was torn is
+        // scanned again, so the CSV holds its row twice; a later resume still
+        // counts it once and does not scan it a third time.
+        let path = journal_path("duplicate");
+        std::fs::write(
+            &path,
+            concat!(
+                r#"{"proxy":"http://a:1","outcome":"clean"}"#,
+                "\n",
+                r#"{"proxy":"http://a:1","outcome":"detected"}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+        let journal = Journal::open(&path, true).unwrap();
+        assert_eq!(journal.previous.len(), 2);
+        assert_eq!(journal.completed().len(), 1);
+        assert!(journal.completed().contains("http://a:1"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn resume_counts_the_last_entry_of_each_input_proxy() {
+        let path = journal_path("resumed");
+        std::fs::write(
+            &path,
+            concat!(
+                r#"{"proxy":"http://a:1","outcome":"clean","bandwidth_bytes":100}"#,
+                "\n",
+                r#"{"proxy":"http://gone:1","outcome":"detected"}"#,
+                "\n",
+                r#"{"proxy":"http://b:1","outcome":"error"}"#,
+                "\n",
+                r#"{"proxy":"http://a:1","outcome":"detected","bandwidth_bytes":300}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+        let journal = Journal::open(&path, true).unwrap();
+        let proxies = ["http://b:1", "http://a:1", "http://c:1", "http://a:1"].map(String::from);
+        let resumed = journal.resumed(&proxies);
+        let summary: Vec<_> = resumed
+            .iter()
+            .map(|e| (e.proxy.as_str(), e.outcome.as_str(), e.bandwidth_bytes))
+            .collect();
+        assert_eq!(
+            summary,
+            [("http://b:1", "error", 0), ("http://a:1", "detected", 300)]
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn fresh_run_and_missing_journal_resume_nothing() {
+        let path = journal_path("fresh");
+        std::fs::write(&path, "{\"proxy\":\"http://a:1\",\"outcome\":\"clean\"}\n").unwrap();
+        let journal = Journal::open(&path, false).unwrap();
+        assert!(journal.previous.is_empty());
+        drop(journal);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "");
+        std::fs::remove_file(&path).unwrap();
+
+        let journal = Journal::open(&path, true).unwrap();
+        assert!(journal.completed().is_empty());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,160 @@
+use super::result::test_detected;
+use super::{run, DetectionResult, Options, ScanError};
+use crate::browser::{get_preset, preset_matches_os, presets_matching_os};
+use std::collections::BTreeMap;
+
+/// One scan made by `--auto-preset`.
+#[derive(Clone, Debug)]
+pub struct PresetAttempt {
+    pub preset: String,
+    /// TCP/IP OS guess the scan saw.
+    pub tcpip_os: Option<String>,
+    /// Cached guess the preset was chosen from without a first scan.
+    pub cached_os: Option<String>,
+    /// Verdict of `tcpip_fp`; `None` if the test gave none or the scan failed.
+    pub tcpip_detected: Option<bool>,
+    pub error: Option<String>,
+}
+
+impl PresetAttempt {
+    fn from_result(result: &DetectionResult, cached_os: Option<&str>) -> PresetAttempt {
+        let tcpip_detected = result
+            .individual_tests()
+            .and_then(|tests| tests.get("tcpip_fp"))
+            .and_then(test_detected);
+        PresetAttempt {
+            preset: result.preset.clone(),
+            tcpip_os: result.tcpip_os(),
+            cached_os: cached_os.map(str::to_string),
+            tcpip_detected,
+            error: None,
+        }
+    }
+}
+
+/// TCP/IP OS guesses per proxy, persisted between runs as a JSON object.
+#[derive(Debug, Default)]
+pub struct OsCache {
+    entries: BTreeMap<String, String>,
+}
+
+impl OsCache {
+    /// Load the cache; a missing file is an empty cache.
+    pub fn load(path: &str) -> Result<OsCache, Box<dyn std::error::Error + Send + Sync>> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(OsCache::default()),
+            Err(e) => return Err(e.into()),
+        };
+        let entries = serde_json::from_str(&content)
+            .map_err(|e| format!("invalid OS cache {}: {}", path, e))?;
+        Ok(OsCache { entries })
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        std::fs::write(path, serde_json::to_string_pretty(&self.entries)?)?;
+        Ok(())
+    }
+
+    pub fn get(&self, proxy: &str) -> Option<&str> {
+        self.entries.get(proxy).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, proxy: &str, os: &str) {
+        self.entries.insert(proxy.to_string(), os.to_string());
+    }
+}
+
+/// Built-in preset matching `os_guess`, preferring the browser family of `current`;
+/// `None` if `current` already matches or no preset does.
+fn matching_preset(current: &str, os_guess: &str) -> Option<&'static str> {
+    let current = get_preset(current);
+    if preset_matches_os(&current, os_guess) {
+        return None;
+    }
+    let family = current.base.split('-').next().unwrap_or_default();
+    let candidates = presets_matching_os(os_guess);
+    candidates
+        .iter()
+        .copied()
+        .find(|name| name.split('-').next() == Some(family))
+        .or_else(|| candidates.first().copied())
+}
+
+/// Scan with `opts.browser_name` and, if `tcpip_fp` flags the exit's TCP/IP OS,
+/// rescan with a preset whose OS family matches it. A cached OS guess picks the
+/// preset up front and skips the first scan; if the engine still flags that preset,
+/// the guess was stale and the usual rescan follows. The result lists every attempt,
+/// and its `bandwidth_bytes` covers all of them, as does `traffic` when every attempt
+/// was metered (otherwise it is `None`).
+pub async fn run_auto_preset(
+    opts: &Options,
+    cached_os: Option<&str>,
+    log: impl Fn(&str),
+) -> Result<DetectionResult, Box<dyn std::error::Error + Send + Sync>> {
+    let mut opts = opts.clone();
+
+    if let Some(os) = cached_os {
+        if let Some(name) = matching_preset(&opts.browser_name, os) {
+            log(&format!("Cached TCP/IP OS {}: using preset {}", os, name));
+            opts.browser_name = name.to_string();
+        }
+    }
+
+    let mut first = run(&opts, &log).await?;
+    let mut attempts = vec![PresetAttempt::from_result(&first, cached_os)];
+
+    let next = match (first.tcpip_os(), attempts[0].tcpip_detected) {
+        (Some(os), Some(true)) => matching_preset(&opts.browser_name, &os).map(|name| (os, name)),
+        _ => None,
+    };
+    let (os, name) = match next {
+        Some(next) => next,
+        None => {
+            first.preset_attempts = attempts;
+            return Ok(first);
+        }
+    };
+
+    log(&format!(
+        "TCP/IP OS {} does not match preset {}: rescanning with {}",
+        os, first.preset, name
+    ));
+    opts.browser_name = name.to_string();
+    match run(&opts, &log).await {
+        Ok(mut second) => {
+            attempts.push(PresetAttempt::from_result(&second, None));
+            second.preset_attempts = attempts;
+            // The row accounts for both attempts; a per-phase breakdown only
+            // exists when both were metered.
+            second.bandwidth_bytes += first.bandwidth_bytes;
+            second.traffic = match (second.traffic, first.traffic) {
+                (Some(rescan), Some(earlier)) => Some(rescan + earlier),
+                _ => None,
+            };
+            Ok(second)
+        }
+        Err(e) => {
+            log(&format!(
+                "WARNING: Rescan failed ({}), keeping first result",
+                e
+            ));
+            attempts.push(PresetAttempt {
+                preset: name.to_string(),
+                tcpip_os: None,
+                cached_os: None,
+                tcpip_detected: None,
+                error: Some(e.to_string()),
+            });
+            first.preset_attempts = attempts;
+            // The failed rescan reports only a total, so the first attempt's
+            // breakdown no longer covers the row's bytes.
+            let (rescan_bytes, _) = ScanError::spent(e.as_ref());
+            first.bandwidth_bytes += rescan_bytes;
+            if rescan_bytes > 0 {
+                first.traffic = None;
+            }
+            Ok(first)
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use super::result::test_detected;
+use super::{ChannelStats, DetectionResult};
+
+/// How a proxied scan differs from an unproxied baseline scan.
+#[derive(Clone, Debug, Default)]
+pub struct BaselineDelta {
+    /// Median image RTT added over the baseline (milliseconds).
+    pub added_latency: Option<f64>,
+    /// TCP/IP OS guess from `tcpip_fp` (baseline, proxied), if it changed.
+    pub tcpip_os: Option<(String, String)>,
+    /// Presented IANA timezone (baseline, proxied), if it changed.
+    pub timezone: Option<(String, String)>,
+    /// Exit ASN (baseline, proxied), if it changed.
+    pub asn: Option<(String, String)>,
+    /// Keys of tests that were clean on the baseline and detected through the proxy.
+    pub flipped: Vec<String>,
+}
+
+impl BaselineDelta {
+    pub fn compare(baseline: &DetectionResult, scan: &DetectionResult) -> BaselineDelta {
+        let added_latency = match (
+            ChannelStats::from_samples(&baseline.image_latencies),
+            ChannelStats::from_samples(&scan.image_latencies),
+        ) {
+            (Some(base), Some(proxied)) => Some(proxied.median - base.median),
+            _ => None,
+        };
+
+        let mut flipped = Vec::new();
+        if let (Some(base_tests), Some(tests)) =
+            (baseline.individual_tests(), scan.individual_tests())
+        {
+            for (key, raw) in tests {
+                if test_detected(raw) == Some(true)
+                    && base_tests.get(key).and_then(test_detected) == Some(false)
+                {
+                    flipped.push(key.clone());
+                }
+            }
+        }
+
+        BaselineDelta {
+            added_latency,
+            tcpip_os: changed(
+                baseline.tcpip_os().unwrap_or_default(),
+                scan.tcpip_os().unwrap_or_default(),
+            ),
+            timezone: changed(baseline.timezone.clone(), scan.timezone.clone()),
+            asn: changed(baseline.asn.clone(), scan.asn.clone()),
+            flipped,
+        }
+    }
+}
+
+/// A value that differs between the scans; a side without a value is no change.
+fn changed(before: String, after: String) -> Option<(String, String)> {
+    (!before.is_empty() && !after.is_empty() && before != after).then_some((before, after))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn scan(tests: serde_json::Value, image: &[f64], timezone: &str, asn: &str) -> DetectionResult {
+        let mut result = DetectionResult {
+            image_latencies: image.to_vec(),
+            timezone: timezone.to_string(),
+            asn: asn.to_string(),
+            ..Default::default()
+        };
+        result.tests.insert("tests".to_string(), tests);
+        result
+    }
+
+    #[test]
+    fn reports_changes_against_the_baseline() {
+        let baseline = scan(
+            json!({
+                "http_headers": {"is_proxy": false},
+                "latency": {"is_proxy": false, "is_vpn": false},
+                "datacenter": {"is_proxy": true},
+                "proxy_ai": {"is_vpn": false},
+                "tcpip_fp": {"is_proxy": false, "info": {"tcpIpHighestOs": "Windows"}},
+            }),
+            &[20.0, 30.0, 40.0],
+            "Europe/Berlin",
+            "AS3320 Deutsche Telekom AG",
+        );
+        let proxied = scan(
+            json!({
+                "http_headers": {"is_proxy": false},
+                "latency": {"is_vpn": true},
+                "datacenter": {"is_proxy": true},
+                "proxy_ai": {"is_proxy": true},
+                "tcpip_fp": {"is_proxy": true, "info": {"tcpIpHighestOs": "Linux"}},
+                "new_test": {"is_proxy": true},
+            }),
+            &[120.0, 130.0, 140.0],
+            "Europe/Berlin",
+            "AS16509 Amazon.com, Inc.",
+        );
+
+        let delta = BaselineDelta::compare(&baseline, &proxied);
+        assert_eq!(delta.added_latency, Some(100.0));
+        assert_eq!(
+            delta.tcpip_os,
+            Some(("Windows".to_string(), "Linux".to_string()))
+        );
+        assert_eq!(delta.timezone, None);
+        assert_eq!(
+            delta.asn,
+            Some((
+                "AS3320 Deutsche Telekom AG".to_string(),
+                "AS16509 Amazon.com, Inc.".to_string()
+            ))
+        );
+        // Already detected on the baseline (datacenter) and tests missing from it do not flip.
+        let mut flipped = delta.flipped.clone();
+        flipped.sort();
+        assert_eq!(flipped, ["latency", "proxy_ai", "tcpip_fp"]);
+    }
+
+    #[test]
+    fn missing_data_is_not_a_change() {
+        let empty = DetectionResult::default();
+        let delta = BaselineDelta::compare(&empty, &empty);
+        assert_eq!(delta.added_latency, None);
+        assert_eq!(delta.tcpip_os, None);
+        assert_eq!(delta.timezone, None);
+        assert_eq!(delta.asn, None);
+        assert!(delta.flipped.is_empty());
+
+        let proxied = scan(json!({"latency": {"is_proxy": true}}), &[50.0], "UTC", "");
+        let delta = BaselineDelta::compare(&empty, &proxied);
+        assert_eq!(delta.added_latency, None);
+        assert!(delta.flipped.is_empty());
+        assert_eq!(delta.timezone, None);
+        assert_eq!(delta.asn, None);
+    }
+}
@@ -0,0 +1,133 @@
+use super::DetectionResult;
+
+/// Summary of one latency channel's samples (milliseconds).
+#[derive(Clone, Copy, Debug)]
+pub struct ChannelStats {
+    pub samples: usize,
+    pub min: f64,
+    pub median: f64,
+    /// Mean absolute difference between consecutive samples.
+    pub jitter: f64,
+}
+
+impl ChannelStats {
+    pub fn from_samples(samples: &[f64]) -> Option<ChannelStats> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let mid = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[mid - 1] + sorted[mid]) / 2.0
+        } else {
+            sorted[mid]
+        };
+
+        let jitter = if samples.len() < 2 {
+            0.0
+        } else {
+            let total: f64 = samples.windows(2).map(|w| (w[1] - w[0]).abs()).sum();
+            total / (samples.len() - 1) as f64
+        };
+
+        Some(ChannelStats {
+            samples: samples.len(),
+            min: sorted[0],
+            median,
+            jitter,
+        })
+    }
+}
+
+/// Local view of the latencies the engine judges in `latency_vs_ping`,
+/// `latency` and `high_latencies`.
+#[derive(Clone, Copy, Debug)]
+pub struct LatencyAnalysis {
+    pub image: Option<ChannelStats>,
+    pub websocket: Option<ChannelStats>,
+    /// Image probes over a direct connection, if a baseline was measured.
+    pub direct: Option<ChannelStats>,
+}
+
+impl LatencyAnalysis {
+    pub fn new(result: &DetectionResult, direct_latencies: Option<&[f64]>) -> LatencyAnalysis {
+        LatencyAnalysis {
+            image: ChannelStats::from_samples(&result.image_latencies),
+            websocket: ChannelStats::from_samples(&result.ws_latencies),
+            direct: direct_latencies.and_then(ChannelStats::from_samples),
+        }
+    }
+
+    /// Median image (HTTP) RTT over median WebSocket RTT.
+    pub fn http_ws_ratio(&self) -> Option<f64> {
+        match (self.image, self.websocket) {
+            (Some(image), Some(ws)) if ws.median > 0.0 => Some(image.median / ws.median),
+            _ => None,
+        }
+    }
+
+    /// Median image RTT added by the proxy over the direct baseline (milliseconds).
+    pub fn proxy_overhead(&self) -> Option<f64> {
+        match (self.image, self.direct) {
+            (Some(image), Some(direct)) => Some(image.median - direct.median),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channel_stats() {
+        assert!(ChannelStats::from_samples(&[]).is_none());
+
+        let single = ChannelStats::from_samples(&[42.0]).unwrap();
+        assert_eq!(single.samples, 1);
+        assert_eq!(
+            (single.min, single.median, single.jitter),
+            (42.0, 42.0, 0.0)
+        );
+
+        // Odd count: middle of the sorted samples; jitter follows arrival order.
+        let odd = ChannelStats::from_samples(&[30.0, 10.0, 20.0]).unwrap();
+        assert_eq!((odd.min, odd.median), (10.0, 20.0));
+        assert_eq!(odd.jitter, 15.0);
+
+        // Even count: mean of the two middle samples.
+        let even = ChannelStats::from_samples(&[40.0, 10.0, 30.0, 20.0]).unwrap();
+        assert_eq!((even.samples, even.min, even.median), (4, 10.0, 25.0));
+        assert_eq!(even.jitter, 20.0);
+    }
+
+    #[test]
+    fn ratio_and_overhead() {
+        let result = DetectionResult {
+            image_latencies: vec![120.0, 100.0, 110.0],
+            ws_latencies: vec![50.0, 60.0],
+            ..Default::default()
+        };
+        let analysis = LatencyAnalysis::new(&result, Some(&[30.0, 40.0, 50.0]));
+        assert_eq!(analysis.http_ws_ratio(), Some(2.0));
+        assert_eq!(analysis.proxy_overhead(), Some(70.0));
+
+        let analysis = LatencyAnalysis::new(&result, None);
+        assert_eq!(analysis.proxy_overhead(), None);
+
+        let no_ws = DetectionResult {
+            image_latencies: vec![100.0],
+            ..Default::default()
+        };
+        assert_eq!(LatencyAnalysis::new(&no_ws, None).http_ws_ratio(), None);
+
+        let zero_ws = DetectionResult {
+            image_latencies: vec![100.0],
+            ws_latencies: vec![0.0],
+            ..Default::default()
+        };
+        assert_eq!(LatencyAnalysis::new(&zero_ws, None).http_ws_ratio(), None);
+    }
+}
@@ -1,18 +1,33 @@
+mod auto_preset;
+mod baseline;
 mod config;
+mod latency;
 mod payload;
+mod repeat;
 mod result;
+mod timing;
 
 use crate::browser::{
-    beacon_headers, get_preset, image_headers, poll_headers, script_headers, websocket_ping_pong,
-    Preset, WsLatencyResult,
+    beacon_headers, get_preset, image_headers, poll_headers, sample_hardware, script_headers,
+    websocket_ping_pong, Preset, WsLatencyResult, WS_ROUNDS,
 };
+use crate::locale::Locale;
+use crate::meter::{Relay, Traffic};
 use crate::timezone;
+use serde_json::Value;
+use std::collections::HashSet;
 use std::time::{Duration, Instant};
+use timing::Timeline;
 use wreq_util::tower::delay::JitterDelayLayer;
 
+pub use auto_preset::{run_auto_preset, OsCache, PresetAttempt};
+pub use baseline::BaselineDelta;
 pub use config::{parse_config, ServerConfig};
+pub use latency::{ChannelStats, LatencyAnalysis};
 pub use payload::{build_payload, ClientPayload};
-pub use result::{parse_result, DetectionResult};
+pub use repeat::{BlockStats, RepeatSummary};
+pub use result::{parse_result, test_detected, DetectionResult, ScanError, TrafficReport};
+pub use timing::TimingSpan;
 
 const ENGINE_ENDPOINT: &str = "https://engine.proxydetect.live";
 const TELEMETRY_JITTER_BASE_MS: u64 = 350;
@@ -23,29 +38,149 @@ const POLL_INTERVALS: &[u64] = &[
     0, 200, 400, 650, 900, 1200, 1600, 2100, 2700, 3500, 4500, 6000, 8000, 10000, 12000,
 ];
 
+/// Image probes a browser sends by default.
+const IMAGE_PROBES: usize = 3;
+
 /// Approximate HTTP overhead per request (headers, TLS record framing).
 const HTTP_OVERHEAD_PER_REQUEST: u64 = 500;
 
 /// Detection run options.
+#[derive(Clone)]
 pub struct Options {
     pub proxy_url: Option<String>,
     pub browser_name: String,
     pub timezone_iana: Option<String>,
+    /// BCP-47 browser locale, or "auto" to derive it from the exit IP country.
+    pub locale: Option<String>,
+    /// Sample a hardware profile from this seed instead of the preset defaults.
+    pub hardware_seed: Option<u64>,
+    /// Send high-entropy client hints on Chromium presets.
+    pub high_entropy_hints: bool,
+    /// Fingerprint property overrides (`key`, `value`).
+    pub fingerprint_overrides: Vec<(String, String)>,
+    /// Wall clock for Date/time payload fields.
+    pub clock: timezone::Clock,
+    pub protocol: ProtocolOptions,
+    /// Look the exit location up even with a timezone override, so the ASN is known.
+    pub resolve_asn: bool,
     pub verbose: bool,
     pub json_output: bool,
 }
 
+/// Probe counts and poll schedule of the detection protocol; the defaults match a
+/// browser visit. Fewer probes and an earlier poll deadline save time and bandwidth
+/// at the cost of latency accuracy and completeness.
+#[derive(Clone, Debug)]
+pub struct ProtocolOptions {
+    pub image_probes: usize,
+    /// Run the WebSocket phase; without it image RTTs stand in for WS latencies.
+    pub websocket: bool,
+    pub ws_rounds: usize,
+    pub poll_schedule: PollSchedule,
+    /// Stop polling after this long and return whatever tests have completed.
+    pub poll_deadline: Option<Duration>,
+    /// Bytes a scan may use: past it the remaining image probes, the WebSocket
+    /// phase and further polls are skipped, and a scan that has not submitted
+    /// telemetry yet is aborted.
+    pub byte_budget: Option<u64>,
+}
+
+impl Default for ProtocolOptions {
+    fn default() -> Self {
+        ProtocolOptions {
+            image_probes: IMAGE_PROBES,
+            websocket: true,
+            ws_rounds: WS_ROUNDS,
+            poll_schedule: PollSchedule::Backoff,
+            poll_deadline: None,
+            byte_budget: None,
+        }
+    }
+}
+
+/// Delays between result checks.
+#[derive(Clone, Copy, Debug)]
+pub enum PollSchedule {
+    /// `POLL_INTERVALS`, then every 12s.
+    Backoff,
+    /// Fixed interval, for the same overall time budget as `Backoff`.
+    Every(Duration),
+}
+
+impl PollSchedule {
+    /// Delays in milliseconds before each check.
+    fn delays(&self) -> Vec<u64> {
+        let mut backoff: Vec<u64> = POLL_INTERVALS.to_vec();
+        backoff.extend(std::iter::repeat_n(12000, 10));
+
+        match self {
+            PollSchedule::Backoff => backoff,
+            PollSchedule::Every(interval) => {
+                let interval_ms = (interval.as_millis() as u64).max(1);
+                let checks = backoff.iter().sum::<u64>() / interval_ms;
+                std::iter::once(0)
+                    .chain(std::iter::repeat_n(interval_ms, checks as usize))
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Browser identity a scan starts from: preset, explicit locale, sampled hardware
+/// and fingerprint overrides. An "auto" locale is resolved later from the exit IP.
+pub fn build_preset(opts: &Options) -> Result<Preset, Box<dyn std::error::Error + Send + Sync>> {
+    let mut preset = get_preset(&opts.browser_name);
+    preset.high_entropy_hints = opts.high_entropy_hints;
+
+    if let Some(tag) = opts.locale.as_deref().filter(|tag| *tag != "auto") {
+        preset.locale = Locale::parse(tag)?;
+    }
+    if let Some(seed) = opts.hardware_seed {
+        preset.hardware = Some(sample_hardware(&preset, seed));
+    }
+    preset.fingerprint_overrides = opts.fingerprint_overrides.clone();
+
+    Ok(preset)
+}
+
 /// Execute the full 4-phase detection protocol.
 pub async fn run(
     opts: &Options,
     log: impl Fn(&str),
 ) -> Result<DetectionResult, Box<dyn std::error::Error + Send + Sync>> {
-    let preset = get_preset(&opts.browser_name);
+    run_streaming(opts, log, |_, _| {}).await
+}
+
+/// Like [`run`], calling `on_test` with the key and raw entry of each individual
+/// test as soon as a poll response first reports it.
+pub async fn run_streaming(
+    opts: &Options,
+    log: impl Fn(&str),
+    on_test: impl Fn(&str, &Value),
+) -> Result<DetectionResult, Box<dyn std::error::Error + Send + Sync>> {
+    let mut preset = build_preset(opts)?;
     let start_time = Instant::now();
+    let timeline = Timeline::new(start_time);
     let mut total_bytes: u64 = 0;
 
+    let locale_auto = opts.locale.as_deref() == Some("auto");
+
     log(&format!("Using browser preset: {}", preset.name));
 
+    if let Some(ref hardware) = preset.hardware {
+        log(&format!(
+            "  Hardware: {} (seed {})",
+            hardware.summary(),
+            hardware.seed
+        ));
+    }
+    for (key, value) in &preset.fingerprint_overrides {
+        log(&format!("  Fingerprint override: {}={}", key, value));
+    }
+    if let timezone::Clock::Fixed(at) = opts.clock {
+        log(&format!("  Simulated time: {}", at.to_rfc3339()));
+    }
+
     let telemetry_jitter = JitterDelayLayer::new(
         Duration::from_millis(TELEMETRY_JITTER_BASE_MS),
         TELEMETRY_JITTER_PCT,
@@ -58,87 +193,240 @@ pub async fn run(
         .emulation(preset.emulation)
         .layer(telemetry_jitter);
 
+    // Route the client through a local relay that counts the bytes on the wire;
+    // TLS proxies cannot be relayed and fall back to estimated bandwidth.
+    let relay = match Relay::start(opts.proxy_url.as_deref()).await {
+        Ok(relay) => Some(relay),
+        Err(e) => {
+            log(&format!(
+                "WARNING: Byte metering unavailable ({}), estimating bandwidth",
+                e
+            ));
+            None
+        }
+    };
+    let metered = || relay.as_ref().map(Relay::traffic).unwrap_or_default();
+    // Bytes used so far: the estimate, or the relay's count plus finished WebSocket bytes.
+    let spent = |estimated: u64, ws_bytes: u64| {
+        relay
+            .as_ref()
+            .map_or(estimated, |relay| relay.traffic().total() + ws_bytes)
+    };
+    let over_budget = |used: u64| opts.protocol.byte_budget.is_some_and(|limit| used >= limit);
+    // A scan that fails part-way has still used bandwidth; report it with the error.
+    let failed =
+        |source: Box<dyn std::error::Error + Send + Sync>, estimated: u64, ws_bytes: u64| {
+            Box::new(ScanError {
+                source,
+                bandwidth_bytes: spent(estimated, ws_bytes),
+                measured: relay.is_some(),
+            }) as Box<dyn std::error::Error + Send + Sync>
+        };
+    let mut budget_skipped = Vec::new();
+
     if let Some(ref proxy) = opts.proxy_url {
-        builder = builder.proxy(wreq::Proxy::all(proxy)?);
+        let route = relay.as_ref().map_or(proxy.as_str(), Relay::proxy_url);
+        builder = builder.proxy(wreq::Proxy::all(route)?);
         log(&format!("Routing through proxy: {}", proxy));
+    } else if let Some(ref relay) = relay {
+        builder = builder.proxy(wreq::Proxy::all(relay.proxy_url())?);
     }
 
     let client = builder.build()?;
 
+    // An "auto" locale must be resolved before the first engine request so every
+    // request carries its Accept-Language; look the exit up through the proxy, if any.
+    let mut early_location = None;
+    if locale_auto {
+        log("  Resolving exit location...");
+        let lookup_started = Instant::now();
+        let lookup = timezone::lookup_exit(opts.proxy_url.is_some().then_some(&client)).await;
+        timeline.record("locale", lookup_started);
+        match lookup {
+            Ok((ip, ref loc)) if !loc.country_code.is_empty() => {
+                preset.locale = Locale::for_country(&loc.country_code);
+                log(&format!(
+                    "  Locale: {} (country {})",
+                    preset.locale.tag, loc.country_code
+                ));
+                early_location = Some((ip, loc.clone()));
+            }
+            Ok(_) => log(&format!(
+                "WARNING: Exit country unknown, keeping locale {}",
+                preset.locale.tag
+            )),
+            Err(e) => log(&format!(
+                "WARNING: Location lookup failed ({}), keeping locale {}",
+                e, preset.locale.tag
+            )),
+        }
+    }
+
     log("Initializing session...");
-    let (cfg, p1_bytes) = phase1_fetch_config(&client, &preset, &log).await?;
+    // Page timings start with the script fetch, as in a browser.
+    let page_start = Instant::now();
+    let (cfg, p1_bytes) = phase1_fetch_config(&client, &preset, &log)
+        .await
+        .map_err(|e| failed(e, total_bytes, 0))?;
+    timeline.record("config", page_start);
     total_bytes += p1_bytes;
-    let loaded_ms = start_time.elapsed().as_millis() as f64;
+    let after_config = metered();
+    let loaded_ms = page_start.elapsed().as_millis() as f64;
     log(&format!("  UUID: {}", cfg.uuid));
     log(&format!("  Exit IP: {}", cfg.rip));
 
+    let location = match early_location {
+        Some((ip, location)) if ip == cfg.rip => Some(location),
+        early => {
+            // A rotating proxy may have switched exits since the locale lookup.
+            if let Some((ip, _)) = early {
+                log(&format!(
+                    "WARNING: Exit IP changed since the locale lookup ({} -> {})",
+                    ip, cfg.rip
+                ));
+            }
+            if opts.timezone_iana.is_none() || locale_auto || opts.resolve_asn {
+                log("  Resolving exit location...");
+                let lookup_started = Instant::now();
+                let lookup = timezone::lookup_from_ip(&cfg.rip).await;
+                timeline.record("timezone", lookup_started);
+                match lookup {
+                    Ok(location) => {
+                        // The early lookup failed or saw another exit, so the
+                        // locale still has to follow this exit's country.
+                        if locale_auto && !location.country_code.is_empty() {
+                            preset.locale = Locale::for_country(&location.country_code);
+                            log(&format!(
+                                "  Locale: {} (country {})",
+                                preset.locale.tag, location.country_code
+                            ));
+                        }
+                        Some(location)
+                    }
+                    Err(e) => {
+                        log(&format!("WARNING: Location lookup failed ({})", e));
+                        None
+                    }
+                }
+            } else {
+                None
+            }
+        }
+    };
+
     let tz_info = if let Some(ref iana) = opts.timezone_iana {
         log(&format!("  Override timezone: {}", iana));
-        timezone::resolve(iana)?
+        timezone::resolve(iana, &preset.locale, &opts.clock)
+            .map_err(|e| failed(e, total_bytes, 0))?
     } else {
-        log("  Resolving timezone...");
-        let iana = match timezone::lookup_from_ip(&cfg.rip).await {
-            Ok(tz) => {
-                log(&format!("  Timezone: {}", tz));
-                tz
+        let iana = match location {
+            Some(ref loc) => {
+                log(&format!("  Timezone: {}", loc.timezone));
+                loc.timezone.clone()
+            }
+            None => {
+                log("WARNING: Timezone unavailable, falling back to UTC");
+                "UTC".to_string()
             }
+        };
+        match timezone::resolve(&iana, &preset.locale, &opts.clock) {
+            Ok(info) => info,
             Err(e) => {
                 log(&format!(
-                    "WARNING: Timezone lookup failed ({}), falling back to UTC",
-                    e
+                    "WARNING: Unknown timezone {} ({}), falling back to UTC",
+                    iana, e
                 ));
-                "UTC".to_string()
+                timezone::resolve("UTC", &preset.locale, &opts.clock)?
             }
-        };
-        timezone::resolve(&iana).unwrap_or_else(|_| timezone::resolve("UTC").unwrap())
+        }
     };
+    if tz_info.windows_zone.is_none() {
+        log(&format!(
+            "WARNING: {} has no Windows timezone; keeping its offset and sending the IANA name as zone",
+            tz_info.iana_name
+        ));
+    }
 
+    let protocol = &opts.protocol;
     log("Measuring latencies...");
-    let ws_uuid = cfg.uuid.clone();
-    let ws_handle = tokio::spawn(async move { websocket_ping_pong(&ws_uuid).await });
+    let ws_over_budget = protocol.websocket && over_budget(spent(total_bytes, 0));
+    if ws_over_budget {
+        log("WARNING: Bandwidth budget reached, skipping the WebSocket phase");
+        budget_skipped.push("websocket".to_string());
+    }
+    let ws_handle = (protocol.websocket && !ws_over_budget).then(|| {
+        let ws_uuid = cfg.uuid.clone();
+        let rounds = protocol.ws_rounds;
+        let started = Instant::now();
+        let task = tokio::spawn(async move {
+            let result = websocket_ping_pong(&ws_uuid, rounds).await;
+            (result, Instant::now())
+        });
+        (task, started)
+    });
 
-    let (image_latencies, p2_bytes) = phase2_image_probes(&client, &preset, &log).await;
+    let before_images = total_bytes;
+    let (image_latencies, p2_bytes) = phase2_image_probes(
+        &client,
+        &preset,
+        protocol.image_probes,
+        &log,
+        &timeline,
+        |phase_bytes| over_budget(spent(before_images + phase_bytes, 0)),
+    )
+    .await;
     total_bytes += p2_bytes;
+    if image_latencies.len() < protocol.image_probes {
+        budget_skipped.push("images".to_string());
+    }
+    let after_images = metered();
     let formatted_images: Vec<String> = image_latencies
         .iter()
         .map(|l| format!("{:.0}", l))
         .collect();
     log(&format!("  Image RTTs: [{}]", formatted_images.join(", ")));
 
-    let ws_result: WsLatencyResult = match ws_handle.await {
-        Ok(Ok(result)) => {
-            log(&format!(
-                "  WebSocket: {} samples captured",
-                result.latencies.len()
-            ));
-            if !result.latencies.is_empty() {
-                let formatted: Vec<String> = result
-                    .latencies
-                    .iter()
-                    .map(|l| format!("{:.2}", l))
-                    .collect();
-                log(&format!("  WS RTTs: [{}]", formatted.join(", ")));
-            }
-            result
+    let skipped = WsLatencyResult {
+        latencies: vec![],
+        bytes_sent: 0,
+        bytes_received: 0,
+    };
+    let ws_result: WsLatencyResult = match ws_handle {
+        None if ws_over_budget => skipped,
+        None => {
+            log("  WebSocket phase skipped");
+            skipped
         }
-        Ok(Err(e)) => {
-            log(&format!("WebSocket ping-pong failed: {}", e));
-            WsLatencyResult {
-                latencies: vec![],
-                bytes_sent: 0,
-                bytes_received: 0,
+        Some((handle, ws_started)) => match handle.await {
+            Ok((Ok(result), ended)) => {
+                timeline.record_until("websocket", ws_started, ended);
+                log(&format!(
+                    "  WebSocket: {} samples captured",
+                    result.latencies.len()
+                ));
+                if !result.latencies.is_empty() {
+                    let formatted: Vec<String> = result
+                        .latencies
+                        .iter()
+                        .map(|l| format!("{:.2}", l))
+                        .collect();
+                    log(&format!("  WS RTTs: [{}]", formatted.join(", ")));
+                }
+                result
             }
-        }
-        Err(_) => {
-            log("WebSocket ping-pong task panicked");
-            WsLatencyResult {
-                latencies: vec![],
-                bytes_sent: 0,
-                bytes_received: 0,
+            Ok((Err(e), ended)) => {
+                timeline.record_until("websocket", ws_started, ended);
+                log(&format!("WebSocket ping-pong failed: {}", e));
+                skipped
             }
-        }
+            Err(_) => {
+                log("WebSocket ping-pong task panicked");
+                skipped
+            }
+        },
     };
-    total_bytes += ws_result.bytes_sent + ws_result.bytes_received;
+    let ws_bytes = ws_result.bytes_sent + ws_result.bytes_received;
+    total_bytes += ws_bytes;
 
     let ws_latencies_for_payload: Vec<f64> = if ws_result.latencies.is_empty() {
         log("  WebSocket latencies unavailable; using image RTTs as fallback");
@@ -147,31 +435,120 @@ pub async fn run(
         ws_result.latencies.clone()
     };
 
-    let elapsed_ms = start_time.elapsed().as_millis() as f64;
+    if let Some(limit) = protocol.byte_budget {
+        let used = spent(total_bytes, ws_bytes);
+        if used >= limit {
+            return Err(failed(
+                format!(
+                    "bandwidth budget of {} KB exhausted before telemetry ({} KB used)",
+                    limit / 1024,
+                    used / 1024
+                )
+                .into(),
+                total_bytes,
+                ws_bytes,
+            ));
+        }
+    }
+
+    let elapsed_ms = page_start.elapsed().as_millis() as f64;
     log("Submitting telemetry...");
     let payload = build_payload(
         &cfg,
         &preset,
         &tz_info,
+        &opts.clock,
         &image_latencies,
         &ws_latencies_for_payload,
         loaded_ms,
         elapsed_ms,
     );
-    let p3_bytes = phase3_submit_telemetry(&client, &preset, &payload, &log).await?;
+    let p3_bytes = phase3_submit_telemetry(&client, &preset, &payload, &log, &timeline)
+        .await
+        .map_err(|e| failed(e, total_bytes, ws_bytes))?;
     total_bytes += p3_bytes;
+    let after_telemetry = metered();
 
     log("Waiting for analysis results...");
-    let (mut result, p4_bytes) = phase4_poll(&client, &preset, &cfg.uuid, &log).await?;
+    let before_poll = total_bytes;
+    let (mut result, p4_bytes) = phase4_poll(
+        &client,
+        &preset,
+        &cfg.uuid,
+        protocol,
+        &log,
+        &on_test,
+        &timeline,
+        |phase_bytes| over_budget(spent(before_poll + phase_bytes, ws_bytes)),
+    )
+    .await
+    .map_err(|e| failed(e, total_bytes, ws_bytes))?;
     total_bytes += p4_bytes;
+    let after_poll = metered();
     result.exit_ip = cfg.rip;
+    result.preset = preset.name.to_string();
+    result.locale = preset.locale.tag.clone();
+    result.hardware = preset.hardware.clone();
+    result.timezone = tz_info.iana_name.clone();
+    result.asn = location.map(|loc| loc.asn).unwrap_or_default();
+    result.image_latencies = image_latencies;
+    result.ws_latencies = ws_result.latencies;
+    budget_skipped.append(&mut result.budget_skipped);
+    result.budget_skipped = budget_skipped;
+    result.timings = timeline.into_spans();
+    if relay.is_some() {
+        let traffic = TrafficReport {
+            config: after_config,
+            images: after_images - after_config,
+            telemetry: after_telemetry - after_images,
+            poll: after_poll - after_telemetry,
+            websocket: Traffic {
+                sent: ws_result.bytes_sent,
+                received: ws_result.bytes_received,
+            },
+        };
+        total_bytes = traffic.total().total();
+        result.traffic = Some(traffic);
+    }
     result.bandwidth_bytes = total_bytes;
 
-    log(&format!("Bandwidth: {:.1} KB", total_bytes as f64 / 1024.0));
+    log(&format!(
+        "Bandwidth: {:.1} KB ({})",
+        total_bytes as f64 / 1024.0,
+        if relay.is_some() {
+            "measured"
+        } else {
+            "estimated"
+        }
+    ));
 
     Ok(result)
 }
 
+/// Image probe RTTs over a direct connection, as a baseline for the proxy overhead.
+pub async fn measure_direct_latencies(
+    opts: &Options,
+    log: impl Fn(&str),
+) -> Result<Vec<f64>, Box<dyn std::error::Error + Send + Sync>> {
+    let preset = build_preset(opts)?;
+    let client = wreq::Client::builder()
+        .emulation(preset.emulation)
+        .build()?;
+
+    log("Measuring direct baseline latencies...");
+    let timeline = Timeline::new(Instant::now());
+    let (latencies, _) = phase2_image_probes(
+        &client,
+        &preset,
+        opts.protocol.image_probes,
+        &log,
+        &timeline,
+        |_| false,
+    )
+    .await;
+    Ok(latencies)
+}
+
 async fn phase1_fetch_config(
     client: &wreq::Client,
     preset: &Preset,
@@ -190,14 +567,25 @@ async fn phase1_fetch_config(
 async fn phase2_image_probes(
     client: &wreq::Client,
     preset: &Preset,
+    image_count: usize,
     log: impl Fn(&str),
+    timeline: &Timeline,
+    over_budget: impl Fn(u64) -> bool,
 ) -> (Vec<f64>, u64) {
-    let image_count = 3;
     let mut latencies = Vec::with_capacity(image_count);
     let headers = image_headers(preset);
     let mut bytes: u64 = 0;
 
     for idx in 0..image_count {
+        // Keep at least one probe so the payload has an RTT to report.
+        if idx > 0 && over_budget(bytes) {
+            log(&format!(
+                "WARNING: Bandwidth budget reached, skipping {} image probe(s)",
+                image_count - idx
+            ));
+            break;
+        }
+
         let random_str = format!("{:x}", rand::random::<u64>());
         let url = format!(
             "{}/images/small.png?n={}&r={}",
@@ -211,10 +599,12 @@ async fn phase2_image_probes(
         match result {
             Ok(resp) => {
                 let body = resp.bytes().await.unwrap_or_default();
+                timeline.record(format!("image #{}", idx + 1), start);
                 bytes += HTTP_OVERHEAD_PER_REQUEST + body.len() as u64;
                 log(&format!("  Probe {}: {}ms", idx + 1, rtt as i64));
             }
             Err(e) => {
+                timeline.record(format!("image #{}", idx + 1), start);
                 bytes += HTTP_OVERHEAD_PER_REQUEST; // Count request even on failure
                 log(&format!(
                     "Image probe {} failed: {} (using synthetic latency)",
@@ -233,6 +623,7 @@ async fn phase3_submit_telemetry(
     preset: &Preset,
     payload: &ClientPayload,
     log: impl Fn(&str),
+    timeline: &Timeline,
 ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
     let payload_json = serde_json::to_string(payload)?;
     let payload_len = payload_json.len() as u64;
@@ -242,6 +633,7 @@ async fn phase3_submit_telemetry(
 
     for attempt in 0..max_attempts {
         let headers = beacon_headers(preset);
+        let started = Instant::now();
         let resp = client
             .post(&url)
             .headers(headers)
@@ -252,6 +644,7 @@ async fn phase3_submit_telemetry(
         let status = resp.status();
         let body = resp.text().await.unwrap_or_default();
         let response_len = body.len() as u64;
+        timeline.record(format!("telemetry #{}", attempt + 1), started);
         log(&format!("  Server response: status {}", status));
 
         if status.as_u16() >= 500 && attempt < max_attempts - 1 {
@@ -278,38 +671,69 @@ async fn phase3_submit_telemetry(
     Err("telemetry submission failed after all retry attempts".into())
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn phase4_poll(
     client: &wreq::Client,
     preset: &Preset,
     uuid: &str,
+    protocol: &ProtocolOptions,
     log: impl Fn(&str),
+    on_test: impl Fn(&str, &Value),
+    timeline: &Timeline,
+    over_budget: impl Fn(u64) -> bool,
 ) -> Result<(DetectionResult, u64), Box<dyn std::error::Error + Send + Sync>> {
     let url = format!("{}/i?&uuid={}", ENGINE_ENDPOINT, uuid);
     let headers = poll_headers(preset);
     let mut bytes: u64 = 0;
 
-    let mut schedule: Vec<u64> = POLL_INTERVALS.to_vec();
-    schedule.extend(std::iter::repeat_n(12000, 10));
+    let schedule = protocol.poll_schedule.delays();
+    let deadline = protocol.poll_deadline.map(|limit| Instant::now() + limit);
 
     let mut last_result = DetectionResult::default();
+    let mut reported: HashSet<String> = HashSet::new();
+
+    let mut deadline_reached = false;
 
     for (idx, delay_ms) in schedule.iter().enumerate() {
-        if *delay_ms > 0 {
-            tokio::time::sleep(Duration::from_millis(*delay_ms)).await;
+        if deadline_reached {
+            log("WARNING: Poll deadline reached, returning partial results");
+            return Ok((last_result, bytes));
         }
 
-        log(&format!("  check #{} ({}ms)...", idx + 1, delay_ms));
+        // The last check lands on the deadline rather than past it.
+        let mut delay = Duration::from_millis(*delay_ms);
+        if let Some(deadline) = deadline {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if delay >= remaining {
+                delay = remaining;
+                deadline_reached = true;
+            }
+        }
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
 
+        log(&format!(
+            "  check #{} ({}ms)...",
+            idx + 1,
+            delay.as_millis()
+        ));
+
+        let started = Instant::now();
+        let span = format!("poll #{}", idx + 1);
         let resp = match client.get(&url).headers(headers.clone()).send().await {
             Ok(r) => r,
             Err(e) => {
+                timeline.record(span, started);
                 log(&format!("Poll request failed: {}", e));
                 bytes += HTTP_OVERHEAD_PER_REQUEST;
                 continue;
             }
         };
 
-        let body = match resp.text().await {
+        let body = resp.text().await;
+        timeline.record(span, started);
+        let body = match body {
             Ok(b) => b,
             Err(e) => {
                 log(&format!("Reading poll response failed: {}", e));
@@ -328,6 +752,12 @@ async fn phase4_poll(
             }
         };
 
+        for (key, raw) in result.individual_tests().into_iter().flatten() {
+            if reported.insert(key.clone()) {
+                on_test(key, raw);
+            }
+        }
+
         last_result = result;
         if last_result.finished {
             log(&format!(
@@ -344,6 +774,11 @@ async fn phase4_poll(
         if last_result.finished {
             return Ok((last_result, bytes));
         }
+        if over_budget(bytes) {
+            log("WARNING: Bandwidth budget reached, returning partial results");
+            last_result.budget_skipped.push("poll".to_string());
+            return Ok((last_result, bytes));
+        }
     }
 
     log("WARNING: Poll schedule exhausted, returning partial results");
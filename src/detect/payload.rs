@@ -1,6 +1,6 @@
 use super::ServerConfig;
-use crate::browser::{compute_fingerprint, Preset};
-use crate::timezone::Info as TzInfo;
+use crate::browser::{compute_fingerprint, MachineCapabilities, Preset};
+use crate::timezone::{Clock, Info as TzInfo};
 use serde::Serialize;
 
 /// Client telemetry payload for POST /s.
@@ -49,6 +49,8 @@ pub struct TZDetails {
     pub valid: TZValid,
     pub date: String,
     pub time: String,
+    /// Windows timezone ID, or the IANA name for zones without one; always sent,
+    /// since a missing key is itself a tell.
     pub zone: String,
     pub reported_offset: i32,
     pub computed_offset: i32,
@@ -94,11 +96,29 @@ pub struct MachineData {
     pub gpu: bool,
 }
 
-/// Build the client telemetry payload for POST /s.
+impl From<&MachineCapabilities> for MachineData {
+    fn from(caps: &MachineCapabilities) -> Self {
+        MachineData {
+            ua_identifiers: caps.ua_identifiers,
+            core: caps.core,
+            system: caps.system,
+            device: caps.device,
+            platform: caps.platform,
+            speech_synthesis: caps.speech_synthesis,
+            device_memory: caps.device_memory,
+            hardware_concurrency: caps.hardware_concurrency,
+            gpu: caps.gpu,
+        }
+    }
+}
+
+/// Build the client telemetry payload for POST /s, timestamped by `clock`.
+#[allow(clippy::too_many_arguments)]
 pub fn build_payload(
     cfg: &ServerConfig,
     preset: &Preset,
     tz_info: &TzInfo,
+    clock: &Clock,
     image_latencies: &[f64],
     ws_latencies: &[f64],
     loaded_ms: f64,
@@ -112,7 +132,7 @@ pub fn build_payload(
         location: "https://proxydetect.live/".to_string(),
         user_agent: preset.user_agent.to_string(),
         time: TimeData {
-            timestamp: tz_info.timestamp_millis,
+            timestamp: clock.now().timestamp_millis(),
             time_str: tz_info.date_string.clone(),
             time_zone: tz_info.iana_name.clone(),
         },
@@ -139,7 +159,10 @@ pub fn build_payload(
             },
             date: tz_info.date_string.clone(),
             time: tz_info.time_string.clone(),
-            zone: tz_info.windows_zone.clone(),
+            zone: tz_info
+                .windows_zone
+                .clone()
+                .unwrap_or_else(|| tz_info.iana_name.clone()),
             reported_offset: tz_info.offset_minutes,
             computed_offset: tz_info.offset_minutes,
             reported_location: tz_info.iana_name.clone(),
@@ -151,9 +174,9 @@ pub fn build_payload(
             finish_event: "notSupported".to_string(),
             elapsed: 0.0,
         },
-        machine: MachineData::default(),
+        machine: MachineData::from(&preset.machine),
         image_latencies: image_latencies.to_vec(),
         ws_latencies: ws_latencies.to_vec(),
-        fp: compute_fingerprint(preset.name),
+        fp: compute_fingerprint(preset),
     }
 }
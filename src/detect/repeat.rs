@@ -0,0 +1,241 @@
+use super::result::test_detected;
+use super::DetectionResult;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Positive count for one individual test across repeated scans.
+#[derive(Clone, Debug, Default)]
+pub struct TestStats {
+    pub name: String,
+    pub positive: usize,
+    /// Scans that reported a proxy/VPN verdict for this test.
+    pub observed: usize,
+}
+
+impl TestStats {
+    pub fn rate(&self) -> f64 {
+        if self.observed == 0 {
+            0.0
+        } else {
+            self.positive as f64 / self.observed as f64
+        }
+    }
+
+    /// Whether the verdict changed between scans.
+    pub fn flaky(&self) -> bool {
+        self.positive > 0 && self.positive < self.observed
+    }
+
+    fn agreeing(&self) -> usize {
+        self.positive.max(self.observed - self.positive)
+    }
+}
+
+/// Detection count for an aggregate `proxy`/`vpn` block across repeated scans.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BlockStats {
+    pub detected: usize,
+    pub observed: usize,
+}
+
+impl BlockStats {
+    /// Strict majority of the scans that carried the block flagged it.
+    pub fn majority(&self) -> bool {
+        self.detected * 2 > self.observed
+    }
+}
+
+/// Statistics over N independent scans of the same proxy.
+#[derive(Clone, Debug, Default)]
+pub struct RepeatSummary {
+    pub scans: usize,
+    pub errors: Vec<String>,
+    /// Distinct exit IPs seen, in order of appearance.
+    pub exit_ips: Vec<String>,
+    pub proxy: BlockStats,
+    pub vpn: BlockStats,
+    /// Per-test statistics keyed by test name (`http_headers`, `proxy_ai`, ...).
+    pub tests: BTreeMap<String, TestStats>,
+}
+
+impl RepeatSummary {
+    pub fn from_results(results: &[Result<DetectionResult, String>]) -> RepeatSummary {
+        let mut summary = RepeatSummary {
+            scans: results.len(),
+            ..Default::default()
+        };
+
+        for result in results {
+            let result = match result {
+                Ok(r) => r,
+                Err(e) => {
+                    summary.errors.push(e.clone());
+                    continue;
+                }
+            };
+
+            if !result.exit_ip.is_empty() && !summary.exit_ips.contains(&result.exit_ip) {
+                summary.exit_ips.push(result.exit_ip.clone());
+            }
+            tally_block(&mut summary.proxy, result.tests.get("proxy"), "isProxy");
+            tally_block(&mut summary.vpn, result.tests.get("vpn"), "isVpn");
+
+            for (key, raw) in result.individual_tests().into_iter().flatten() {
+                let Some(detected) = test_detected(raw) else {
+                    continue;
+                };
+
+                let stats = summary.tests.entry(key.clone()).or_default();
+                if stats.name.is_empty() {
+                    stats.name = raw
+                        .get("name")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or(key)
+                        .to_string();
+                }
+                stats.observed += 1;
+                if detected {
+                    stats.positive += 1;
+                }
+            }
+        }
+
+        summary
+    }
+
+    /// Share of per-test observations that agree with that test's majority
+    /// verdict: 1.0 means every test gave the same answer on every scan.
+    pub fn stability(&self) -> Option<f64> {
+        let observed: usize = self.tests.values().map(|t| t.observed).sum();
+        if observed == 0 {
+            return None;
+        }
+        let agreeing: usize = self.tests.values().map(TestStats::agreeing).sum();
+        Some(agreeing as f64 / observed as f64)
+    }
+
+    /// Keys of tests whose verdict changed between scans.
+    pub fn flaky_tests(&self) -> Vec<&str> {
+        self.tests
+            .iter()
+            .filter(|(_, stats)| stats.flaky())
+            .map(|(key, _)| key.as_str())
+            .collect()
+    }
+}
+
+fn tally_block(block: &mut BlockStats, raw: Option<&Value>, flag: &str) {
+    if let Some(raw) = raw {
+        block.observed += 1;
+        if raw.get(flag).and_then(|v| v.as_bool()).unwrap_or(false) {
+            block.detected += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn scan(exit_ip: &str, is_proxy: bool, tests: Value) -> Result<DetectionResult, String> {
+        let mut result = DetectionResult {
+            exit_ip: exit_ip.to_string(),
+            ..Default::default()
+        };
+        result
+            .tests
+            .insert("proxy".to_string(), json!({ "isProxy": is_proxy }));
+        result.tests.insert("tests".to_string(), tests);
+        Ok(result)
+    }
+
+    #[test]
+    fn majority_needs_more_than_half() {
+        let tie = BlockStats {
+            detected: 2,
+            observed: 4,
+        };
+        assert!(!tie.majority());
+        let majority = BlockStats {
+            detected: 3,
+            observed: 4,
+        };
+        assert!(majority.majority());
+        assert!(!BlockStats::default().majority());
+    }
+
+    #[test]
+    fn counts_only_scans_that_completed() {
+        let results = vec![
+            scan("1.2.3.4", true, json!({"datacenter": {"is_proxy": true}})),
+            Err("timed out".to_string()),
+            scan("1.2.3.4", false, json!({"datacenter": {"is_proxy": false}})),
+            Err("connection reset".to_string()),
+        ];
+        let summary = RepeatSummary::from_results(&results);
+
+        assert_eq!(summary.scans, 4);
+        assert_eq!(summary.errors, vec!["timed out", "connection reset"]);
+        assert_eq!(summary.exit_ips, vec!["1.2.3.4"]);
+        assert_eq!(summary.proxy.observed, 2);
+        assert_eq!(summary.proxy.detected, 1);
+        assert!(!summary.proxy.majority());
+        // No scan carried a `vpn` block.
+        assert_eq!(summary.vpn.observed, 0);
+        assert_eq!(summary.tests["datacenter"].observed, 2);
+    }
+
+    #[test]
+    fn flags_tests_whose_verdict_changed() {
+        let results = vec![
+            scan(
+                "1.2.3.4",
+                true,
+                json!({
+                    "datacenter": {"name": "Datacenter IP", "is_proxy": true},
+                    "latency": {"is_proxy": false, "is_vpn": false},
+                    "webrtc": {"info": {}},
+                }),
+            ),
+            scan(
+                "5.6.7.8",
+                true,
+                json!({
+                    "datacenter": {"is_proxy": true},
+                    "latency": {"is_vpn": true},
+                }),
+            ),
+        ];
+        let summary = RepeatSummary::from_results(&results);
+
+        assert_eq!(summary.exit_ips, vec!["1.2.3.4", "5.6.7.8"]);
+        assert_eq!(summary.tests["datacenter"].name, "Datacenter IP");
+        assert_eq!(summary.tests["latency"].name, "latency");
+        assert!(!summary.tests.contains_key("webrtc"));
+        assert_eq!(summary.flaky_tests(), vec!["latency"]);
+        assert_eq!(summary.tests["latency"].rate(), 0.5);
+    }
+
+    #[test]
+    fn stability_is_the_share_of_agreeing_observations() {
+        let mut summary = RepeatSummary::default();
+        assert_eq!(summary.stability(), None);
+
+        for (key, positive, observed) in
+            [("datacenter", 4, 4), ("latency", 1, 4), ("proxy_ai", 0, 2)]
+        {
+            summary.tests.insert(
+                key.to_string(),
+                TestStats {
+                    name: key.to_string(),
+                    positive,
+                    observed,
+                },
+            );
+        }
+        // 4 + 3 + 2 of 10 observations agree with their test's majority.
+        assert_eq!(summary.stability(), Some(0.9));
+        assert_eq!(summary.flaky_tests(), vec!["latency"]);
+    }
+}
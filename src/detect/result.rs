@@ -1,4 +1,9 @@
+use super::{PresetAttempt, TimingSpan};
+use crate::browser::HardwareProfile;
+use crate::meter::Traffic;
 use serde_json::{Map, Value};
+use std::error::Error;
+use std::fmt;
 
 /// Detection result from polling endpoint.
 #[derive(Clone, Debug, Default)]
@@ -7,8 +12,135 @@ pub struct DetectionResult {
     pub tests: Map<String, Value>,
     pub raw_json: Value,
     pub exit_ip: String,
-    /// Total bandwidth used (bytes sent + received).
+    /// Browser preset the scan presented.
+    pub preset: String,
+    /// Presets tried by `--auto-preset`, in order; empty for a plain scan.
+    pub preset_attempts: Vec<PresetAttempt>,
+    /// Browser locale the scan presented (BCP-47 tag).
+    pub locale: String,
+    /// IANA timezone the scan presented.
+    pub timezone: String,
+    /// Exit ASN from the location lookup; empty if the lookup was skipped or failed.
+    pub asn: String,
+    /// Sampled hardware profile, if the scan used one.
+    pub hardware: Option<HardwareProfile>,
+    /// Image probe RTTs (ms) the scan measured.
+    pub image_latencies: Vec<f64>,
+    /// WebSocket ping-pong RTTs (ms); empty if the phase was skipped or failed.
+    pub ws_latencies: Vec<f64>,
+    /// Total bandwidth used (bytes sent + received); measured when `traffic` is set,
+    /// estimated otherwise.
     pub bandwidth_bytes: u64,
+    /// Bytes on the wire per phase and channel, if the scan could be metered.
+    pub traffic: Option<TrafficReport>,
+    /// Phases cut short by the byte budget ("images", "websocket", "poll").
+    pub budget_skipped: Vec<String>,
+    /// Start and end of each request and session of the scan, by start time.
+    pub timings: Vec<TimingSpan>,
+}
+
+/// Bytes on the wire per protocol phase (HTTP channel) and the WebSocket channel.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TrafficReport {
+    pub config: Traffic,
+    pub images: Traffic,
+    pub telemetry: Traffic,
+    pub poll: Traffic,
+    pub websocket: Traffic,
+}
+
+impl TrafficReport {
+    /// HTTP channel across all phases.
+    pub fn http(&self) -> Traffic {
+        self.config + self.images + self.telemetry + self.poll
+    }
+
+    pub fn total(&self) -> Traffic {
+        self.http() + self.websocket
+    }
+}
+
+impl std::ops::Add for TrafficReport {
+    type Output = TrafficReport;
+
+    fn add(self, other: TrafficReport) -> TrafficReport {
+        TrafficReport {
+            config: self.config + other.config,
+            images: self.images + other.images,
+            telemetry: self.telemetry + other.telemetry,
+            poll: self.poll + other.poll,
+            websocket: self.websocket + other.websocket,
+        }
+    }
+}
+
+/// A scan that failed after it had started using bandwidth.
+#[derive(Debug)]
+pub struct ScanError {
+    pub source: Box<dyn Error + Send + Sync>,
+    /// Bandwidth used before the failure; measured when `measured` is set.
+    pub bandwidth_bytes: u64,
+    pub measured: bool,
+}
+
+impl ScanError {
+    /// Bandwidth a scan used and whether it was measured, whether it finished or not.
+    pub fn bandwidth(
+        result: &Result<DetectionResult, Box<dyn Error + Send + Sync>>,
+    ) -> (u64, bool) {
+        match result {
+            Ok(res) => (res.bandwidth_bytes, res.traffic.is_some()),
+            Err(err) => Self::spent(err.as_ref()),
+        }
+    }
+
+    /// Bandwidth a failed scan used; `(0, false)` if the error carries none.
+    pub fn spent(err: &(dyn Error + Send + Sync + 'static)) -> (u64, bool) {
+        err.downcast_ref::<ScanError>()
+            .map_or((0, false), |err| (err.bandwidth_bytes, err.measured))
+    }
+}
+
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.source.fmt(f)
+    }
+}
+
+impl Error for ScanError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+impl DetectionResult {
+    /// Individual test entries, keyed by test name (`http_headers`, `datacenter_ip`, ...).
+    pub fn individual_tests(&self) -> Option<&Map<String, Value>> {
+        self.tests.get("tests").and_then(|v| v.as_object())
+    }
+
+    /// The engine's OS guess from the exit host's TCP/IP stack (`tcpip_fp`).
+    pub fn tcpip_os(&self) -> Option<String> {
+        let os = self
+            .individual_tests()?
+            .get("tcpip_fp")?
+            .get("info")?
+            .get("tcpIpHighestOs")?;
+        Some(match os.as_str() {
+            Some(s) => s.to_string(),
+            None => os.to_string(),
+        })
+    }
+}
+
+/// `Some(true)` if an individual test flagged a proxy or VPN, `None` if it gave no verdict.
+pub fn test_detected(raw: &Value) -> Option<bool> {
+    let is_proxy = raw.get("is_proxy").and_then(|v| v.as_bool());
+    let is_vpn = raw.get("is_vpn").and_then(|v| v.as_bool());
+    match (is_proxy, is_vpn) {
+        (None, None) => None,
+        _ => Some(is_proxy == Some(true) || is_vpn == Some(true)),
+    }
 }
 
 /// Parse polling response JSON.
@@ -0,0 +1,57 @@
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// One timed step of a scan, in milliseconds since the scan started.
+#[derive(Clone, Debug)]
+pub struct TimingSpan {
+    /// Step name: "config", "timezone", "image #1", "websocket", "telemetry #1", "poll #1", ...
+    pub name: String,
+    pub start_ms: f64,
+    pub end_ms: f64,
+}
+
+impl TimingSpan {
+    pub fn duration_ms(&self) -> f64 {
+        self.end_ms - self.start_ms
+    }
+}
+
+/// Collects the timed steps of one scan. Steps can finish out of order (the
+/// WebSocket session runs alongside the image probes), so spans are sorted by
+/// start time when taken out.
+pub struct Timeline {
+    origin: Instant,
+    spans: Mutex<Vec<TimingSpan>>,
+}
+
+impl Timeline {
+    pub fn new(origin: Instant) -> Timeline {
+        Timeline {
+            origin,
+            spans: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Record a step that started at `started` and ends now.
+    pub fn record(&self, name: impl Into<String>, started: Instant) {
+        self.record_until(name, started, Instant::now());
+    }
+
+    pub fn record_until(&self, name: impl Into<String>, started: Instant, ended: Instant) {
+        let offset = |at: Instant| at.saturating_duration_since(self.origin).as_secs_f64() * 1000.0;
+        let span = TimingSpan {
+            name: name.into(),
+            start_ms: offset(started),
+            end_ms: offset(ended),
+        };
+        if let Ok(mut spans) = self.spans.lock() {
+            spans.push(span);
+        }
+    }
+
+    pub fn into_spans(self) -> Vec<TimingSpan> {
+        let mut spans = self.spans.into_inner().unwrap_or_default();
+        spans.sort_by(|a, b| a.start_ms.total_cmp(&b.start_ms));
+        spans
+    }
+}
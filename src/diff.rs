@@ -0,0 +1,398 @@
+use crate::detect::{parse_result, test_detected};
+use crate::output::{classify_result, BulkStatus};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// One proxy scan read back from an NDJSON or CSV result file.
+#[derive(Clone, Debug, Default)]
+pub struct ScanRecord {
+    pub proxy: String,
+    pub exit_ip: String,
+    /// "clean", "detected" or "error".
+    pub status: String,
+    /// Individual test verdicts (detected = true); NDJSON files only.
+    pub tests: BTreeMap<String, bool>,
+    pub abuser_score: Option<f64>,
+    pub asn: String,
+    pub country: String,
+}
+
+/// How one proxy (or exit IP) changed between two result sets.
+#[derive(Clone, Debug, Default)]
+pub struct RecordChange {
+    pub key: String,
+    pub status: Option<(String, String)>,
+    pub exit_ip: Option<(String, String)>,
+    /// Tests that went from clean to detected.
+    pub newly_detected: Vec<String>,
+    /// Tests that went from detected to clean.
+    pub cleared: Vec<String>,
+    pub abuser_score: Option<(f64, f64)>,
+    pub asn: Option<(String, String)>,
+    pub country: Option<(String, String)>,
+}
+
+impl RecordChange {
+    fn is_empty(&self) -> bool {
+        self.status.is_none()
+            && self.exit_ip.is_none()
+            && self.newly_detected.is_empty()
+            && self.cleared.is_empty()
+            && self.abuser_score.is_none()
+            && self.asn.is_none()
+            && self.country.is_none()
+    }
+}
+
+/// Differences between an old and a new result set.
+#[derive(Clone, Debug, Default)]
+pub struct ScanDiff {
+    /// Keys only present in the new set.
+    pub added: Vec<String>,
+    /// Keys only present in the old set.
+    pub removed: Vec<String>,
+    pub changed: Vec<RecordChange>,
+    pub new_exit_ips: Vec<String>,
+    pub gone_exit_ips: Vec<String>,
+}
+
+/// Read a result file written by `--json` (NDJSON) or `--csv`.
+pub fn load_records(
+    path: &str,
+) -> Result<Vec<ScanRecord>, Box<dyn std::error::Error + Send + Sync>> {
+    let content = std::fs::read_to_string(path)?;
+    let is_json = content.trim_start().starts_with('{');
+    let records = if is_json {
+        parse_ndjson(&content)
+    } else {
+        parse_csv(&content)?
+    };
+    if records.is_empty() {
+        return Err(format!("no scan results found in {}", path).into());
+    }
+    Ok(records)
+}
+
+/// Parse bulk NDJSON lines; lines of other shapes (repeat, matrix) are skipped.
+fn parse_ndjson(content: &str) -> Vec<ScanRecord> {
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line.trim()).ok())
+        .filter(|line| line.get("proxy").is_some() && line.get("exit_ip").is_some())
+        .map(|line| ndjson_record(&line))
+        .collect()
+}
+
+fn ndjson_record(line: &Value) -> ScanRecord {
+    let text = |value: Option<&Value>| {
+        value
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let ipapi = line.get("ipapi").filter(|v| !v.is_null());
+
+    let mut record = ScanRecord {
+        proxy: text(line.get("proxy")),
+        exit_ip: text(line.get("exit_ip")),
+        abuser_score: ipapi
+            .and_then(|info| info.get("abuser_score"))
+            .and_then(|v| v.as_f64()),
+        asn: text(ipapi.and_then(|info| info.get("asn_org"))),
+        country: text(ipapi.and_then(|info| info.get("country"))),
+        ..Default::default()
+    };
+    if record.asn.is_empty() {
+        record.asn = text(line.get("asn"));
+    }
+
+    let result = line
+        .get("result")
+        .filter(|v| !v.is_null())
+        .and_then(|raw| parse_result(raw.to_string().as_bytes()).ok());
+    match result {
+        Some(result) if line.get("error").is_none_or(Value::is_null) => {
+            record.status = match classify_result(&result) {
+                BulkStatus::Clean => "clean",
+                BulkStatus::Detected => "detected",
+            }
+            .to_string();
+            for (key, raw) in result.individual_tests().into_iter().flatten() {
+                if let Some(detected) = test_detected(raw) {
+                    record.tests.insert(key.clone(), detected);
+                }
+            }
+        }
+        _ => record.status = "error".to_string(),
+    }
+    record
+}
+
+fn parse_csv(content: &str) -> Result<Vec<ScanRecord>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut lines = content.lines().filter(|line| !line.trim().is_empty());
+    let header = split_csv_line(lines.next().unwrap_or_default());
+    let column = |name: &str| header.iter().position(|h| h == name);
+    let (proxy_col, exit_col, status_col) =
+        match (column("proxy"), column("exit_ip"), column("status")) {
+            (Some(p), Some(e), Some(s)) => (p, e, s),
+            _ => return Err("CSV is missing the proxy, exit_ip or status column".into()),
+        };
+    let abuser_col = column("abuser_score");
+    let asn_col = column("asn_org");
+    let country_col = column("country");
+
+    Ok(lines
+        .map(|line| {
+            let fields = split_csv_line(line);
+            let field =
+                |col: Option<usize>| col.and_then(|c| fields.get(c)).cloned().unwrap_or_default();
+            ScanRecord {
+                proxy: field(Some(proxy_col)),
+                exit_ip: field(Some(exit_col)),
+                status: field(Some(status_col)),
+                tests: BTreeMap::new(),
+                abuser_score: field(abuser_col).parse().ok(),
+                asn: field(asn_col),
+                country: field(country_col),
+            }
+        })
+        .collect())
+}
+
+/// Split one CSV line as written by `csv_quote`: quoted fields with doubled quotes.
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    fields.push(current);
+    fields
+}
+
+/// Compare two result sets keyed by proxy URL, or by exit IP when `by_exit_ip`.
+pub fn diff(old: &[ScanRecord], new: &[ScanRecord], by_exit_ip: bool) -> ScanDiff {
+    let key = |record: &ScanRecord| {
+        if by_exit_ip {
+            record.exit_ip.clone()
+        } else {
+            record.proxy.clone()
+        }
+    };
+    let index = |records: &[ScanRecord]| -> BTreeMap<String, ScanRecord> {
+        records
+            .iter()
+            .filter(|record| !key(record).is_empty())
+            .map(|record| (key(record), record.clone()))
+            .collect()
+    };
+    let old_map = index(old);
+    let new_map = index(new);
+
+    let exit_ips = |records: &[ScanRecord]| -> BTreeSet<String> {
+        records
+            .iter()
+            .map(|record| record.exit_ip.clone())
+            .filter(|ip| !ip.is_empty())
+            .collect()
+    };
+    let old_ips = exit_ips(old);
+    let new_ips = exit_ips(new);
+
+    let mut result = ScanDiff {
+        added: new_map
+            .keys()
+            .filter(|k| !old_map.contains_key(*k))
+            .cloned()
+            .collect(),
+        removed: old_map
+            .keys()
+            .filter(|k| !new_map.contains_key(*k))
+            .cloned()
+            .collect(),
+        new_exit_ips: new_ips.difference(&old_ips).cloned().collect(),
+        gone_exit_ips: old_ips.difference(&new_ips).cloned().collect(),
+        ..Default::default()
+    };
+
+    for (key, before) in &old_map {
+        if let Some(after) = new_map.get(key) {
+            let change = compare(key, before, after);
+            if !change.is_empty() {
+                result.changed.push(change);
+            }
+        }
+    }
+    result
+}
+
+fn compare(key: &str, before: &ScanRecord, after: &ScanRecord) -> RecordChange {
+    let changed = |a: &String, b: &String| {
+        (!a.is_empty() && !b.is_empty() && a != b).then(|| (a.clone(), b.clone()))
+    };
+
+    let mut change = RecordChange {
+        key: key.to_string(),
+        status: changed(&before.status, &after.status),
+        exit_ip: changed(&before.exit_ip, &after.exit_ip),
+        asn: changed(&before.asn, &after.asn),
+        country: changed(&before.country, &after.country),
+        ..Default::default()
+    };
+    if let (Some(a), Some(b)) = (before.abuser_score, after.abuser_score) {
+        if a != b {
+            change.abuser_score = Some((a, b));
+        }
+    }
+    for (test, detected) in &after.tests {
+        match before.tests.get(test) {
+            Some(false) if *detected => change.newly_detected.push(test.clone()),
+            Some(true) if !*detected => change.cleared.push(test.clone()),
+            _ => {}
+        }
+    }
+    change
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(proxy: &str, exit_ip: &str, status: &str) -> ScanRecord {
+        ScanRecord {
+            proxy: proxy.to_string(),
+            exit_ip: exit_ip.to_string(),
+            status: status.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn splits_quoted_csv_fields() {
+        assert_eq!(split_csv_line("a,b,,c"), ["a", "b", "", "c"]);
+        assert_eq!(
+            split_csv_line(r#""http://u:p@h:1","AS1 ""Big"", Inc.",x"#),
+            ["http://u:p@h:1", r#"AS1 "Big", Inc."#, "x"]
+        );
+        assert_eq!(split_csv_line(""), [""]);
+    }
+
+    #[test]
+    fn parses_csv_by_column_name() {
+        let csv = "status,proxy,exit_ip,abuser_score,asn_org\n\
+                   clean,http://a:1,1.1.1.1,0.0012,\"AS1 Example, Inc.\"\n\
+                   \n\
+                   error,http://b:1,,,\n";
+        let records = parse_csv(csv).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].proxy, "http://a:1");
+        assert_eq!(records[0].exit_ip, "1.1.1.1");
+        assert_eq!(records[0].status, "clean");
+        assert_eq!(records[0].abuser_score, Some(0.0012));
+        assert_eq!(records[0].asn, "AS1 Example, Inc.");
+        assert_eq!(records[0].country, "");
+        assert_eq!(records[1].abuser_score, None);
+
+        assert!(parse_csv("proxy,status\nhttp://a:1,clean\n").is_err());
+    }
+
+    #[test]
+    fn parses_ndjson_results_and_errors() {
+        let ndjson = [
+            r#"{"proxy":"http://a:1","exit_ip":"1.1.1.1","asn":"AS1","result":{"finished":true,"proxy":{"isProxy":true},"tests":{"latency":{"is_proxy":true},"http_headers":{"is_proxy":false,"is_vpn":false},"geo":{"score":1}}}}"#,
+            r#"{"proxy":"http://b:1","exit_ip":"","error":"timeout","result":null}"#,
+            r#"{"proxy":"http://c:1","exit_ip":"3.3.3.3","ipapi":{"asn_org":"AS3 Org","country":"DE","abuser_score":0.5},"result":{"proxy":{"isProxy":false}}}"#,
+            r#"{"repeat":3,"summary":{}}"#,
+            "not json",
+        ]
+        .join("\n");
+        let records = parse_ndjson(&ndjson);
+        assert_eq!(records.len(), 3);
+
+        assert_eq!(records[0].status, "detected");
+        assert_eq!(records[0].asn, "AS1");
+        assert_eq!(
+            records[0].tests,
+            BTreeMap::from([
+                ("http_headers".to_string(), false),
+                ("latency".to_string(), true)
+            ])
+        );
+        assert_eq!(records[1].status, "error");
+        assert_eq!(records[2].status, "clean");
+        assert_eq!(records[2].asn, "AS3 Org");
+        assert_eq!(records[2].country, "DE");
+        assert_eq!(records[2].abuser_score, Some(0.5));
+    }
+
+    #[test]
+    fn diffs_by_proxy() {
+        let mut old_a = record("http://a:1", "1.1.1.1", "clean");
+        old_a.tests = BTreeMap::from([("latency".to_string(), false), ("tor".to_string(), true)]);
+        old_a.abuser_score = Some(0.1);
+        let mut new_a = record("http://a:1", "2.2.2.2", "detected");
+        new_a.tests = BTreeMap::from([("latency".to_string(), true), ("tor".to_string(), false)]);
+        new_a.abuser_score = Some(0.3);
+
+        let old = [
+            old_a,
+            record("http://b:1", "5.5.5.5", "clean"),
+            record("http://gone:1", "9.9.9.9", "clean"),
+        ];
+        let new = [
+            new_a,
+            // An empty value on one side is not a change.
+            record("http://b:1", "", "clean"),
+            record("http://new:1", "7.7.7.7", "clean"),
+        ];
+
+        let diff = diff(&old, &new, false);
+        assert_eq!(diff.added, ["http://new:1"]);
+        assert_eq!(diff.removed, ["http://gone:1"]);
+        assert_eq!(diff.changed.len(), 1);
+        let change = &diff.changed[0];
+        assert_eq!(change.key, "http://a:1");
+        assert_eq!(
+            change.status,
+            Some(("clean".to_string(), "detected".to_string()))
+        );
+        assert_eq!(
+            change.exit_ip,
+            Some(("1.1.1.1".to_string(), "2.2.2.2".to_string()))
+        );
+        assert_eq!(change.newly_detected, ["latency"]);
+        assert_eq!(change.cleared, ["tor"]);
+        assert_eq!(change.abuser_score, Some((0.1, 0.3)));
+        assert_eq!(diff.new_exit_ips, ["2.2.2.2", "7.7.7.7"]);
+        assert_eq!(diff.gone_exit_ips, ["1.1.1.1", "5.5.5.5", "9.9.9.9"]);
+    }
+
+    #[test]
+    fn diffs_by_exit_ip() {
+        let old = [record("http://a:1", "1.1.1.1", "clean")];
+        let new = [
+            record("http://b:1", "1.1.1.1", "detected"),
+            record("http://c:1", "", "error"),
+        ];
+        let diff = diff(&old, &new, true);
+        // Records without an exit IP have no key.
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].key, "1.1.1.1");
+        assert_eq!(
+            diff.changed[0].status,
+            Some(("clean".to_string(), "detected".to_string()))
+        );
+    }
+}
@@ -0,0 +1,229 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+static TAG_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^([A-Za-z]{2,3})(?:-([A-Za-z]{4}))?(?:-([A-Za-z]{2}|[0-9]{3}))?$").unwrap()
+});
+
+/// Browser UI locale driving Accept-Language, navigator languages and date formatting.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Locale {
+    /// Canonical BCP-47 tag, e.g. "ja-JP".
+    pub tag: String,
+    /// Primary language subtag, e.g. "ja".
+    pub language: String,
+    /// Region subtag, e.g. "JP".
+    pub region: Option<String>,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale {
+            tag: "en-US".to_string(),
+            language: "en".to_string(),
+            region: Some("US".to_string()),
+        }
+    }
+}
+
+impl Locale {
+    /// Parse and canonicalize a BCP-47 tag (language, optional script, optional region).
+    pub fn parse(tag: &str) -> Result<Locale, Box<dyn std::error::Error + Send + Sync>> {
+        let caps = TAG_REGEX
+            .captures(tag.trim())
+            .ok_or_else(|| format!("invalid locale tag: {}", tag))?;
+
+        let language = caps[1].to_ascii_lowercase();
+        let script = caps.get(2).map(|m| {
+            let lower = m.as_str().to_ascii_lowercase();
+            let mut chars = lower.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        });
+        let region = caps.get(3).map(|m| m.as_str().to_ascii_uppercase());
+
+        let mut canonical = language.clone();
+        if let Some(ref script) = script {
+            canonical.push('-');
+            canonical.push_str(script);
+        }
+        if let Some(ref region) = region {
+            canonical.push('-');
+            canonical.push_str(region);
+        }
+
+        Ok(Locale {
+            tag: canonical,
+            language,
+            region,
+        })
+    }
+
+    /// Most common browser locale for an ISO 3166-1 alpha-2 country code.
+    pub fn for_country(country_code: &str) -> Locale {
+        let tag = match country_code.to_ascii_uppercase().as_str() {
+            // North America
+            "US" => "en-US",
+            "CA" => "en-CA",
+            "MX" => "es-MX",
+            // Latin America
+            "AR" => "es-AR",
+            "BO" => "es-BO",
+            "BR" => "pt-BR",
+            "CL" => "es-CL",
+            "CO" => "es-CO",
+            "CR" => "es-CR",
+            "DO" => "es-DO",
+            "EC" => "es-EC",
+            "GT" => "es-GT",
+            "PA" => "es-PA",
+            "PE" => "es-PE",
+            "PR" => "es-PR",
+            "PY" => "es-PY",
+            "UY" => "es-UY",
+            "VE" => "es-VE",
+            // Europe
+            "AT" => "de-AT",
+            "BE" => "nl-BE",
+            "BG" => "bg-BG",
+            "BY" => "ru-BY",
+            "CH" => "de-CH",
+            "CZ" => "cs-CZ",
+            "DE" => "de-DE",
+            "DK" => "da-DK",
+            "EE" => "et-EE",
+            "ES" => "es-ES",
+            "FI" => "fi-FI",
+            "FR" => "fr-FR",
+            "GB" => "en-GB",
+            "GR" => "el-GR",
+            "HR" => "hr-HR",
+            "HU" => "hu-HU",
+            "IE" => "en-IE",
+            "IS" => "is-IS",
+            "IT" => "it-IT",
+            "LT" => "lt-LT",
+            "LU" => "fr-LU",
+            "LV" => "lv-LV",
+            "NL" => "nl-NL",
+            "NO" => "nb-NO",
+            "PL" => "pl-PL",
+            "PT" => "pt-PT",
+            "RO" => "ro-RO",
+            "RS" => "sr-RS",
+            "RU" => "ru-RU",
+            "SE" => "sv-SE",
+            "SI" => "sl-SI",
+            "SK" => "sk-SK",
+            "TR" => "tr-TR",
+            "UA" => "uk-UA",
+            // Asia
+            "AE" => "ar-AE",
+            "BD" => "bn-BD",
+            "CN" => "zh-CN",
+            "HK" => "zh-HK",
+            "ID" => "id-ID",
+            "IL" => "he-IL",
+            "IN" => "en-IN",
+            "IR" => "fa-IR",
+            "JP" => "ja-JP",
+            "KR" => "ko-KR",
+            "KZ" => "ru-KZ",
+            "MY" => "ms-MY",
+            "PH" => "en-PH",
+            "PK" => "en-PK",
+            "SA" => "ar-SA",
+            "SG" => "en-SG",
+            "TH" => "th-TH",
+            "TW" => "zh-TW",
+            "VN" => "vi-VN",
+            // Oceania
+            "AU" => "en-AU",
+            "NZ" => "en-NZ",
+            // Africa
+            "EG" => "ar-EG",
+            "KE" => "en-KE",
+            "MA" => "fr-MA",
+            "NG" => "en-NG",
+            "ZA" => "en-ZA",
+            _ => "en-US",
+        };
+        Locale::parse(tag).unwrap_or_default()
+    }
+
+    /// Language preference list of a browser configured for this locale:
+    /// the locale itself, its bare language, then English fallbacks.
+    pub fn preferences(&self) -> Vec<String> {
+        let mut list = vec![self.tag.clone()];
+        let bare = if self.language == "en" {
+            None
+        } else {
+            Some(self.language.as_str())
+        };
+        for fallback in bare.into_iter().chain(["en-US", "en"]) {
+            if !list.iter().any(|tag| tag == fallback) {
+                list.push(fallback.to_string());
+            }
+        }
+        list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_canonicalizes_case() {
+        let locale = Locale::parse("JA-jp").unwrap();
+        assert_eq!(locale.tag, "ja-JP");
+        assert_eq!(locale.language, "ja");
+        assert_eq!(locale.region.as_deref(), Some("JP"));
+
+        let script = Locale::parse(" zh-hant-tw ").unwrap();
+        assert_eq!(script.tag, "zh-Hant-TW");
+        assert_eq!(script.region.as_deref(), Some("TW"));
+
+        let numeric = Locale::parse("es-419").unwrap();
+        assert_eq!(numeric.tag, "es-419");
+        assert_eq!(numeric.region.as_deref(), Some("419"));
+
+        let bare = Locale::parse("fr").unwrap();
+        assert_eq!(bare.tag, "fr");
+        assert_eq!(bare.region, None);
+    }
+
+    #[test]
+    fn parse_rejects_invalid_tags() {
+        for tag in [
+            "",
+            "e",
+            "english",
+            "en_US",
+            "en-USA",
+            "en-US-x-private",
+            "12-US",
+        ] {
+            assert!(Locale::parse(tag).is_err(), "{:?} should be rejected", tag);
+        }
+    }
+
+    #[test]
+    fn country_defaults() {
+        assert_eq!(Locale::for_country("jp").tag, "ja-JP");
+        assert_eq!(Locale::for_country("BE").tag, "nl-BE");
+        assert_eq!(Locale::for_country("ZZ"), Locale::default());
+        assert_eq!(Locale::for_country(""), Locale::default());
+    }
+
+    #[test]
+    fn preferences_add_language_and_english_fallbacks() {
+        let prefs = |tag: &str| Locale::parse(tag).unwrap().preferences();
+        assert_eq!(prefs("de-DE"), ["de-DE", "de", "en-US", "en"]);
+        assert_eq!(prefs("en-GB"), ["en-GB", "en-US", "en"]);
+        assert_eq!(prefs("en-US"), ["en-US", "en"]);
+        assert_eq!(prefs("ja"), ["ja", "en-US", "en"]);
+    }
+}
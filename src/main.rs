@@ -1,19 +1,31 @@
 mod browser;
+mod capture;
+mod checkpoint;
+mod cost;
 mod detect;
+mod diff;
 mod ipapi;
+mod locale;
+mod meter;
 mod output;
+mod shutdown;
 mod timezone;
 
-use clap::Parser;
-use detect::{run, Options};
+use clap::{Parser, Subcommand};
+use detect::{run, run_streaming, DetectionResult, Options};
 use futures_util::stream::{self, StreamExt};
 use std::io::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 #[derive(Parser)]
 #[command(name = "proxy-detector")]
 #[command(about = "Detect proxy/VPN usage via TLS fingerprinting")]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Proxy URL to test (http://, socks5://)
     #[arg(long, default_value = "", conflicts_with = "file")]
     proxy: String,
@@ -26,14 +38,82 @@ struct Cli {
     #[arg(long, default_value = "200")]
     concurrency: usize,
 
-    /// Browser preset: chrome-143, firefox-133, safari-18
-    #[arg(long, default_value = "chrome-143")]
+    /// Browser preset: chrome-143, chrome-143-linux, firefox-133, safari-18, or a preset .json file
+    #[arg(long, default_value = "chrome-143", global = true)]
     browser: String,
 
     /// Override IANA timezone (default: auto from exit IP)
     #[arg(long, default_value = "")]
     timezone: String,
 
+    /// Debug: generate Date/time payload fields for this instant (RFC 3339 or Unix seconds)
+    #[arg(long, value_parser = timezone::parse_instant)]
+    at: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// Browser locale: BCP-47 tag (e.g. ja-JP) or "auto" from the exit IP country (default: en-US)
+    #[arg(long, default_value = "", global = true)]
+    locale: String,
+
+    /// Sample a realistic hardware profile (cores, memory, screen) for each scan
+    #[arg(long, global = true)]
+    random_hardware: bool,
+
+    /// Seed for --random-hardware (bulk scans use seed + line index)
+    #[arg(long, requires = "random_hardware", global = true)]
+    seed: Option<u64>,
+
+    /// Override a fingerprint property, e.g. hardwareConcurrency=8 or dimensions=2560,1440 (repeatable)
+    #[arg(long = "set", value_name = "KEY=VALUE", value_parser = parse_fingerprint_override, global = true)]
+    fingerprint_overrides: Vec<(String, String)>,
+
+    /// Image latency probes per scan (default: 3)
+    #[arg(long, value_name = "N")]
+    images: Option<usize>,
+
+    /// WebSocket ping-pong rounds per scan (default: 5)
+    #[arg(long, value_name = "N")]
+    ws_rounds: Option<usize>,
+
+    /// Skip the WebSocket phase (image RTTs stand in for WS latencies)
+    #[arg(long)]
+    no_ws: bool,
+
+    /// Poll for results at a fixed interval instead of the progressive backoff
+    #[arg(long, value_name = "MS")]
+    poll_interval: Option<u64>,
+
+    /// Return whatever tests have completed after this many seconds of polling
+    #[arg(long, value_name = "SECS")]
+    poll_timeout: Option<u64>,
+
+    /// Per-scan byte budget: skip remaining probes, WebSocket and polls past it, abort before telemetry
+    #[arg(long, value_name = "KB")]
+    proxy_budget: Option<u64>,
+
+    /// Bulk byte budget: stop starting new scans once the run has used this much (only with --file)
+    #[arg(long, value_name = "MB", requires = "file", conflicts_with_all = ["repeat", "matrix"])]
+    run_budget: Option<u64>,
+
+    /// Proxy traffic price for the bulk cost estimate (only with --file)
+    #[arg(long, value_name = "USD", requires = "file", conflicts_with_all = ["repeat", "matrix"])]
+    price_per_gb: Option<f64>,
+
+    /// Price per GB for proxies on this host or its subdomains, e.g. pool.example.com=4.5 (repeatable)
+    #[arg(long, value_name = "HOST=USD", value_parser = parse_host_price, requires = "file", conflicts_with_all = ["repeat", "matrix"])]
+    host_price: Vec<(String, f64)>,
+
+    /// Journal each completed proxy to FILE so an interrupted --file run can be resumed
+    #[arg(long, value_name = "FILE", requires = "file", conflicts_with_all = ["repeat", "matrix"])]
+    checkpoint: Option<String>,
+
+    /// Skip proxies already in the --checkpoint journal and append to the existing --csv file
+    #[arg(long, requires = "checkpoint")]
+    resume: bool,
+
+    /// Send high-entropy client hints (Sec-Ch-Ua-Full-Version-List, -Arch, -Platform-Version, -Model) on Chromium presets
+    #[arg(long)]
+    client_hints: bool,
+
     /// Show detailed test info
     #[arg(long)]
     verbose: bool,
@@ -42,6 +122,44 @@ struct Cli {
     #[arg(long)]
     json: bool,
 
+    /// Run N independent scans per proxy and report positive rates, majority verdicts and stability
+    #[arg(long, value_name = "N", default_value = "1", value_parser = clap::value_parser!(u32).range(1..),
+          conflicts_with_all = ["ipapi", "max_fraud_score", "clean", "live"])]
+    repeat: u32,
+
+    /// Show local image/WebSocket latency statistics and the proxy overhead over a direct baseline
+    #[arg(long, conflicts_with_all = ["file", "json", "repeat"])]
+    latency: bool,
+
+    /// Scan each proxy with every listed preset ("all" for the built-ins) and report a verdict grid
+    #[arg(long, value_name = "PRESETS", value_delimiter = ',',
+          conflicts_with_all = ["repeat", "live", "auto_preset", "baseline", "explain", "latency", "ipapi", "max_fraud_score", "clean"])]
+    matrix: Vec<String>,
+
+    /// Timezone overrides for --matrix, each one a grid column per preset ("auto" derives it from the exit IP)
+    #[arg(long, value_name = "ZONES", value_delimiter = ',', requires = "matrix")]
+    matrix_timezones: Vec<String>,
+
+    /// Rescan with a preset whose OS matches the exit's TCP/IP OS when tcpip_fp flags a mismatch
+    #[arg(long, conflicts_with_all = ["repeat", "live"])]
+    auto_preset: bool,
+
+    /// JSON file caching each proxy's TCP/IP OS guess for --auto-preset (skips the first scan)
+    #[arg(long, value_name = "FILE", requires = "auto_preset")]
+    os_cache: Option<String>,
+
+    /// Scan the direct connection first and report each proxy's deltas against it
+    #[arg(long, conflicts_with_all = ["json", "repeat"])]
+    baseline: bool,
+
+    /// Explain the cause of each failed test and what would change the outcome
+    #[arg(long, conflicts_with_all = ["file", "json", "repeat"])]
+    explain: bool,
+
+    /// Print each individual test to stderr as soon as it completes (single proxy only)
+    #[arg(long, conflicts_with_all = ["file", "json"])]
+    live: bool,
+
     /// Enrich results with ipapi.is intelligence (queried through the proxy).
     #[arg(long)]
     ipapi: bool,
@@ -59,8 +177,83 @@ struct Cli {
     csv: Option<String>,
 }
 
+#[derive(Subcommand)]
+enum Command {
+    /// Inspect and validate browser presets
+    #[command(alias = "preset")]
+    Presets {
+        #[command(subcommand)]
+        action: PresetsAction,
+    },
+    /// Capture each preset's TLS ClientHello and HTTP/2 preamble locally and compare
+    /// JA3N, JA4 and Akamai fingerprints with the values pinned in the preset
+    Selftest {
+        /// Presets (names or .json files) to test (default: all built-in presets)
+        names: Vec<String>,
+    },
+    /// Compare two saved result sets (--json NDJSON or --csv) and report status changes,
+    /// flipped tests, abuser score and ASN/country moves, and new or gone exit IPs
+    Diff {
+        /// Earlier result file
+        old: String,
+
+        /// Later result file
+        new: String,
+
+        /// Match scans by exit IP instead of proxy URL
+        #[arg(long)]
+        by_exit_ip: bool,
+    },
+    /// Print the fingerprint property string and MurmurHash3 value for a preset
+    Fingerprint {
+        /// Run a scan with the inspected properties afterwards
+        #[arg(long)]
+        scan: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum PresetsAction {
+    /// Cross-validate User-Agent, client hints, header contexts and navigator properties
+    Check {
+        /// Presets to check (default: all built-in presets)
+        names: Vec<String>,
+    },
+    /// Capture every request context on a local server and diff the on-the-wire
+    /// header order and casing against reference captures
+    Wire {
+        /// Presets to check (default: all built-in presets)
+        names: Vec<String>,
+
+        /// Directory holding <preset>/<context>.<h1|h2>.txt reference captures
+        #[arg(long, default_value = "references")]
+        references: String,
+
+        /// Write the references of a single preset from a HAR recorded in the real
+        /// browser instead of comparing
+        #[arg(long, value_name = "HAR")]
+        record: Option<String>,
+    },
+    /// Generate a preset file from a HAR recorded on proxydetect.live
+    ImportHar {
+        /// HAR file exported from the browser's developer tools
+        file: String,
+
+        /// Preset name (default: <browser>-<major version>)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Write the preset here instead of stdout
+        #[arg(long, short)]
+        output: Option<String>,
+    },
+}
+
 const CLEAN_ABUSER_THRESHOLD: f64 = 0.0001;
 
+/// How long an interrupted bulk run waits for in-flight scans.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(30);
+
 fn normalize_proxy(proxy_str: &str) -> String {
     // If it already looks like a URL with user info (contains @), trust it.
     if proxy_str.contains('@') {
@@ -105,6 +298,36 @@ fn mask_proxy(proxy_url: &str) -> String {
     }
 }
 
+/// Parse a `--set key=value` fingerprint override.
+fn parse_fingerprint_override(arg: &str) -> Result<(String, String), String> {
+    let (key, value) = arg
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=VALUE, got '{}'", arg))?;
+    let keys = browser::fingerprint_keys();
+    if !keys.contains(&key) {
+        return Err(format!(
+            "unknown fingerprint property '{}' (known: {})",
+            key,
+            keys.join(", ")
+        ));
+    }
+    Ok((key.to_string(), value.to_string()))
+}
+
+/// Parse a `--host-price host=price` pricing override.
+fn parse_host_price(arg: &str) -> Result<(String, f64), String> {
+    let (host, price) = arg
+        .split_once('=')
+        .ok_or_else(|| format!("expected HOST=USD, got '{}'", arg))?;
+    let price: f64 = price
+        .parse()
+        .map_err(|_| format!("invalid price '{}'", price))?;
+    if host.is_empty() || price < 0.0 {
+        return Err(format!("expected HOST=USD, got '{}'", arg));
+    }
+    Ok((host.to_lowercase(), price))
+}
+
 fn parse_proxy_file(path: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
     let content = std::fs::read_to_string(path)?;
     let proxies: Vec<String> = content
@@ -120,10 +343,469 @@ fn parse_proxy_file(path: &str) -> Result<Vec<String>, Box<dyn std::error::Error
     Ok(proxies)
 }
 
+/// Proxies from --file, else the single --proxy; `None` is the direct connection.
+fn scan_targets(cli: &Cli) -> Vec<Option<String>> {
+    match cli.file {
+        Some(ref path) => match parse_proxy_file(path) {
+            Ok(list) => list.into_iter().map(Some).collect(),
+            Err(err) => {
+                eprintln!("Error reading proxy file: {}", err);
+                std::process::exit(1);
+            }
+        },
+        None if cli.proxy.is_empty() => vec![None],
+        None => vec![Some(normalize_proxy(&cli.proxy))],
+    }
+}
+
+/// Identity options shared by every scan in a run.
+#[derive(Clone)]
+struct ScanSettings {
+    browser: String,
+    timezone: Option<String>,
+    clock: timezone::Clock,
+    locale: Option<String>,
+    hardware_seed: Option<u64>,
+    high_entropy_hints: bool,
+    fingerprint_overrides: Vec<(String, String)>,
+    protocol: detect::ProtocolOptions,
+    /// Rescan with an OS-matched preset when `tcpip_fp` flags the first one.
+    auto_preset: bool,
+    /// Always look up the exit ASN, which `--baseline` compares.
+    resolve_asn: bool,
+}
+
+impl ScanSettings {
+    fn from_cli(cli: &Cli, hardware_seed: Option<u64>) -> Self {
+        let defaults = detect::ProtocolOptions::default();
+        ScanSettings {
+            browser: cli.browser.clone(),
+            timezone: if cli.timezone.is_empty() {
+                None
+            } else {
+                Some(cli.timezone.clone())
+            },
+            clock: cli
+                .at
+                .map_or(timezone::Clock::System, timezone::Clock::Fixed),
+            locale: if cli.locale.is_empty() {
+                None
+            } else {
+                Some(cli.locale.clone())
+            },
+            hardware_seed,
+            high_entropy_hints: cli.client_hints,
+            fingerprint_overrides: cli.fingerprint_overrides.clone(),
+            protocol: detect::ProtocolOptions {
+                image_probes: cli.images.unwrap_or(defaults.image_probes),
+                websocket: !cli.no_ws,
+                ws_rounds: cli.ws_rounds.unwrap_or(defaults.ws_rounds),
+                poll_schedule: match cli.poll_interval {
+                    Some(ms) => detect::PollSchedule::Every(Duration::from_millis(ms)),
+                    None => detect::PollSchedule::Backoff,
+                },
+                poll_deadline: cli.poll_timeout.map(Duration::from_secs),
+                byte_budget: cli.proxy_budget.map(|kb| kb * 1024),
+            },
+            auto_preset: cli.auto_preset,
+            resolve_asn: cli.baseline,
+        }
+    }
+
+    /// Detection options for one scan; bulk scans offset the hardware seed by line index.
+    fn options(
+        &self,
+        proxy_url: Option<String>,
+        idx: usize,
+        verbose: bool,
+        json_output: bool,
+    ) -> Options {
+        Options {
+            proxy_url,
+            browser_name: self.browser.clone(),
+            timezone_iana: self.timezone.clone(),
+            locale: self.locale.clone(),
+            hardware_seed: self.hardware_seed.map(|seed| seed.wrapping_add(idx as u64)),
+            high_entropy_hints: self.high_entropy_hints,
+            fingerprint_overrides: self.fingerprint_overrides.clone(),
+            clock: self.clock,
+            protocol: self.protocol.clone(),
+            resolve_asn: self.resolve_asn,
+            verbose,
+            json_output,
+        }
+    }
+
+    /// Run one scan, through `--auto-preset` when enabled.
+    async fn scan(
+        &self,
+        opts: &Options,
+        cached_os: Option<&str>,
+        log: impl Fn(&str),
+    ) -> Result<DetectionResult, Box<dyn std::error::Error + Send + Sync>> {
+        if self.auto_preset {
+            detect::run_auto_preset(opts, cached_os, log).await
+        } else {
+            run(opts, log).await
+        }
+    }
+}
+
+/// Load the `--os-cache` file, exiting on a corrupt one.
+fn load_os_cache(path: Option<&str>) -> detect::OsCache {
+    match path.map(detect::OsCache::load) {
+        Some(Ok(cache)) => cache,
+        Some(Err(err)) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+        None => detect::OsCache::default(),
+    }
+}
+
+/// Record the TCP/IP OS guess of an auto-preset scan and write the cache file.
+fn update_os_cache(cache: &mut detect::OsCache, path: Option<&str>, updates: &[(String, String)]) {
+    let path = match path {
+        Some(path) if !updates.is_empty() => path,
+        _ => return,
+    };
+    for (proxy, os) in updates {
+        cache.insert(proxy, os);
+    }
+    if let Err(err) = cache.save(path) {
+        eprintln!("Failed to write OS cache {}: {}", path, err);
+    }
+}
+
+/// Open the bulk CSV: a new file with a header, or when resuming the existing
+/// file for appending, provided it was written with the same columns.
+fn open_bulk_csv(path: &str, header: &str, resuming: bool) -> std::fs::File {
+    if resuming {
+        match std::fs::read_to_string(path) {
+            Ok(content) if !content.is_empty() => {
+                if content.lines().next() != Some(header) {
+                    eprintln!(
+                        "Error: {} has different columns; resume with the same --ipapi/--random-hardware options",
+                        path
+                    );
+                    std::process::exit(1);
+                }
+                let mut file = std::fs::OpenOptions::new()
+                    .append(true)
+                    .open(path)
+                    .expect("failed to open CSV file");
+                if !content.ends_with('\n') {
+                    let _ = writeln!(file);
+                }
+                return file;
+            }
+            _ => {}
+        }
+    }
+    let mut file = std::fs::File::create(path).expect("failed to create CSV file");
+    writeln!(file, "{}", header).unwrap();
+    file
+}
+
+/// Record a finished bulk scan in the checkpoint journal, after its output rows.
+fn journal_scan(
+    journal: &mut Option<checkpoint::Journal>,
+    proxy_url: &str,
+    outcome: &str,
+    result: &Result<DetectionResult, Box<dyn std::error::Error + Send + Sync>>,
+    ip_info: Option<&ipapi::IpInfo>,
+) {
+    let Some(journal) = journal else {
+        return;
+    };
+    let res = result.as_ref().ok();
+    let (bandwidth_bytes, measured) = detect::ScanError::bandwidth(result);
+    let entry = checkpoint::JournalEntry {
+        proxy: proxy_url.to_string(),
+        outcome: outcome.to_string(),
+        exit_ip: res.map(|r| r.exit_ip.clone()).unwrap_or_default(),
+        bandwidth_bytes,
+        measured,
+        abuser_score: ip_info.map(|info| info.abuser_score),
+    };
+    if let Err(err) = journal.record(&entry) {
+        eprintln!("Failed to write checkpoint: {}", err);
+    }
+}
+
+/// TCP/IP OS the first `--auto-preset` scan saw, when the OS cache lacks it or
+/// holds a different guess.
+fn observed_os(result: &DetectionResult) -> Option<String> {
+    let first = result.preset_attempts.first()?;
+    let os = first.tcpip_os.clone()?;
+    (first.cached_os.as_ref() != Some(&os)).then_some(os)
+}
+
+/// Preset for follow-up requests, carrying the preset and locale the scan presented.
+fn preset_for_result(
+    browser: &str,
+    result: &Result<DetectionResult, Box<dyn std::error::Error + Send + Sync>>,
+) -> browser::Preset {
+    let mut preset = browser::get_preset(browser);
+    if let Ok(res) = result {
+        if !res.preset.is_empty() {
+            preset = browser::get_preset(&res.preset);
+        }
+        if let Ok(locale) = locale::Locale::parse(&res.locale) {
+            preset.locale = locale;
+        }
+    }
+    preset
+}
+
+/// Validate a preset argument: built-in names pass through, `.json` preset files are
+/// loaded and registered under their own name.
+fn resolve_preset(name: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    if name.ends_with(".json") {
+        let preset = browser::load_preset_file(name)?;
+        let resolved = preset.name.to_string();
+        browser::register_preset(preset);
+        Ok(resolved)
+    } else if browser::PRESET_NAMES.contains(&name) {
+        Ok(name.to_string())
+    } else {
+        Err(format!("Unknown preset: {}", name).into())
+    }
+}
+
+fn run_presets_check(names: &[String]) {
+    let names: Vec<&str> = if names.is_empty() {
+        browser::PRESET_NAMES.to_vec()
+    } else {
+        names.iter().map(String::as_str).collect()
+    };
+
+    let mut failed = false;
+    for name in names {
+        let name = match resolve_preset(name) {
+            Ok(name) => name,
+            Err(err) => {
+                eprintln!("{}", err);
+                failed = true;
+                continue;
+            }
+        };
+        // Validate with every optional header enabled so all contexts are covered.
+        let mut preset = browser::get_preset(&name);
+        preset.high_entropy_hints = true;
+        let issues = browser::check_preset(&preset);
+        output::render_preset_check(&name, &issues);
+        failed |= !issues.is_empty();
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+fn run_import_har(file: &str, name: Option<&str>, output_path: Option<&str>) {
+    let preset_file = match browser::import_har(file, name) {
+        Ok(preset_file) => preset_file,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    };
+    let json = serde_json::to_string_pretty(&preset_file).unwrap_or_default();
+
+    for context in browser::HAR_CONTEXTS {
+        if preset_file.headers.for_context(context).is_empty() {
+            eprintln!(
+                "Note: no {} request in HAR; {} keeps the {} headers for it",
+                context, preset_file.name, preset_file.base
+            );
+        }
+    }
+
+    let name = preset_file.name.clone();
+    match preset_file.into_preset() {
+        Ok(mut preset) => {
+            preset.high_entropy_hints = true;
+            let issues = browser::check_preset(&preset);
+            if output_path.is_some() {
+                output::render_preset_check(&name, &issues);
+            } else if !issues.is_empty() {
+                eprintln!(
+                    "Warning: {} coherence mismatch(es); save with --output and run presets check on the file",
+                    issues.len()
+                );
+            }
+        }
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    }
+
+    match output_path {
+        Some(path) => {
+            if let Err(err) = std::fs::write(path, json + "\n") {
+                eprintln!("Error writing {}: {}", path, err);
+                std::process::exit(1);
+            }
+            eprintln!("Preset written to {} (use --browser {})", path, path);
+        }
+        None => println!("{}", json),
+    }
+}
+
+async fn run_wire_check(names: &[String], references: &str, record: Option<&str>) {
+    if let Some(har) = record {
+        let [name] = names else {
+            eprintln!("--record takes exactly one preset name");
+            std::process::exit(2);
+        };
+        let name = match resolve_preset(name) {
+            Ok(name) => name,
+            Err(err) => {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+        };
+        match capture::record_references(&name, har, std::path::Path::new(references)) {
+            Ok(reports) => {
+                output::render_wire_check(&name, &reports);
+                if reports
+                    .iter()
+                    .any(|report| matches!(report.outcome, capture::WireOutcome::Failed(_)))
+                {
+                    std::process::exit(1);
+                }
+            }
+            Err(err) => {
+                eprintln!("Recording references failed: {}", err);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let names: Vec<&str> = if names.is_empty() {
+        browser::PRESET_NAMES.to_vec()
+    } else {
+        names.iter().map(String::as_str).collect()
+    };
+
+    let mut failed = false;
+    for name in names {
+        let name = match resolve_preset(name) {
+            Ok(name) => name,
+            Err(err) => {
+                eprintln!("{}", err);
+                failed = true;
+                continue;
+            }
+        };
+        let preset = browser::get_preset(&name);
+        match capture::check_wire_headers(&preset, std::path::Path::new(references)).await {
+            Ok(reports) => {
+                output::render_wire_check(&name, &reports);
+                failed |= reports.iter().any(|report| {
+                    matches!(
+                        report.outcome,
+                        capture::WireOutcome::Differs(_) | capture::WireOutcome::Failed(_)
+                    )
+                });
+            }
+            Err(err) => {
+                eprintln!("Wire check failed for {}: {}", name, err);
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+fn run_diff(old_path: &str, new_path: &str, by_exit_ip: bool) {
+    let load = |path: &str| match diff::load_records(path) {
+        Ok(records) => records,
+        Err(err) => {
+            eprintln!("Error reading {}: {}", path, err);
+            std::process::exit(1);
+        }
+    };
+    let old = load(old_path);
+    let new = load(new_path);
+    let changes = diff::diff(&old, &new, by_exit_ip);
+    output::render_diff(old_path, new_path, by_exit_ip, &changes);
+}
+
+async fn run_selftest(names: &[String]) {
+    let names: Vec<&str> = if names.is_empty() {
+        browser::PRESET_NAMES.to_vec()
+    } else {
+        names.iter().map(String::as_str).collect()
+    };
+
+    let mut failed = false;
+    let mut unpinned = 0;
+    for name in names {
+        let name = match resolve_preset(name) {
+            Ok(name) => name,
+            Err(err) => {
+                eprintln!("{}", err);
+                failed = true;
+                continue;
+            }
+        };
+        let checks = capture::selftest(&browser::get_preset(&name)).await;
+        output::render_selftest(&name, &checks);
+        for check in &checks {
+            match check.status() {
+                capture::CheckStatus::Pass => {}
+                capture::CheckStatus::Unpinned => unpinned += 1,
+                capture::CheckStatus::Mismatch | capture::CheckStatus::Error => failed = true,
+            }
+        }
+    }
+
+    // Unpinned values were never compared with a real browser, so they do not pass.
+    if unpinned > 0 {
+        eprintln!(
+            "{} fingerprint(s) unpinned and unverified; pin them in tls_fingerprints",
+            unpinned
+        );
+    }
+    if failed || unpinned > 0 {
+        std::process::exit(1);
+    }
+}
+
+fn run_fingerprint(settings: &ScanSettings) {
+    let opts = settings.options(None, 0, false, false);
+    let preset = match detect::build_preset(&opts) {
+        Ok(preset) => preset,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    };
+    if opts.locale.as_deref() == Some("auto") {
+        eprintln!(
+            "Note: --locale auto is resolved from the exit IP during a scan; showing {}",
+            preset.locale.tag
+        );
+    }
+
+    output::render_fingerprint(
+        &preset,
+        &browser::fingerprint_properties(&preset),
+        &browser::fingerprint_string(&preset),
+        browser::compute_fingerprint(&preset),
+    );
+}
+
 async fn run_bulk(
     proxies: Vec<String>,
-    browser: &str,
-    timezone: &Option<String>,
+    settings: &ScanSettings,
     verbose: bool,
     json_output: bool,
     concurrency: usize,
@@ -131,32 +813,106 @@ async fn run_bulk(
     ipapi_enabled: bool,
     max_fraud_score: Option<f64>,
     clean_only: bool,
+    baseline: Option<&DetectionResult>,
+    os_cache_path: Option<&str>,
+    budget: &cost::RunBudget,
+    mut journal: Option<checkpoint::Journal>,
 ) {
     let total = proxies.len();
     let concurrency = concurrency.max(1);
-    let preset = browser::get_preset(browser);
+    let include_hardware = settings.hardware_seed.is_some();
+    let mut os_cache = load_os_cache(os_cache_path);
+    let mut os_updates = Vec::new();
+    let mut cost_tally = cost::CostTally::default();
+
+    let mut clean_count: usize = 0;
+    let mut detected_count: usize = 0;
+    let mut filtered_count: usize = 0;
+    let mut error_count: usize = 0;
+    let mut ipapi_abuser_score_sum: f64 = 0.0;
+    let mut ipapi_abuser_score_count: usize = 0;
+    let mut completed_count: usize = 0;
+
+    // Carry the interrupted run's outcomes into the counters and summary.
+    let previous = journal
+        .as_ref()
+        .map(|j| j.resumed(&proxies))
+        .unwrap_or_default();
+    for entry in &previous {
+        match entry.outcome.as_str() {
+            "clean" => clean_count += 1,
+            "detected" => detected_count += 1,
+            "filtered" => filtered_count += 1,
+            _ => error_count += 1,
+        }
+        if let Some(score) = entry.abuser_score {
+            ipapi_abuser_score_sum += score;
+            ipapi_abuser_score_count += 1;
+        }
+        cost_tally.add_bytes(
+            &entry.proxy,
+            entry.bandwidth_bytes,
+            entry.measured,
+            &budget.pricing,
+        );
+        completed_count += 1;
+    }
+    let resuming = !previous.is_empty();
+    let completed = journal
+        .as_ref()
+        .map(checkpoint::Journal::completed)
+        .unwrap_or_default();
+
+    // Bytes of finished scans, checked before each new scan starts.
+    let run_bytes = Arc::new(AtomicU64::new(cost_tally.bytes));
 
     if !json_output {
         eprintln!("Bulk scan: {} proxies, concurrency {}", total, concurrency);
+        if resuming {
+            eprintln!("Resuming: {} already completed", completed_count);
+        }
         eprintln!();
     }
 
     let mut csv_file = csv_path.map(|path| {
-        let mut file = std::fs::File::create(path).expect("failed to create CSV file");
-        writeln!(file, "{}", output::csv_header(ipapi_enabled)).unwrap();
-        file
+        open_bulk_csv(
+            path,
+            &output::csv_header(ipapi_enabled, include_hardware),
+            resuming,
+        )
     });
 
-    let mut results = stream::iter(proxies.into_iter().enumerate().map(|(idx, proxy_url)| {
-        let browser = browser.to_string();
-        let timezone = timezone.clone();
-        let preset = preset.clone();
+    // Line indexes are kept across resumes so hardware seeds stay the same.
+    let shutdown = shutdown::Shutdown::install(SHUTDOWN_GRACE);
+    let shutdown = &shutdown;
+
+    let pending = proxies
+        .into_iter()
+        .enumerate()
+        .filter(|(_, proxy_url)| !completed.contains(proxy_url))
+        .take_while(|_| !shutdown.requested());
+    let mut results = stream::iter(pending.map(|(idx, proxy_url)| {
+        let settings = settings.clone();
+        let cached_os = os_cache.get(&proxy_url).map(str::to_string);
+        let run_bytes = run_bytes.clone();
+        let max_run_bytes = budget.max_bytes;
         async move {
             // Stagger launches: spread concurrent tasks over time to avoid
             // overwhelming the detection server with simultaneous telemetry POSTs.
             // Each batch of `concurrency` tasks is spread over ~20s (100ms apart).
             let stagger_ms = (idx % concurrency) as u64 * 100;
-            tokio::time::sleep(Duration::from_millis(stagger_ms)).await;
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_millis(stagger_ms)) => {}
+                _ = shutdown.wait() => {}
+            }
+
+            // Scans already in flight finish, so the run can overshoot by up to
+            // `concurrency` scans.
+            if shutdown.requested()
+                || max_run_bytes.is_some_and(|limit| run_bytes.load(Ordering::Relaxed) >= limit)
+            {
+                return (idx, proxy_url, None, None, None, 0.0);
+            }
 
             if !json_output {
                 let display = mask_proxy(&proxy_url);
@@ -164,15 +920,12 @@ async fn run_bulk(
             }
 
             let start = Instant::now();
-            let opts = Options {
-                proxy_url: Some(proxy_url.clone()),
-                browser_name: browser,
-                timezone_iana: timezone,
-                verbose: false,
-                json_output: false,
-            };
+            let opts = settings.options(Some(proxy_url.clone()), idx, false, false);
             let log = |_msg: &str| {};
-            let result = run(&opts, log).await;
+            let result = settings.scan(&opts, cached_os.as_deref(), log).await;
+            let (bytes, _) = detect::ScanError::bandwidth(&result);
+            run_bytes.fetch_add(bytes, Ordering::Relaxed);
+            let preset = preset_for_result(&settings.browser, &result);
             let (ip_info, ipapi_error) = if ipapi_enabled {
                 match ipapi::lookup(Some(proxy_url.as_str()), &preset).await {
                     Ok(info) => (Some(info), None),
@@ -190,24 +943,52 @@ async fn run_bulk(
                 (None, None)
             };
             let elapsed = start.elapsed().as_secs_f64();
-            (idx, proxy_url, result, ip_info, ipapi_error, elapsed)
+            (idx, proxy_url, Some(result), ip_info, ipapi_error, elapsed)
         }
     }))
     .buffer_unordered(concurrency);
 
-    let mut clean_count: usize = 0;
-    let mut detected_count: usize = 0;
-    let mut filtered_count: usize = 0;
-    let mut error_count: usize = 0;
-    let mut ipapi_abuser_score_sum: f64 = 0.0;
-    let mut ipapi_abuser_score_count: usize = 0;
-    let mut completed_count: usize = 0;
+    // After a shutdown request, in-flight scans get until this deadline.
+    let mut deadline: Option<tokio::time::Instant> = None;
+    loop {
+        let next = tokio::select! {
+            next = results.next() => next,
+            _ = shutdown.wait(), if deadline.is_none() => {
+                deadline = Some(tokio::time::Instant::now() + SHUTDOWN_GRACE);
+                continue;
+            }
+            _ = tokio::time::sleep_until(deadline.unwrap_or_else(tokio::time::Instant::now)),
+                if deadline.is_some() => {
+                eprintln!("Shutdown deadline reached, abandoning in-flight scans");
+                break;
+            }
+        };
+        let Some((_idx, proxy_url, result, ip_info, ipapi_error, elapsed)) = next else {
+            break;
+        };
 
-    while let Some((_idx, proxy_url, result, ip_info, ipapi_error, elapsed)) = results.next().await
-    {
+        let display = mask_proxy(&proxy_url);
+        // Skipped proxies stay out of the outputs and the journal so a resumed
+        // run scans them.
+        let Some(result) = result else {
+            if shutdown.requested() {
+                continue;
+            }
+            completed_count += 1;
+            cost_tally.skipped += 1;
+            if !json_output {
+                let progress = format!("[{}/{}]", completed_count, total);
+                let err = "skipped: run bandwidth budget exhausted";
+                output::render_bulk_error(&progress, &display, err, 0.0, None);
+            }
+            continue;
+        };
         completed_count += 1;
         let progress = format!("[{}/{}]", completed_count, total);
-        let display = mask_proxy(&proxy_url);
+        cost_tally.add(&proxy_url, &result, &budget.pricing);
+        if let Some(os) = result.as_ref().ok().and_then(observed_os) {
+            os_updates.push((proxy_url.clone(), os));
+        }
         if verbose {
             if let Some(ref err) = ipapi_error {
                 eprintln!("ipapi lookup failed for {}: {}", display, err);
@@ -229,6 +1010,13 @@ async fn run_bulk(
                 .unwrap_or(false);
             if !(main_clean && ipapi_clean) {
                 filtered_count += 1;
+                journal_scan(
+                    &mut journal,
+                    &proxy_url,
+                    "filtered",
+                    &result,
+                    ip_info.as_ref(),
+                );
                 continue;
             }
         }
@@ -250,7 +1038,7 @@ async fn run_bulk(
                     ),
                     Err(ref err) => output::render_bulk_json_error(
                         &proxy_url,
-                        &err.to_string(),
+                        err.as_ref(),
                         ip_info.as_ref(),
                         true,
                         max_fraud_score,
@@ -285,16 +1073,34 @@ async fn run_bulk(
                 let _ = writeln!(
                     file,
                     "{}",
-                    output::csv_row(&proxy_url, res, ip_info.as_ref(), ipapi_enabled)
+                    output::csv_row(
+                        &proxy_url,
+                        res,
+                        ip_info.as_ref(),
+                        ipapi_enabled,
+                        include_hardware,
+                    )
                 );
             } else if let (Some(ref mut file), Err(ref err)) = (&mut csv_file, &result) {
                 let _ = writeln!(
                     file,
                     "{}",
-                    output::csv_error_row(&proxy_url, &err.to_string(), ipapi_enabled)
+                    output::csv_error_row(
+                        &proxy_url,
+                        err.as_ref(),
+                        ipapi_enabled,
+                        include_hardware,
+                    )
                 );
             }
 
+            journal_scan(
+                &mut journal,
+                &proxy_url,
+                "filtered",
+                &result,
+                ip_info.as_ref(),
+            );
             continue;
         }
 
@@ -318,6 +1124,12 @@ async fn run_bulk(
                         &status,
                         ip_info.as_ref(),
                     );
+                    if !res.preset_attempts.is_empty() {
+                        output::render_bulk_attempts_line(res);
+                    }
+                    if let Some(base) = baseline {
+                        output::render_bulk_delta_line(&detect::BaselineDelta::compare(base, res));
+                    }
                     if verbose {
                         output::render_table(res, &res.exit_ip, true);
                         if let Some(ref info) = ip_info {
@@ -329,20 +1141,33 @@ async fn run_bulk(
                     let _ = writeln!(
                         file,
                         "{}",
-                        output::csv_row(&proxy_url, res, ip_info.as_ref(), ipapi_enabled)
+                        output::csv_row(
+                            &proxy_url,
+                            res,
+                            ip_info.as_ref(),
+                            ipapi_enabled,
+                            include_hardware,
+                        )
                     );
                 }
-                match status {
-                    output::BulkStatus::Clean => clean_count += 1,
-                    output::BulkStatus::Detected => detected_count += 1,
-                }
+                let outcome = match status {
+                    output::BulkStatus::Clean => {
+                        clean_count += 1;
+                        "clean"
+                    }
+                    output::BulkStatus::Detected => {
+                        detected_count += 1;
+                        "detected"
+                    }
+                };
+                journal_scan(&mut journal, &proxy_url, outcome, &result, ip_info.as_ref());
             }
             Err(ref err) => {
                 error_count += 1;
                 if json_output {
                     output::render_bulk_json_error(
                         &proxy_url,
-                        &err.to_string(),
+                        err.as_ref(),
                         ip_info.as_ref(),
                         false,
                         max_fraud_score,
@@ -360,13 +1185,26 @@ async fn run_bulk(
                     let _ = writeln!(
                         file,
                         "{}",
-                        output::csv_error_row(&proxy_url, &err.to_string(), ipapi_enabled)
+                        output::csv_error_row(
+                            &proxy_url,
+                            err.as_ref(),
+                            ipapi_enabled,
+                            include_hardware,
+                        )
                     );
                 }
+                journal_scan(&mut journal, &proxy_url, "error", &result, ip_info.as_ref());
             }
         }
     }
 
+    drop(results);
+    update_os_cache(&mut os_cache, os_cache_path, &os_updates);
+    if let Some(ref mut file) = csv_file {
+        let _ = file.flush();
+    }
+    let _ = std::io::stdout().flush();
+
     output::render_bulk_summary(
         total,
         clean_count,
@@ -380,16 +1218,344 @@ async fn run_bulk(
         },
         ipapi_abuser_score_count,
     );
+    output::render_cost_summary(&cost_tally, budget);
+
+    if shutdown.requested() {
+        eprintln!(
+            "Interrupted: {} of {} proxies not scanned{}",
+            total.saturating_sub(completed_count),
+            total,
+            if journal.is_some() {
+                " (rerun with --resume to continue)"
+            } else {
+                ""
+            }
+        );
+    }
+    if let Some(path) = csv_path {
+        eprintln!("Results written to {}", path);
+    }
+}
+
+/// Scan each proxy `repeat` times and report statistics over the runs instead of
+/// a single verdict. `None` scans the direct connection.
+async fn run_repeat(
+    proxies: Vec<Option<String>>,
+    settings: &ScanSettings,
+    repeat: u32,
+    verbose: bool,
+    json_output: bool,
+    concurrency: usize,
+    csv_path: Option<&str>,
+) {
+    let total = proxies.len();
+    let concurrency = concurrency.max(1);
+
+    if !json_output {
+        eprintln!(
+            "Repeated scan: {} proxies x {} scans, concurrency {}",
+            total, repeat, concurrency
+        );
+    }
+
+    let mut csv_file = csv_path.map(|path| {
+        let mut file = std::fs::File::create(path).expect("failed to create CSV file");
+        writeln!(file, "{}", output::repeat_csv_header()).unwrap();
+        file
+    });
+
+    let mut summaries = stream::iter(proxies.into_iter().enumerate().map(|(idx, proxy_url)| {
+        let settings = settings.clone();
+        async move {
+            let display = proxy_url
+                .as_deref()
+                .map(mask_proxy)
+                .unwrap_or_else(|| "direct".to_string());
+            if !json_output && total > 1 {
+                output::render_bulk_start_line(&display, idx + 1, total);
+            }
+
+            let opts = settings.options(proxy_url.clone(), idx, false, false);
+            let mut results = Vec::with_capacity(repeat as usize);
+            for round in 1..=repeat {
+                let result = run(&opts, |_msg: &str| {}).await;
+                if verbose {
+                    let outcome = match &result {
+                        Ok(res) => match output::classify_result(res) {
+                            output::BulkStatus::Detected => "detected".to_string(),
+                            output::BulkStatus::Clean => "clean".to_string(),
+                        },
+                        Err(err) => format!("error: {}", err),
+                    };
+                    eprintln!("  {} scan {}/{}: {}", display, round, repeat, outcome);
+                }
+                results.push(result.map_err(|err| err.to_string()));
+            }
+            (
+                proxy_url,
+                display,
+                detect::RepeatSummary::from_results(&results),
+            )
+        }
+    }))
+    .buffer_unordered(concurrency);
+
+    while let Some((proxy_url, display, summary)) = summaries.next().await {
+        let proxy_raw = proxy_url.as_deref().unwrap_or("direct");
+        if json_output {
+            output::render_repeat_json_line(proxy_raw, &summary);
+        } else {
+            output::render_repeat_summary(&display, &summary);
+        }
+        if let Some(ref mut file) = csv_file {
+            let _ = writeln!(file, "{}", output::repeat_csv_row(proxy_raw, &summary));
+        }
+    }
+
+    if let Some(path) = csv_path {
+        eprintln!("Results written to {}", path);
+    }
+}
+
+/// Scan every proxy with every preset/timezone combination and report a verdict
+/// grid with per-combination clean rates. `None` scans the direct connection.
+async fn run_matrix(
+    proxies: Vec<Option<String>>,
+    settings: &ScanSettings,
+    combos: &[output::MatrixCombo],
+    json_output: bool,
+    concurrency: usize,
+    csv_path: Option<&str>,
+) {
+    let total = proxies.len();
+    let concurrency = concurrency.max(1);
+
+    if !json_output {
+        eprintln!(
+            "Matrix scan: {} proxies x {} combinations, concurrency {}",
+            total,
+            combos.len(),
+            concurrency
+        );
+        eprintln!();
+        output::render_matrix_header(combos);
+    }
+
+    let mut csv_file = csv_path.map(|path| {
+        let mut file = std::fs::File::create(path).expect("failed to create CSV file");
+        writeln!(file, "{}", output::matrix_csv_header()).unwrap();
+        file
+    });
+
+    let mut rows = stream::iter(proxies.into_iter().enumerate().map(|(idx, proxy_url)| {
+        let settings = settings.clone();
+        async move {
+            let mut cells = Vec::with_capacity(combos.len());
+            for combo in combos {
+                let mut opts = settings.options(proxy_url.clone(), idx, false, false);
+                opts.browser_name = combo.preset.clone();
+                // An "auto" column (`None`) derives the zone from the exit IP even
+                // with --timezone; without --matrix-timezones the combo carries it.
+                opts.timezone_iana = combo.timezone.clone();
+                let result = run(&opts, |_msg: &str| {}).await;
+                cells.push(result.map_err(|err| err.to_string()));
+            }
+            (proxy_url, cells)
+        }
+    }))
+    .buffer_unordered(concurrency);
+
+    let mut tallies = vec![output::MatrixTally::default(); combos.len()];
+    while let Some((proxy_url, cells)) = rows.next().await {
+        for (tally, cell) in tallies.iter_mut().zip(&cells) {
+            tally.add(cell);
+        }
+
+        let proxy_raw = proxy_url.as_deref().unwrap_or("direct");
+        if json_output {
+            output::render_matrix_json_line(proxy_raw, combos, &cells);
+        } else {
+            let display = proxy_url
+                .as_deref()
+                .map(mask_proxy)
+                .unwrap_or_else(|| "direct".to_string());
+            output::render_matrix_row(&display, &cells);
+        }
+        if let Some(ref mut file) = csv_file {
+            for (combo, cell) in combos.iter().zip(&cells) {
+                let _ = writeln!(file, "{}", output::matrix_csv_row(proxy_raw, combo, cell));
+            }
+        }
+    }
 
+    if json_output {
+        output::render_matrix_json_summary(combos, &tallies);
+    } else {
+        output::render_matrix_summary(combos, &tallies);
+    }
     if let Some(path) = csv_path {
         eprintln!("Results written to {}", path);
     }
 }
 
+/// Preset/timezone combinations for `--matrix`, exiting on an unknown preset or zone.
+fn matrix_combos(cli: &Cli, settings: &ScanSettings) -> Vec<output::MatrixCombo> {
+    let mut presets = Vec::new();
+    for name in &cli.matrix {
+        if name == "all" {
+            presets.extend(browser::PRESET_NAMES.iter().map(|n| n.to_string()));
+            continue;
+        }
+        match resolve_preset(name) {
+            Ok(name) => presets.push(name),
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let timezones: Vec<Option<String>> = if cli.matrix_timezones.is_empty() {
+        vec![settings.timezone.clone()]
+    } else {
+        cli.matrix_timezones
+            .iter()
+            .map(|zone| (zone != "auto").then(|| zone.clone()))
+            .collect()
+    };
+    for zone in timezones.iter().flatten() {
+        if let Err(err) = timezone::resolve(zone, &locale::Locale::default(), &settings.clock) {
+            eprintln!("Error: invalid timezone {}: {}", zone, err);
+            std::process::exit(1);
+        }
+    }
+
+    presets
+        .iter()
+        .flat_map(|preset| {
+            timezones.iter().map(move |zone| output::MatrixCombo {
+                preset: preset.clone(),
+                timezone: zone.clone(),
+            })
+        })
+        .collect()
+}
+
 #[tokio::main]
 async fn main() {
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
+    if cli.browser.ends_with(".json") {
+        match resolve_preset(&cli.browser) {
+            Ok(name) => cli.browser = name,
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                std::process::exit(1);
+            }
+        }
+    }
     let ipapi_enabled = cli.ipapi || cli.max_fraud_score.is_some() || cli.clean;
+    let hardware_seed = cli
+        .random_hardware
+        .then(|| cli.seed.unwrap_or_else(rand::random));
+
+    let settings = ScanSettings::from_cli(&cli, hardware_seed);
+
+    if let Some(ref command) = cli.command {
+        match command {
+            Command::Presets {
+                action: PresetsAction::Check { names },
+            } => {
+                run_presets_check(names);
+                return;
+            }
+            Command::Presets {
+                action:
+                    PresetsAction::Wire {
+                        names,
+                        references,
+                        record,
+                    },
+            } => {
+                run_wire_check(names, references, record.as_deref()).await;
+                return;
+            }
+            Command::Presets {
+                action: PresetsAction::ImportHar { file, name, output },
+            } => {
+                run_import_har(file, name.as_deref(), output.as_deref());
+                return;
+            }
+            Command::Selftest { names } => {
+                run_selftest(names).await;
+                return;
+            }
+            Command::Diff {
+                old,
+                new,
+                by_exit_ip,
+            } => {
+                run_diff(old, new, *by_exit_ip);
+                return;
+            }
+            Command::Fingerprint { scan } => {
+                run_fingerprint(&settings);
+                if !scan {
+                    return;
+                }
+                println!();
+            }
+        }
+    }
+
+    if let (Some(seed), false) = (hardware_seed, cli.json) {
+        eprintln!("Hardware seed: {}", seed);
+    }
+
+    if !cli.matrix.is_empty() {
+        let combos = matrix_combos(&cli, &settings);
+        let proxies = scan_targets(&cli);
+
+        run_matrix(
+            proxies,
+            &settings,
+            &combos,
+            cli.json,
+            cli.concurrency,
+            cli.csv.as_deref(),
+        )
+        .await;
+        return;
+    }
+
+    if cli.repeat > 1 {
+        let proxies = scan_targets(&cli);
+
+        run_repeat(
+            proxies,
+            &settings,
+            cli.repeat,
+            cli.verbose,
+            cli.json,
+            cli.concurrency,
+            cli.csv.as_deref(),
+        )
+        .await;
+        return;
+    }
+
+    let baseline = if cli.baseline {
+        eprintln!("Scanning direct baseline...");
+        let opts = settings.options(None, 0, false, false);
+        match run(&opts, |_msg: &str| {}).await {
+            Ok(result) => Some(result),
+            Err(err) => {
+                eprintln!("Baseline scan failed: {}", err);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        None
+    };
 
     // Bulk mode: --file takes precedence
     if let Some(ref path) = cli.file {
@@ -400,17 +1566,20 @@ async fn main() {
                 std::process::exit(1);
             }
         };
-
-        let timezone = if cli.timezone.is_empty() {
-            None
-        } else {
-            Some(cli.timezone.clone())
+        let journal = match cli.checkpoint.as_deref() {
+            Some(path) => match checkpoint::Journal::open(path, cli.resume) {
+                Ok(journal) => Some(journal),
+                Err(err) => {
+                    eprintln!("Error: {}", err);
+                    std::process::exit(1);
+                }
+            },
+            None => None,
         };
 
         run_bulk(
             proxies,
-            &cli.browser,
-            &timezone,
+            &settings,
             cli.verbose,
             cli.json,
             cli.concurrency,
@@ -418,6 +1587,16 @@ async fn main() {
             ipapi_enabled,
             cli.max_fraud_score,
             cli.clean,
+            baseline.as_ref(),
+            cli.os_cache.as_deref(),
+            &cost::RunBudget {
+                max_bytes: cli.run_budget.map(|mb| mb * 1024 * 1024),
+                pricing: cost::Pricing {
+                    per_gb: cli.price_per_gb,
+                    hosts: cli.host_price.clone(),
+                },
+            },
+            journal,
         )
         .await;
         return;
@@ -430,17 +1609,7 @@ async fn main() {
         Some(normalize_proxy(&cli.proxy))
     };
 
-    let opts = Options {
-        proxy_url: proxy_url.clone(),
-        browser_name: cli.browser.clone(),
-        timezone_iana: if cli.timezone.is_empty() {
-            None
-        } else {
-            Some(cli.timezone.clone())
-        },
-        verbose: cli.verbose,
-        json_output: cli.json,
-    };
+    let opts = settings.options(proxy_url.clone(), 0, cli.verbose, cli.json);
 
     let log = |msg: &str| {
         if opts.verbose {
@@ -457,8 +1626,32 @@ async fn main() {
     }
 
     let single_start = Instant::now();
-    let single_preset = browser::get_preset(&cli.browser);
-    let detection_result = run(&opts, log).await;
+    let mut os_cache = load_os_cache(cli.os_cache.as_deref());
+    let single_key = proxy_url.clone().unwrap_or_else(|| "direct".to_string());
+    let detection_result = if cli.live {
+        run_streaming(&opts, log, |key, raw| {
+            output::render_live_test(key, raw, single_start.elapsed().as_secs_f64())
+        })
+        .await
+    } else {
+        let cached_os = os_cache.get(&single_key).map(str::to_string);
+        settings.scan(&opts, cached_os.as_deref(), log).await
+    };
+    if let Some(os) = detection_result.as_ref().ok().and_then(observed_os) {
+        update_os_cache(&mut os_cache, cli.os_cache.as_deref(), &[(single_key, os)]);
+    }
+    let direct_latencies = if cli.latency && opts.proxy_url.is_some() {
+        match detect::measure_direct_latencies(&opts, log).await {
+            Ok(latencies) => Some(latencies),
+            Err(err) => {
+                eprintln!("Direct baseline failed: {}", err);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let single_preset = preset_for_result(&cli.browser, &detection_result);
     let (ip_info, ipapi_error) = if ipapi_enabled {
         match ipapi::lookup(opts.proxy_url.as_deref(), &single_preset).await {
             Ok(info) => (Some(info), None),
@@ -506,7 +1699,12 @@ async fn main() {
                 if let Some(ref csv_path) = cli.csv {
                     let mut file =
                         std::fs::File::create(csv_path).expect("failed to create CSV file");
-                    writeln!(file, "{}", output::csv_header(ipapi_enabled)).unwrap();
+                    writeln!(
+                        file,
+                        "{}",
+                        output::csv_header(ipapi_enabled, hardware_seed.is_some())
+                    )
+                    .unwrap();
                     eprintln!("Results written to {}", csv_path);
                 }
                 return;
@@ -550,16 +1748,39 @@ async fn main() {
                 if let Some(ref info) = ip_info {
                     output::render_ip_intelligence(info);
                 }
+                if cli.explain {
+                    output::render_explanations(&result);
+                }
+                if cli.latency {
+                    let analysis =
+                        detect::LatencyAnalysis::new(&result, direct_latencies.as_deref());
+                    output::render_latency_analysis(&result, &analysis);
+                }
+                if let Some(ref base) = baseline {
+                    let delta = detect::BaselineDelta::compare(base, &result);
+                    output::render_baseline_delta(&result, &base.exit_ip, &delta);
+                }
             }
 
             if let Some(ref csv_path) = cli.csv {
                 let csv_proxy = proxy_url.clone().unwrap_or_else(|| "direct".to_string());
                 let mut file = std::fs::File::create(csv_path).expect("failed to create CSV file");
-                writeln!(file, "{}", output::csv_header(ipapi_enabled)).unwrap();
                 writeln!(
                     file,
                     "{}",
-                    output::csv_row(&csv_proxy, &result, ip_info.as_ref(), ipapi_enabled)
+                    output::csv_header(ipapi_enabled, hardware_seed.is_some())
+                )
+                .unwrap();
+                writeln!(
+                    file,
+                    "{}",
+                    output::csv_row(
+                        &csv_proxy,
+                        &result,
+                        ip_info.as_ref(),
+                        ipapi_enabled,
+                        hardware_seed.is_some(),
+                    )
                 )
                 .unwrap();
                 eprintln!("Results written to {}", csv_path);
@@ -0,0 +1,274 @@
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// Proxy schemes the relay can forward without terminating TLS.
+const RELAY_SCHEMES: &[&str] = &["http", "socks4", "socks4a", "socks5", "socks5h"];
+
+/// Largest CONNECT request head accepted from the local client.
+const MAX_CONNECT_HEAD: usize = 8192;
+
+/// Bytes sent and received on a connection (TCP payload, TLS and proxy handshakes included).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Traffic {
+    pub sent: u64,
+    pub received: u64,
+}
+
+impl Traffic {
+    pub fn total(&self) -> u64 {
+        self.sent + self.received
+    }
+}
+
+impl std::ops::Sub for Traffic {
+    type Output = Traffic;
+
+    fn sub(self, earlier: Traffic) -> Traffic {
+        Traffic {
+            sent: self.sent.saturating_sub(earlier.sent),
+            received: self.received.saturating_sub(earlier.received),
+        }
+    }
+}
+
+impl std::ops::Add for Traffic {
+    type Output = Traffic;
+
+    fn add(self, other: Traffic) -> Traffic {
+        Traffic {
+            sent: self.sent + other.sent,
+            received: self.received + other.received,
+        }
+    }
+}
+
+/// Running byte totals shared by every connection of a channel.
+#[derive(Debug, Default)]
+pub struct ByteCounter {
+    sent: AtomicU64,
+    received: AtomicU64,
+}
+
+impl ByteCounter {
+    pub fn snapshot(&self) -> Traffic {
+        Traffic {
+            sent: self.sent.load(Ordering::Relaxed),
+            received: self.received.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Stream wrapper adding every byte read or written to a counter.
+pub struct CountingStream<S> {
+    inner: S,
+    counter: Arc<ByteCounter>,
+}
+
+impl<S> CountingStream<S> {
+    pub fn new(inner: S, counter: Arc<ByteCounter>) -> CountingStream<S> {
+        CountingStream { inner, counter }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for CountingStream<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let before = buf.filled().len();
+        let poll = Pin::new(&mut self.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = poll {
+            let read = (buf.filled().len() - before) as u64;
+            self.counter.received.fetch_add(read, Ordering::Relaxed);
+        }
+        poll
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for CountingStream<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let poll = Pin::new(&mut self.inner).poll_write(cx, buf);
+        if let Poll::Ready(Ok(written)) = poll {
+            self.counter
+                .sent
+                .fetch_add(written as u64, Ordering::Relaxed);
+        }
+        poll
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+/// Local relay the HTTP client connects through so its traffic can be counted.
+///
+/// With an upstream proxy the relay forwards raw bytes to it, so the proxy
+/// handshake is counted too; without one it accepts CONNECT requests and counts
+/// only the tunnelled bytes.
+pub struct Relay {
+    proxy_url: String,
+    counter: Arc<ByteCounter>,
+    task: JoinHandle<()>,
+}
+
+impl Relay {
+    pub async fn start(
+        upstream: Option<&str>,
+    ) -> Result<Relay, Box<dyn std::error::Error + Send + Sync>> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let local = listener.local_addr()?;
+        let counter = Arc::new(ByteCounter::default());
+
+        let (proxy_url, target) = match upstream {
+            Some(proxy) => {
+                let (url, target) = route_through(proxy, local)?;
+                (url, Some(target))
+            }
+            None => (format!("http://{}", local), None),
+        };
+
+        let task_counter = counter.clone();
+        let task = tokio::spawn(async move {
+            while let Ok((inbound, _)) = listener.accept().await {
+                let counter = task_counter.clone();
+                let target = target.clone();
+                tokio::spawn(async move {
+                    let _ = match target {
+                        Some(target) => forward(inbound, &target, counter).await,
+                        None => tunnel(inbound, counter).await,
+                    };
+                });
+            }
+        });
+
+        Ok(Relay {
+            proxy_url,
+            counter,
+            task,
+        })
+    }
+
+    /// Proxy URL the HTTP client should use instead of the upstream one.
+    pub fn proxy_url(&self) -> &str {
+        &self.proxy_url
+    }
+
+    pub fn traffic(&self) -> Traffic {
+        self.counter.snapshot()
+    }
+}
+
+impl Drop for Relay {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Point the proxy URL at the local relay, keeping scheme and credentials, and
+/// return it with the upstream `host:port`.
+fn route_through(
+    proxy: &str,
+    local: SocketAddr,
+) -> Result<(String, String), Box<dyn std::error::Error + Send + Sync>> {
+    let mut url = url::Url::parse(proxy)?;
+    if !RELAY_SCHEMES.contains(&url.scheme()) {
+        return Err(format!("{} proxies cannot be metered", url.scheme()).into());
+    }
+    let host = url
+        .host_str()
+        .ok_or_else(|| format!("proxy URL {} has no host", proxy))?
+        .to_string();
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| format!("proxy URL {} has no port", proxy))?;
+
+    url.set_ip_host(local.ip())
+        .map_err(|_| format!("cannot route {} through the relay", proxy))?;
+    url.set_port(Some(local.port()))
+        .map_err(|_| format!("cannot route {} through the relay", proxy))?;
+    Ok((url.to_string(), format!("{}:{}", host, port)))
+}
+
+async fn forward(
+    mut inbound: TcpStream,
+    target: &str,
+    counter: Arc<ByteCounter>,
+) -> io::Result<()> {
+    let outbound = TcpStream::connect(target).await?;
+    let mut outbound = CountingStream::new(outbound, counter);
+    tokio::io::copy_bidirectional(&mut inbound, &mut outbound).await?;
+    Ok(())
+}
+
+async fn tunnel(mut inbound: TcpStream, counter: Arc<ByteCounter>) -> io::Result<()> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    let header_end = loop {
+        let read = inbound.read(&mut buf).await?;
+        if read == 0 {
+            return Ok(());
+        }
+        head.extend_from_slice(&buf[..read]);
+        if let Some(pos) = head.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        if head.len() > MAX_CONNECT_HEAD {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "CONNECT head too long",
+            ));
+        }
+    };
+
+    let request = String::from_utf8_lossy(&head[..header_end]);
+    let target = match request
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .split(' ')
+        .collect::<Vec<_>>()[..]
+    {
+        ["CONNECT", target, _] => target.to_string(),
+        _ => {
+            inbound
+                .write_all(b"HTTP/1.1 405 Method Not Allowed\r\n\r\n")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let outbound = match TcpStream::connect(&target).await {
+        Ok(stream) => stream,
+        Err(e) => {
+            inbound
+                .write_all(b"HTTP/1.1 502 Bad Gateway\r\n\r\n")
+                .await?;
+            return Err(e);
+        }
+    };
+    inbound
+        .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+        .await?;
+
+    let mut outbound = CountingStream::new(outbound, counter);
+    outbound.write_all(&head[header_end..]).await?;
+    tokio::io::copy_bidirectional(&mut inbound, &mut outbound).await?;
+    Ok(())
+}
@@ -0,0 +1,182 @@
+use crate::browser::presets_matching_os;
+use crate::detect::{ChannelStats, DetectionResult};
+use serde_json::Value;
+
+/// Plain-language cause of a failed test and what would change the outcome.
+pub(super) struct Explanation {
+    pub cause: String,
+    pub remedy: String,
+}
+
+/// Explain why `key` flagged the connection, using its `info` values where the
+/// engine provides them.
+pub(super) fn explain(key: &str, raw: &Value, result: &DetectionResult) -> Explanation {
+    let info = raw.get("info");
+    let field = |name: &str| info.and_then(|i| i.get(name)).map(value_text);
+
+    let (cause, remedy) = match key {
+        "tcpip_fp" => {
+            let tcp_os = field("tcpIpHighestOs").unwrap_or_else(|| "another OS".to_string());
+            let ua_os = field("userAgentOs").unwrap_or_else(|| "the preset OS".to_string());
+            let presets = presets_matching_os(&tcp_os);
+            let remedy = if presets.is_empty() {
+                format!(
+                    "Use a preset file whose User-Agent claims {}, or a proxy whose exit host runs {}",
+                    tcp_os, ua_os
+                )
+            } else {
+                format!(
+                    "Use a preset whose OS matches the TCP/IP stack (--browser {}), or a proxy whose exit host runs {}",
+                    presets.join(" or --browser "),
+                    ua_os
+                )
+            };
+            (
+                format!(
+                    "TCP/IP stack of the exit host looks like {} but the User-Agent claims {}",
+                    tcp_os, ua_os
+                ),
+                remedy,
+            )
+        }
+        "timezone" => {
+            // The engine reports only `isProxyByTimezone`, not the zones it compared.
+            let reported = if result.timezone.is_empty() {
+                "(unknown)"
+            } else {
+                result.timezone.as_str()
+            };
+            (
+                format!(
+                    "Presented timezone {} does not match the exit IP location",
+                    reported
+                ),
+                "Drop --timezone so the zone is derived from the exit IP, or override it with the exit location's zone".to_string(),
+            )
+        }
+        "datacenter_ip" => (
+            format!(
+                "Exit IP {} belongs to a hosting/datacenter range",
+                result.exit_ip
+            ),
+            "Use a residential or mobile proxy pool".to_string(),
+        ),
+        "proxy_ip" => (
+            format!(
+                "Exit IP {} is listed in known proxy databases",
+                result.exit_ip
+            ),
+            "Rotate to a fresh residential IP that has not been used as a public proxy".to_string(),
+        ),
+        "vpn_ip" => (
+            format!("Exit IP {} is attributed to a VPN provider", result.exit_ip),
+            "Use a residential or mobile proxy pool instead of VPN endpoints".to_string(),
+        ),
+        "enumerated_vpn_ip" => (
+            format!(
+                "Exit IP {} was found by enumerating commercial VPN servers",
+                result.exit_ip
+            ),
+            "Use an exit outside commercial VPN networks".to_string(),
+        ),
+        "latency_vs_ping" | "latency" | "high_latencies" => {
+            let image = ChannelStats::from_samples(&result.image_latencies);
+            let ws = ChannelStats::from_samples(&result.ws_latencies);
+            let measured = match (image, ws) {
+                (Some(image), Some(ws)) => format!(
+                    " (image median {:.0}ms vs WebSocket median {:.0}ms)",
+                    image.median, ws.median
+                ),
+                (Some(image), None) => format!(" (image median {:.0}ms)", image.median),
+                _ => String::new(),
+            };
+            let cause = match key {
+                "latency_vs_ping" => {
+                    "HTTP round-trips exceed the ping time to the exit IP, revealing an extra hop"
+                }
+                "latency" => "Round-trip times do not fit the exit IP's location",
+                _ => "Round-trip times are unusually high for a direct connection",
+            };
+            (
+                format!("{}{}", cause, measured),
+                "Use a proxy whose server sits close to both the client and its exit IP; --latency shows the overhead".to_string(),
+            )
+        }
+        "http_headers" => (
+            "Request headers reveal an intermediary (e.g. Via or X-Forwarded-For)".to_string(),
+            "Use an anonymous (elite) proxy that does not add forwarding headers".to_string(),
+        ),
+        "flow_pattern" => (
+            match field("numFlows") {
+                Some(flows) => format!("Traffic pattern over {} flows looks tunnelled", flows),
+                None => "Traffic pattern looks tunnelled".to_string(),
+            },
+            "Use a proxy that terminates connections at the exit host rather than a tunnel"
+                .to_string(),
+        ),
+        "proxy_ai" | "vpn_ai" => (
+            match (field("label"), field("score")) {
+                (Some(label), Some(score)) => {
+                    format!("Model labelled the connection {} (score {})", label, score)
+                }
+                _ => "Model combined the other signals into a positive verdict".to_string(),
+            },
+            "Fix the failing network tests above; the model follows them".to_string(),
+        ),
+        "tor_detection" => (
+            format!("Exit IP {} is a Tor exit node", result.exit_ip),
+            "Use a proxy that does not route through Tor".to_string(),
+        ),
+        _ => (
+            "Flagged by the engine".to_string(),
+            "No specific remedy known for this test".to_string(),
+        ),
+    };
+
+    let cause = match field("error").or_else(|| field("message")) {
+        Some(note) => format!("{} [{}]", cause, note),
+        None => cause,
+    };
+    Explanation { cause, remedy }
+}
+
+fn value_text(value: &Value) -> String {
+    match value.as_str() {
+        Some(s) => s.to_string(),
+        None => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn timezone_names_the_presented_zone() {
+        let result = DetectionResult {
+            timezone: "Europe/Berlin".to_string(),
+            ..Default::default()
+        };
+        let raw = json!({"is_proxy": true, "info": {"isProxyByTimezone": true}});
+        assert_eq!(
+            explain("timezone", &raw, &result).cause,
+            "Presented timezone Europe/Berlin does not match the exit IP location"
+        );
+        let raw = json!({"is_proxy": true});
+        assert_eq!(
+            explain("timezone", &raw, &DetectionResult::default()).cause,
+            "Presented timezone (unknown) does not match the exit IP location"
+        );
+    }
+
+    #[test]
+    fn notes_from_the_engine_are_appended() {
+        let result = DetectionResult::default();
+        let raw = json!({"is_proxy": true, "info": {"message": "Via: 1.1 squid"}});
+        assert_eq!(
+            explain("http_headers", &raw, &result).cause,
+            "Request headers reveal an intermediary (e.g. Via or X-Forwarded-For) [Via: 1.1 squid]"
+        );
+    }
+}
@@ -0,0 +1,275 @@
+use super::{classify_result, csv_quote, extract_verdict, BulkStatus};
+use crate::detect::DetectionResult;
+use serde_json::Value;
+
+/// One preset/timezone combination of a matrix scan.
+#[derive(Clone, Debug)]
+pub struct MatrixCombo {
+    pub preset: String,
+    /// Timezone override; `None` derives it from the exit IP.
+    pub timezone: Option<String>,
+}
+
+impl MatrixCombo {
+    pub fn label(&self) -> String {
+        format!(
+            "{} / {}",
+            self.preset,
+            self.timezone.as_deref().unwrap_or("auto")
+        )
+    }
+}
+
+/// Scans of one combination across the pool.
+#[derive(Clone, Debug, Default)]
+pub struct MatrixTally {
+    pub clean: usize,
+    pub detected: usize,
+    pub errors: usize,
+}
+
+impl MatrixTally {
+    pub fn add(&mut self, result: &Result<DetectionResult, String>) {
+        match result {
+            Ok(res) => match classify_result(res) {
+                BulkStatus::Clean => self.clean += 1,
+                BulkStatus::Detected => self.detected += 1,
+            },
+            Err(_) => self.errors += 1,
+        }
+    }
+
+    /// Clean scans over all scans, errors included.
+    pub fn clean_rate(&self) -> f64 {
+        let total = self.clean + self.detected + self.errors;
+        if total == 0 {
+            0.0
+        } else {
+            self.clean as f64 / total as f64
+        }
+    }
+}
+
+fn cell_label(result: &Result<DetectionResult, String>) -> &'static str {
+    match result {
+        Ok(res) => {
+            let verdict = extract_verdict(&res.tests);
+            match (verdict.proxy_detected, verdict.vpn_detected) {
+                (true, true) => "PROXY+VPN",
+                (true, false) => "PROXY",
+                (false, true) => "VPN",
+                (false, false) => "clean",
+            }
+        }
+        Err(_) => "error",
+    }
+}
+
+/// Print the column legend of the verdict grid.
+pub fn render_matrix_header(combos: &[MatrixCombo]) {
+    println!("Columns:");
+    for (idx, combo) in combos.iter().enumerate() {
+        println!("  [{}] {}", idx + 1, combo.label());
+    }
+    let columns: Vec<String> = (1..=combos.len())
+        .map(|idx| format!("{:<10}", format!("[{}]", idx)))
+        .collect();
+    println!();
+    println!("{:<30} {}", "proxy", columns.join(" "));
+}
+
+/// Print one grid row: the aggregate verdict of every combination for a proxy.
+pub fn render_matrix_row(proxy_display: &str, cells: &[Result<DetectionResult, String>]) {
+    let columns: Vec<String> = cells
+        .iter()
+        .map(|cell| format!("{:<10}", cell_label(cell)))
+        .collect();
+    println!("{:<30} {}", proxy_display, columns.join(" "));
+}
+
+/// Print each combination's clean rate across the pool, marking the best one.
+pub fn render_matrix_summary(combos: &[MatrixCombo], tallies: &[MatrixTally]) {
+    let divider = "=".repeat(64);
+    let best = best_combo(tallies);
+
+    println!();
+    println!("{}", divider);
+    println!("  Matrix Summary (clean rate per combination)");
+    println!("{}", divider);
+    for (idx, (combo, tally)) in combos.iter().zip(tallies).enumerate() {
+        let icon = if Some(idx) == best { "[ok]" } else { "[ ]" };
+        println!(
+            "  {} {:<36} {:>5.1}% clean={} detected={} errors={}{}",
+            icon,
+            combo.label(),
+            tally.clean_rate() * 100.0,
+            tally.clean,
+            tally.detected,
+            tally.errors,
+            if Some(idx) == best { "  <- best" } else { "" },
+        );
+    }
+    println!("{}", divider);
+}
+
+/// Index of the combination with the highest clean rate (first one on ties).
+fn best_combo(tallies: &[MatrixTally]) -> Option<usize> {
+    tallies
+        .iter()
+        .enumerate()
+        .filter(|(_, tally)| tally.clean > 0)
+        .fold(None, |best: Option<(usize, f64)>, (idx, tally)| {
+            let rate = tally.clean_rate();
+            match best {
+                Some((_, best_rate)) if best_rate >= rate => best,
+                _ => Some((idx, rate)),
+            }
+        })
+        .map(|(idx, _)| idx)
+}
+
+fn cell_json(combo: &MatrixCombo, result: &Result<DetectionResult, String>) -> Value {
+    let (exit_ip, error) = match result {
+        Ok(res) => (Some(res.exit_ip.as_str()), None),
+        Err(err) => (None, Some(err.as_str())),
+    };
+    serde_json::json!({
+        "preset": combo.preset,
+        "timezone": combo.timezone,
+        "verdict": cell_label(result),
+        "exit_ip": exit_ip,
+        "error": error,
+    })
+}
+
+/// Print a single NDJSON line with every combination's verdict for a proxy.
+pub fn render_matrix_json_line(
+    proxy_raw: &str,
+    combos: &[MatrixCombo],
+    cells: &[Result<DetectionResult, String>],
+) {
+    let cells: Vec<Value> = combos
+        .iter()
+        .zip(cells)
+        .map(|(combo, cell)| cell_json(combo, cell))
+        .collect();
+    let line = serde_json::json!({
+        "proxy": proxy_raw,
+        "cells": cells,
+    });
+    println!("{}", serde_json::to_string(&line).unwrap_or_default());
+}
+
+/// Print the closing NDJSON line with per-combination clean rates.
+pub fn render_matrix_json_summary(combos: &[MatrixCombo], tallies: &[MatrixTally]) {
+    let best = best_combo(tallies);
+    let rows: Vec<Value> = combos
+        .iter()
+        .zip(tallies)
+        .enumerate()
+        .map(|(idx, (combo, tally))| {
+            serde_json::json!({
+                "preset": combo.preset,
+                "timezone": combo.timezone,
+                "clean": tally.clean,
+                "detected": tally.detected,
+                "errors": tally.errors,
+                "clean_rate": tally.clean_rate(),
+                "best": Some(idx) == best,
+            })
+        })
+        .collect();
+    let line = serde_json::json!({ "summary": rows });
+    println!("{}", serde_json::to_string(&line).unwrap_or_default());
+}
+
+/// CSV header row for matrix scans: one row per proxy and combination.
+pub fn matrix_csv_header() -> String {
+    "proxy,preset,timezone,exit_ip,verdict,error".to_string()
+}
+
+/// Format one matrix cell as a CSV row.
+pub fn matrix_csv_row(
+    proxy_display: &str,
+    combo: &MatrixCombo,
+    result: &Result<DetectionResult, String>,
+) -> String {
+    let (exit_ip, error) = match result {
+        Ok(res) => (res.exit_ip.as_str(), ""),
+        Err(err) => ("", err.as_str()),
+    };
+    format!(
+        "{},{},{},{},{},{}",
+        csv_quote(proxy_display),
+        csv_quote(&combo.preset),
+        csv_quote(combo.timezone.as_deref().unwrap_or("auto")),
+        csv_quote(exit_ip),
+        cell_label(result),
+        csv_quote(error),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tally(clean: usize, detected: usize, errors: usize) -> MatrixTally {
+        MatrixTally {
+            clean,
+            detected,
+            errors,
+        }
+    }
+
+    #[test]
+    fn clean_rate_counts_errors_against_the_combo() {
+        assert_eq!(tally(3, 0, 1).clean_rate(), 0.75);
+        assert_eq!(tally(0, 2, 2).clean_rate(), 0.0);
+        assert_eq!(tally(0, 0, 0).clean_rate(), 0.0);
+    }
+
+    #[test]
+    fn add_sorts_scans_into_clean_detected_and_errors() {
+        let mut detected = DetectionResult::default();
+        detected
+            .tests
+            .insert("proxy".to_string(), serde_json::json!({ "isProxy": true }));
+
+        let mut counts = MatrixTally::default();
+        counts.add(&Ok(DetectionResult::default()));
+        counts.add(&Ok(detected));
+        counts.add(&Err("timed out".to_string()));
+        assert_eq!((counts.clean, counts.detected, counts.errors), (1, 1, 1));
+    }
+
+    #[test]
+    fn best_combo_picks_the_highest_clean_rate() {
+        assert_eq!(
+            best_combo(&[tally(1, 3, 0), tally(3, 1, 0), tally(2, 2, 0)]),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn best_combo_keeps_the_first_on_ties() {
+        assert_eq!(
+            best_combo(&[tally(0, 4, 0), tally(2, 2, 0), tally(1, 1, 0)]),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn best_combo_skips_error_only_columns() {
+        // A column of errors has no clean scan, even though its rate ties at zero.
+        assert_eq!(
+            best_combo(&[tally(0, 0, 4), tally(1, 2, 1), tally(0, 0, 0)]),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn best_combo_is_none_without_a_clean_scan() {
+        assert_eq!(best_combo(&[tally(0, 4, 0), tally(0, 0, 4)]), None);
+        assert_eq!(best_combo(&[]), None);
+    }
+}
@@ -1,8 +1,24 @@
-use crate::detect::DetectionResult;
+use crate::browser::{HardwareProfile, Mismatch, Preset};
+use crate::capture::{CheckStatus, FingerprintCheck, WireOutcome, WireReport};
+use crate::cost::{CostTally, RunBudget};
+use crate::detect::{
+    test_detected, BaselineDelta, BlockStats, ChannelStats, DetectionResult, LatencyAnalysis,
+    PresetAttempt, RepeatSummary, ScanError, TimingSpan, TrafficReport,
+};
+use crate::diff::ScanDiff;
 use crate::ipapi::IpInfo;
+use crate::meter::Traffic;
 use serde_json::{Map, Value};
 use std::io::Write;
 
+mod explain;
+mod matrix;
+
+pub use matrix::{
+    matrix_csv_header, matrix_csv_row, render_matrix_header, render_matrix_json_line,
+    render_matrix_json_summary, render_matrix_row, render_matrix_summary, MatrixCombo, MatrixTally,
+};
+
 /// Test display order.
 const TEST_DISPLAY_ORDER: &[&str] = &[
     "latency_vs_ping",
@@ -25,7 +41,36 @@ const TEST_DISPLAY_ORDER: &[&str] = &[
 
 /// Output raw JSON with pretty formatting.
 pub fn render_json(result: &DetectionResult) {
-    let output = serde_json::to_string_pretty(&result.raw_json).unwrap_or_default();
+    let mut raw = result.raw_json.clone();
+    if let Some(map) = raw.as_object_mut() {
+        map.insert("preset".to_string(), Value::from(result.preset.clone()));
+        map.insert("asn".to_string(), Value::from(result.asn.clone()));
+        if let Some(ref hardware) = result.hardware {
+            map.insert("hardware".to_string(), hardware_json(hardware));
+        }
+        if !result.preset_attempts.is_empty() {
+            map.insert(
+                "preset_attempts".to_string(),
+                result
+                    .preset_attempts
+                    .iter()
+                    .map(preset_attempt_json)
+                    .collect(),
+            );
+        }
+        if !result.timings.is_empty() {
+            map.insert("timings".to_string(), timings_json(&result.timings));
+        }
+        map.insert(
+            "bandwidth_bytes".to_string(),
+            Value::from(result.bandwidth_bytes),
+        );
+        map.insert(
+            "traffic".to_string(),
+            result.traffic.as_ref().map_or(Value::Null, traffic_json),
+        );
+    }
+    let output = serde_json::to_string_pretty(&raw).unwrap_or_default();
     println!("{}", output);
 }
 
@@ -49,6 +94,21 @@ pub fn render_table(result: &DetectionResult, exit_ip: &str, verbose: bool) {
     render_aggregate(&result.tests, "vpn", "VPN Score");
     render_client_threat(&result.tests);
     render_meta(&result.tests);
+    if let Some(ref hardware) = result.hardware {
+        println!(
+            "  [ ] {:<20} {} (seed {})",
+            "Hardware Profile",
+            hardware.summary(),
+            hardware.seed
+        );
+    }
+    if !result.preset_attempts.is_empty() {
+        println!(
+            "  [ ] {:<20} {}",
+            "Preset Attempts",
+            format_preset_attempts(&result.preset_attempts)
+        );
+    }
 
     println!();
     println!("{}", thin_div);
@@ -80,16 +140,106 @@ pub fn render_table(result: &DetectionResult, exit_ip: &str, verbose: bool) {
         }
     }
 
+    if verbose && !result.timings.is_empty() {
+        println!();
+        println!("{}", thin_div);
+        println!("  Timing Waterfall (ms since scan start)");
+        println!("{}", thin_div);
+        render_waterfall(&result.timings);
+    }
+
     println!();
     println!("{}", thin_div);
     println!(
-        "  Bandwidth Used: {} bytes ({:.2} KB)",
+        "  Bandwidth Used: {} bytes ({:.2} KB, {})",
         result.bandwidth_bytes,
-        result.bandwidth_bytes as f64 / 1024.0
+        result.bandwidth_bytes as f64 / 1024.0,
+        if result.traffic.is_some() {
+            "measured"
+        } else {
+            "estimated"
+        }
     );
+    if let Some(ref traffic) = result.traffic {
+        for (label, phase) in traffic_rows(traffic) {
+            println!(
+                "    {:<18} {:>9} sent {:>9} received",
+                label, phase.sent, phase.received
+            );
+        }
+    }
+    if !result.budget_skipped.is_empty() {
+        println!(
+            "  [!!] Byte budget reached, cut short: {}",
+            result.budget_skipped.join(", ")
+        );
+    }
     println!("{}", divider);
 }
 
+/// Print one bar per timed step, scaled to the scan's wall time.
+fn render_waterfall(timings: &[TimingSpan]) {
+    const WIDTH: usize = 30;
+    let total = timings
+        .iter()
+        .map(|span| span.end_ms)
+        .fold(0.0, f64::max)
+        .max(1.0);
+    let column = |ms: f64| ((ms / total) * WIDTH as f64).round() as usize;
+
+    for span in timings {
+        let start = column(span.start_ms).min(WIDTH - 1);
+        let end = column(span.end_ms).clamp(start + 1, WIDTH);
+        println!(
+            "  {:<14} {:>7.0} {:>7.0} {:>7.0}ms |{}{}{}|",
+            span.name,
+            span.start_ms,
+            span.end_ms,
+            span.duration_ms(),
+            " ".repeat(start),
+            "#".repeat(end - start),
+            " ".repeat(WIDTH - end),
+        );
+    }
+}
+
+fn timings_json(timings: &[TimingSpan]) -> Value {
+    let ms = |value: f64| (value * 10.0).round() / 10.0;
+    timings
+        .iter()
+        .map(|span| {
+            serde_json::json!({
+                "name": span.name,
+                "start_ms": ms(span.start_ms),
+                "end_ms": ms(span.end_ms),
+            })
+        })
+        .collect()
+}
+
+fn traffic_rows(traffic: &TrafficReport) -> [(&'static str, Traffic); 5] {
+    [
+        ("config (HTTP)", traffic.config),
+        ("images (HTTP)", traffic.images),
+        ("telemetry (HTTP)", traffic.telemetry),
+        ("poll (HTTP)", traffic.poll),
+        ("WebSocket", traffic.websocket),
+    ]
+}
+
+fn traffic_json(traffic: &TrafficReport) -> Value {
+    let phase = |t: Traffic| serde_json::json!({ "sent": t.sent, "received": t.received });
+    serde_json::json!({
+        "config": phase(traffic.config),
+        "images": phase(traffic.images),
+        "telemetry": phase(traffic.telemetry),
+        "poll": phase(traffic.poll),
+        "websocket": phase(traffic.websocket),
+        "http": phase(traffic.http()),
+        "total": phase(traffic.total()),
+    })
+}
+
 /// Output an IP intelligence section sourced from ipapi.is.
 pub fn render_ip_intelligence(info: &IpInfo) {
     let thin_div = "-".repeat(64);
@@ -144,17 +294,192 @@ pub fn render_ip_intelligence(info: &IpInfo) {
     );
 }
 
+/// Output a cause and remedy for every test that flagged the connection.
+pub fn render_explanations(result: &DetectionResult) {
+    let thin_div = "-".repeat(64);
+    println!();
+    println!("{}", thin_div);
+    println!("  Why Tests Failed");
+    println!("{}", thin_div);
+
+    let tests = match result.individual_tests() {
+        Some(tests) => tests,
+        None => {
+            println!("  No test data available");
+            return;
+        }
+    };
+
+    let mut keys: Vec<&str> = TEST_DISPLAY_ORDER
+        .iter()
+        .copied()
+        .filter(|key| tests.contains_key(*key))
+        .collect();
+    keys.extend(
+        tests
+            .keys()
+            .map(String::as_str)
+            .filter(|key| !TEST_DISPLAY_ORDER.contains(key)),
+    );
+
+    let mut failed = 0;
+    for key in keys {
+        let raw = &tests[key];
+        if test_detected(raw) != Some(true) {
+            continue;
+        }
+        failed += 1;
+
+        let explanation = explain::explain(key, raw, result);
+        println!("{}", test_line(key, raw));
+        println!("       Cause: {}", explanation.cause);
+        println!("       Fix:   {}", explanation.remedy);
+    }
+    if failed == 0 {
+        println!("  [ok] No test flagged the connection");
+    }
+}
+
+/// Engine tests that judge the latency channels.
+const LATENCY_TESTS: &[&str] = &["latency_vs_ping", "latency", "high_latencies"];
+
+fn format_channel(stats: Option<&ChannelStats>) -> String {
+    match stats {
+        Some(s) => format!(
+            "min={:.0}ms median={:.0}ms jitter={:.1}ms ({} samples)",
+            s.min, s.median, s.jitter, s.samples
+        ),
+        None => "no samples".to_string(),
+    }
+}
+
+/// Output local latency statistics next to the engine's latency verdicts.
+pub fn render_latency_analysis(result: &DetectionResult, analysis: &LatencyAnalysis) {
+    let thin_div = "-".repeat(64);
+    println!();
+    println!("{}", thin_div);
+    println!("  Latency Analysis");
+    println!("{}", thin_div);
+
+    println!(
+        "  [ ] {:<20} {}",
+        "Image (HTTP)",
+        format_channel(analysis.image.as_ref())
+    );
+    println!(
+        "  [ ] {:<20} {}",
+        "WebSocket",
+        format_channel(analysis.websocket.as_ref())
+    );
+    if let Some(ratio) = analysis.http_ws_ratio() {
+        println!("  [ ] {:<20} {:.2}x", "HTTP/WS Ratio", ratio);
+    }
+    if let Some(direct) = analysis.direct.as_ref() {
+        println!(
+            "  [ ] {:<20} {}",
+            "Direct Baseline",
+            format_channel(Some(direct))
+        );
+    }
+    if let Some(overhead) = analysis.proxy_overhead() {
+        println!("  [ ] {:<20} {:+.0}ms median", "Proxy Overhead", overhead);
+    }
+
+    if let Some(tests) = result.individual_tests() {
+        println!();
+        for key in LATENCY_TESTS {
+            if let Some(raw) = tests.get(*key) {
+                render_test(key, raw, true);
+            }
+        }
+    }
+}
+
+fn format_change(change: &(String, String)) -> String {
+    format!(
+        "{} -> {}",
+        value_or_unknown(&change.0),
+        value_or_unknown(&change.1)
+    )
+}
+
+fn flipped_names(result: &DetectionResult, delta: &BaselineDelta) -> Vec<String> {
+    let tests = result.individual_tests();
+    delta
+        .flipped
+        .iter()
+        .map(|key| {
+            tests
+                .and_then(|t| t.get(key))
+                .and_then(|raw| raw.get("name"))
+                .and_then(|v| v.as_str())
+                .unwrap_or(key)
+                .to_string()
+        })
+        .collect()
+}
+
+/// Output how a proxied scan differs from the direct baseline scan.
+pub fn render_baseline_delta(result: &DetectionResult, baseline_ip: &str, delta: &BaselineDelta) {
+    let thin_div = "-".repeat(64);
+    println!();
+    println!("{}", thin_div);
+    println!("  Compared with Direct Baseline ({})", baseline_ip);
+    println!("{}", thin_div);
+
+    match delta.added_latency {
+        Some(ms) => println!("  [ ] {:<20} {:+.0}ms median", "Added Latency", ms),
+        None => println!("  [ ] {:<20} n/a", "Added Latency"),
+    }
+    let changes = [
+        ("TCP/IP OS", &delta.tcpip_os),
+        ("Timezone", &delta.timezone),
+        ("ASN", &delta.asn),
+    ];
+    for (label, change) in changes {
+        match change {
+            Some(change) => println!("  [! ] {:<20} {}", label, format_change(change)),
+            None => println!("  [ok] {:<20} unchanged", label),
+        }
+    }
+
+    if delta.flipped.is_empty() {
+        println!("  [ok] {:<20} none", "Flipped Tests");
+    } else {
+        println!("  [!!] {:<20} clean -> detected", "Flipped Tests");
+        for name in flipped_names(result, delta) {
+            println!("       {}", name);
+        }
+    }
+}
+
+/// Print a compact baseline comparison under a bulk result line.
+pub fn render_bulk_delta_line(delta: &BaselineDelta) {
+    let mut parts = Vec::new();
+    if let Some(ms) = delta.added_latency {
+        parts.push(format!("latency={:+.0}ms", ms));
+    }
+    if let Some(ref change) = delta.tcpip_os {
+        parts.push(format!("os={}", format_change(change)));
+    }
+    if let Some(ref change) = delta.timezone {
+        parts.push(format!("tz={}", format_change(change)));
+    }
+    if let Some(ref change) = delta.asn {
+        parts.push(format!("asn={}", format_change(change)));
+    }
+    if !delta.flipped.is_empty() {
+        parts.push(format!("flipped={}", delta.flipped.join(",")));
+    }
+    println!("       vs direct: {}", parts.join(" "));
+}
+
 fn extract_tests_map(tests: &Map<String, Value>) -> Option<Map<String, Value>> {
     tests.get("tests").and_then(|v| v.as_object()).cloned()
 }
 
 fn render_test(key: &str, raw: &Value, verbose: bool) {
-    let is_proxy = raw.get("is_proxy").and_then(|v| v.as_bool());
-    let is_vpn = raw.get("is_vpn").and_then(|v| v.as_bool());
-    let name = raw.get("name").and_then(|v| v.as_str()).unwrap_or(key);
-
-    let (verdict, icon) = determine_verdict(is_proxy, is_vpn);
-    println!("  {} {:<36} {}", icon, name, verdict);
+    println!("{}", test_line(key, raw));
 
     if verbose {
         if let Some(info) = raw.get("info") {
@@ -163,6 +488,50 @@ fn render_test(key: &str, raw: &Value, verbose: bool) {
     }
 }
 
+fn test_line(key: &str, raw: &Value) -> String {
+    let is_proxy = raw.get("is_proxy").and_then(|v| v.as_bool());
+    let is_vpn = raw.get("is_vpn").and_then(|v| v.as_bool());
+    let name = raw.get("name").and_then(|v| v.as_str()).unwrap_or(key);
+
+    let (verdict, icon) = determine_verdict(is_proxy, is_vpn);
+    format!("  {} {:<36} {}", icon, name, verdict)
+}
+
+/// Print one test line to stderr as soon as it completes, with elapsed scan time.
+pub fn render_live_test(key: &str, raw: &Value, elapsed_secs: f64) {
+    eprintln!("{}  ({:.1}s)", test_line(key, raw), elapsed_secs);
+}
+
+fn format_preset_attempts(attempts: &[PresetAttempt]) -> String {
+    attempts
+        .iter()
+        .map(|attempt| {
+            let outcome = match (&attempt.error, attempt.tcpip_detected) {
+                (Some(err), _) => format!("error: {}", err),
+                (None, Some(true)) => "tcpip_fp detected".to_string(),
+                (None, Some(false)) => "tcpip_fp clean".to_string(),
+                (None, None) => "tcpip_fp n/a".to_string(),
+            };
+            let os = attempt.tcpip_os.as_deref().unwrap_or("unknown");
+            let source = if attempt.cached_os.is_some() {
+                ", cached"
+            } else {
+                ""
+            };
+            format!("{} (os {}{}, {})", attempt.preset, os, source, outcome)
+        })
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+/// Print the presets `--auto-preset` tried under a bulk result line.
+pub fn render_bulk_attempts_line(result: &DetectionResult) {
+    println!(
+        "       presets: {}",
+        format_preset_attempts(&result.preset_attempts)
+    );
+}
+
 fn determine_verdict(is_proxy: Option<bool>, is_vpn: Option<bool>) -> (&'static str, &'static str) {
     let proxy_detected = is_proxy == Some(true);
     let vpn_detected = is_vpn == Some(true);
@@ -383,6 +752,15 @@ pub fn render_bulk_json_line(
     let line = serde_json::json!({
         "proxy": proxy_raw,
         "exit_ip": result.exit_ip,
+        "hardware": result.hardware.as_ref().map(hardware_json),
+        "preset": result.preset,
+        "asn": result.asn,
+        "preset_attempts": result.preset_attempts.iter().map(preset_attempt_json).collect::<Vec<_>>(),
+        "bandwidth_bytes": result.bandwidth_bytes,
+        "bandwidth_measured": result.traffic.is_some(),
+        "traffic": result.traffic.as_ref().map(traffic_json),
+        "budget_skipped": result.budget_skipped,
+        "timings": timings_json(&result.timings),
         "result": result.raw_json,
         "ipapi": ip_info.map(ip_info_json),
         "filtered": filtered,
@@ -395,19 +773,25 @@ pub fn render_bulk_json_line(
 /// Print a single NDJSON line for a failed scan.
 pub fn render_bulk_json_error(
     proxy_raw: &str,
-    err: &str,
+    err: &(dyn std::error::Error + Send + Sync + 'static),
     ip_info: Option<&IpInfo>,
     filtered: bool,
     filter_threshold: Option<f64>,
 ) {
+    // A failed scan reports only its total, so there is no per-phase `traffic`.
+    let (bandwidth_bytes, measured) = ScanError::spent(err);
     let line = serde_json::json!({
         "proxy": proxy_raw,
         "exit_ip": null,
+        "hardware": null,
+        "bandwidth_bytes": bandwidth_bytes,
+        "bandwidth_measured": measured,
+        "traffic": null,
         "result": null,
         "ipapi": ip_info.map(ip_info_json),
         "filtered": filtered,
         "max_fraud_score": filter_threshold,
-        "error": err,
+        "error": err.to_string(),
     });
     println!("{}", serde_json::to_string(&line).unwrap_or_default());
 }
@@ -460,11 +844,404 @@ pub fn render_bulk_summary(
     let _ = writeln!(out, "{}", divider);
 }
 
+/// Print the run's bandwidth, budget use and estimated cost per billing host.
+pub fn render_cost_summary(tally: &CostTally, budget: &RunBudget) {
+    let divider = "=".repeat(64);
+    let mb = |bytes: u64| bytes as f64 / (1024.0 * 1024.0);
+    let mut out = std::io::stderr();
+    let _ = writeln!(
+        out,
+        "  Bandwidth: {:.2} MB over {} scans ({} measured)",
+        mb(tally.bytes),
+        tally.scans,
+        tally.measured
+    );
+    if let Some(limit) = budget.max_bytes {
+        let _ = writeln!(
+            out,
+            "  Run Budget: {:.2} / {:.2} MB, {} scans skipped",
+            mb(tally.bytes),
+            mb(limit),
+            tally.skipped
+        );
+    }
+    if let Some(cost) = tally.cost() {
+        let _ = writeln!(out, "  Est. Cost: ${:.4}", cost);
+        for (label, host) in &tally.hosts {
+            let _ = writeln!(
+                out,
+                "    {:<24} {:>5} scans {:>9.2} MB  ${:.2}/GB  ${:.4}",
+                label,
+                host.scans,
+                mb(host.bytes),
+                host.price_per_gb,
+                host.cost()
+            );
+        }
+    } else if budget.pricing.is_set() {
+        let _ = writeln!(out, "  Est. Cost: n/a (no priced proxies scanned)");
+    }
+    let _ = writeln!(out, "{}", divider);
+}
+
+// ── Repeated scans ───────────────────────────────────────────────────
+
+/// Tests in display order followed by any the engine added since.
+fn ordered_test_keys(summary: &RepeatSummary) -> Vec<&str> {
+    let mut keys: Vec<&str> = TEST_DISPLAY_ORDER
+        .iter()
+        .copied()
+        .filter(|key| summary.tests.contains_key(*key))
+        .collect();
+    for key in summary.tests.keys() {
+        if !TEST_DISPLAY_ORDER.contains(&key.as_str()) {
+            keys.push(key);
+        }
+    }
+    keys
+}
+
+fn format_majority(block: &BlockStats) -> String {
+    let verdict = if block.majority() {
+        "DETECTED"
+    } else {
+        "clean"
+    };
+    format!("{} ({}/{} scans)", verdict, block.detected, block.observed)
+}
+
+/// Print per-test positive rates, majority verdicts and stability for one proxy.
+pub fn render_repeat_summary(proxy_display: &str, summary: &RepeatSummary) {
+    let divider = "=".repeat(64);
+    let thin_div = "-".repeat(64);
+
+    println!();
+    println!("{}", divider);
+    println!(
+        "  {} scans of {} ({} failed)",
+        summary.scans,
+        proxy_display,
+        summary.errors.len()
+    );
+    println!("{}", divider);
+    println!();
+
+    let block_icon = |block: &BlockStats| if block.majority() { "[!!]" } else { "[ok]" };
+    println!(
+        "  {} {:<20} {}",
+        block_icon(&summary.proxy),
+        "Proxy Majority",
+        format_majority(&summary.proxy)
+    );
+    println!(
+        "  {} {:<20} {}",
+        block_icon(&summary.vpn),
+        "VPN Majority",
+        format_majority(&summary.vpn)
+    );
+    match summary.stability() {
+        Some(stability) => println!("  [ ] {:<20} {:.1}%", "Stability", stability * 100.0),
+        None => println!("  [ ] {:<20} n/a", "Stability"),
+    }
+    if !summary.exit_ips.is_empty() {
+        println!("  [ ] {:<20} {}", "Exit IPs", summary.exit_ips.join(", "));
+    }
+
+    println!();
+    println!("{}", thin_div);
+    println!("  Individual Tests (positive rate)");
+    println!("{}", thin_div);
+
+    if summary.tests.is_empty() {
+        println!("  No test data available");
+    }
+    for key in ordered_test_keys(summary) {
+        let stats = &summary.tests[key];
+        let icon = if stats.flaky() {
+            "[? ]"
+        } else if stats.positive > 0 {
+            "[!!]"
+        } else {
+            "[ok]"
+        };
+        println!(
+            "  {} {:<36} {}/{} ({:.0}%){}",
+            icon,
+            stats.name,
+            stats.positive,
+            stats.observed,
+            stats.rate() * 100.0,
+            if stats.flaky() { "  flaky" } else { "" },
+        );
+    }
+
+    for err in &summary.errors {
+        println!("  [ER] {}", err);
+    }
+    println!("{}", divider);
+}
+
+/// Print a single NDJSON line summarizing repeated scans of one proxy.
+pub fn render_repeat_json_line(proxy_raw: &str, summary: &RepeatSummary) {
+    let block_json = |block: &BlockStats| {
+        serde_json::json!({
+            "majority": block.majority(),
+            "detected": block.detected,
+            "observed": block.observed,
+        })
+    };
+    let tests: Map<String, Value> = summary
+        .tests
+        .iter()
+        .map(|(key, stats)| {
+            let entry = serde_json::json!({
+                "name": stats.name,
+                "positive": stats.positive,
+                "observed": stats.observed,
+                "rate": stats.rate(),
+                "flaky": stats.flaky(),
+            });
+            (key.clone(), entry)
+        })
+        .collect();
+
+    let line = serde_json::json!({
+        "proxy": proxy_raw,
+        "scans": summary.scans,
+        "errors": summary.errors,
+        "exit_ips": summary.exit_ips,
+        "proxy_majority": block_json(&summary.proxy),
+        "vpn_majority": block_json(&summary.vpn),
+        "stability": summary.stability(),
+        "tests": tests,
+    });
+    println!("{}", serde_json::to_string(&line).unwrap_or_default());
+}
+
+/// CSV header row for repeated scans: one positive-rate column per known test.
+pub fn repeat_csv_header() -> String {
+    let mut header = "proxy,scans,errors,exit_ips,proxy_majority,proxy_detected_scans,vpn_majority,vpn_detected_scans,stability,flaky_tests".to_string();
+    for key in TEST_DISPLAY_ORDER {
+        header.push_str(&format!(",{}_rate", key));
+    }
+    header
+}
+
+/// Format repeated-scan statistics for one proxy as a CSV row.
+pub fn repeat_csv_row(proxy_display: &str, summary: &RepeatSummary) -> String {
+    let mut row = format!(
+        "{},{},{},{},{},{},{},{},{},{}",
+        csv_quote(proxy_display),
+        summary.scans,
+        summary.errors.len(),
+        csv_quote(&summary.exit_ips.join(" ")),
+        summary.proxy.majority(),
+        summary.proxy.detected,
+        summary.vpn.majority(),
+        summary.vpn.detected,
+        summary
+            .stability()
+            .map(|s| format!("{:.4}", s))
+            .unwrap_or_default(),
+        csv_quote(&summary.flaky_tests().join(" ")),
+    );
+    for key in TEST_DISPLAY_ORDER {
+        row.push(',');
+        if let Some(stats) = summary.tests.get(*key) {
+            row.push_str(&format!("{:.4}", stats.rate()));
+        }
+    }
+    row
+}
+
+// ── Result diffs ─────────────────────────────────────────────────────
+
+/// Print what changed between two saved result sets.
+pub fn render_diff(old_path: &str, new_path: &str, by_exit_ip: bool, diff: &ScanDiff) {
+    let divider = "=".repeat(64);
+    let thin_div = "-".repeat(64);
+
+    println!();
+    println!("{}", divider);
+    println!(
+        "  Scan Diff: {} -> {} (by {})",
+        old_path,
+        new_path,
+        if by_exit_ip { "exit IP" } else { "proxy" }
+    );
+    println!("{}", divider);
+    println!("  [ ] {:<20} {}", "Changed", diff.changed.len());
+    render_key_list("[+]", "Added", &diff.added);
+    render_key_list("[-]", "Removed", &diff.removed);
+    render_key_list("[+]", "New Exit IPs", &diff.new_exit_ips);
+    render_key_list("[-]", "Gone Exit IPs", &diff.gone_exit_ips);
+
+    if !diff.changed.is_empty() {
+        println!();
+        println!("{}", thin_div);
+        println!("  Changes");
+        println!("{}", thin_div);
+    }
+    for change in &diff.changed {
+        let icon = match change.status {
+            Some((_, ref now)) if now == "detected" => "[!!]",
+            Some((_, ref now)) if now == "clean" => "[ok]",
+            _ => "[! ]",
+        };
+        println!("  {} {}", icon, change.key);
+        if let Some((ref a, ref b)) = change.status {
+            println!("       status:       {} -> {}", a, b);
+        }
+        if let Some((ref a, ref b)) = change.exit_ip {
+            println!("       exit IP:      {} -> {}", a, b);
+        }
+        if !change.newly_detected.is_empty() || !change.cleared.is_empty() {
+            let flips: Vec<String> = change
+                .newly_detected
+                .iter()
+                .map(|t| format!("+{}", t))
+                .chain(change.cleared.iter().map(|t| format!("-{}", t)))
+                .collect();
+            println!("       tests:        {}", flips.join(" "));
+        }
+        if let Some((a, b)) = change.abuser_score {
+            println!("       abuser score: {:.4} -> {:.4} ({:+.4})", a, b, b - a);
+        }
+        if let Some((ref a, ref b)) = change.asn {
+            println!("       ASN:          {} -> {}", a, b);
+        }
+        if let Some((ref a, ref b)) = change.country {
+            println!("       country:      {} -> {}", a, b);
+        }
+    }
+    println!("{}", divider);
+}
+
+fn render_key_list(icon: &str, label: &str, keys: &[String]) {
+    if keys.is_empty() {
+        println!("  [ ] {:<20} 0", label);
+    } else {
+        println!(
+            "  {} {:<20} {} ({})",
+            icon,
+            label,
+            keys.len(),
+            keys.join(", ")
+        );
+    }
+}
+
+// ── Preset checks ────────────────────────────────────────────────────
+
+/// Print the coherence check outcome for one preset.
+pub fn render_preset_check(name: &str, issues: &[Mismatch]) {
+    if issues.is_empty() {
+        println!("  [ok] {:<20} coherent", name);
+        return;
+    }
+
+    println!("  [!!] {:<20} {} mismatch(es)", name, issues.len());
+    for issue in issues {
+        println!("       {}: {}", issue.context, issue.message);
+    }
+}
+
+/// Print the wire-level header comparison for one preset.
+pub fn render_wire_check(name: &str, reports: &[WireReport]) {
+    println!("  {}", name);
+    for report in reports {
+        let label = format!("{} ({})", report.context, report.protocol.label());
+        match report.outcome {
+            WireOutcome::Match => println!("  [ok] {:<20} matches reference", label),
+            WireOutcome::Recorded => println!("  [ok] {:<20} reference recorded", label),
+            WireOutcome::NoReference => {
+                println!(
+                    "  [--] {:<20} unchecked, no reference (record one with --record <HAR>)",
+                    label
+                )
+            }
+            WireOutcome::Failed(ref err) => println!("  [!!] {:<20} {}", label, err),
+            WireOutcome::Differs(ref diff) => {
+                println!("  [!!] {:<20} {} difference(s)", label, diff.len());
+                for line in diff {
+                    println!("       {}", line);
+                }
+            }
+        }
+    }
+
+    let unchecked = reports
+        .iter()
+        .filter(|report| matches!(report.outcome, WireOutcome::NoReference))
+        .count();
+    if unchecked > 0 {
+        println!(
+            "       {} of {} request(s) unchecked: a missing reference is not a pass",
+            unchecked,
+            reports.len()
+        );
+    }
+}
+
+/// Print the local TLS/HTTP2 fingerprint self-test for one preset, grouped by route.
+pub fn render_selftest(name: &str, checks: &[FingerprintCheck]) {
+    println!("  {}", name);
+    let mut route = None;
+    for check in checks {
+        if route != Some(check.route) {
+            route = Some(check.route);
+            println!("   {}", check.route.label());
+        }
+        match (check.status(), &check.actual, check.expected) {
+            (CheckStatus::Error, Err(err), _) => println!("  [!!] {:<20} {}", check.label, err),
+            (CheckStatus::Pass, Ok(actual), _) => println!("  [ok] {:<20} {}", check.label, actual),
+            (CheckStatus::Mismatch, Ok(actual), Some(expected)) => {
+                println!("  [!!] {:<20} {}", check.label, actual);
+                println!("       expected {}", expected);
+            }
+            (_, Ok(actual), _) => {
+                println!(
+                    "  [? ] {:<20} {} (unpinned, not verified)",
+                    check.label, actual
+                )
+            }
+            _ => {}
+        }
+        if let Some(ref detail) = check.detail {
+            println!("       {}", detail);
+        }
+    }
+}
+
+// ── Fingerprint inspection ───────────────────────────────────────────
+
+/// Print a preset's fingerprint properties, the exact hashed string and its hash.
+pub fn render_fingerprint(preset: &Preset, properties: &[(&str, String)], input: &str, hash: u32) {
+    println!("  [ ] {:<20} {}", "Preset", preset.name);
+    println!("  [ ] {:<20} {} (0x{:08x})", "Fingerprint", hash, hash);
+    println!();
+
+    for (key, value) in properties {
+        let overridden = preset
+            .fingerprint_overrides
+            .iter()
+            .any(|(name, _)| name == key);
+        let icon = if overridden { "[* ]" } else { "[ ] " };
+        println!("  {} {:<22} {}", icon, key, value);
+    }
+
+    println!();
+    println!("{}", input);
+}
+
 // ── CSV output ───────────────────────────────────────────────────────
 
 /// CSV header row.
-pub fn csv_header(include_ipapi: bool) -> String {
-    let mut base = "proxy,exit_ip,status,proxy_detected,vpn_detected,proxy_score,vpn_score,proxy_positive_tests,proxy_total_tests,vpn_positive_tests,vpn_total_tests,error".to_string();
+pub fn csv_header(include_ipapi: bool, include_hardware: bool) -> String {
+    let mut base = "proxy,exit_ip,status,proxy_detected,vpn_detected,proxy_score,vpn_score,proxy_positive_tests,proxy_total_tests,vpn_positive_tests,vpn_total_tests,error,bandwidth_bytes,bytes_sent,bytes_received,bandwidth_measured".to_string();
+    if include_hardware {
+        base.push_str(",hardware_concurrency,device_memory,screen,hardware_seed");
+    }
     if include_ipapi {
         base.push_str(",ipapi_proxy,ipapi_vpn,ipapi_datacenter,ipapi_abuser,abuser_score,company,company_type,asn_org,country,city");
     }
@@ -482,6 +1259,7 @@ pub fn csv_row(
     result: &DetectionResult,
     ip_info: Option<&IpInfo>,
     include_ipapi: bool,
+    include_hardware: bool,
 ) -> String {
     let verdict = extract_verdict(&result.tests);
     let status = if verdict.proxy_detected || verdict.vpn_detected {
@@ -518,8 +1296,20 @@ pub fn csv_row(
         verdict.vpn_total,
     );
 
+    // Keep the "error" column explicitly empty before the bandwidth columns.
+    let measured = result.traffic.map(|t| t.total()).unwrap_or_default();
+    row.push_str(&format!(
+        ",{},{},{},{}",
+        result.bandwidth_bytes,
+        measured.sent,
+        measured.received,
+        result.traffic.is_some(),
+    ));
+    if include_hardware {
+        row.push(',');
+        row.push_str(&csv_hardware_columns(result.hardware.as_ref()));
+    }
     if include_ipapi {
-        // Keep the "error" column explicitly empty before appending ipapi columns.
         row.push(',');
         row.push_str(&csv_ipapi_columns(ip_info));
     }
@@ -527,13 +1317,26 @@ pub fn csv_row(
     row
 }
 
-/// Format an error as a CSV row.
-pub fn csv_error_row(proxy_display: &str, err: &str, include_ipapi: bool) -> String {
+/// Format an error as a CSV row, with the bandwidth the failed scan used.
+pub fn csv_error_row(
+    proxy_display: &str,
+    err: &(dyn std::error::Error + Send + Sync + 'static),
+    include_ipapi: bool,
+    include_hardware: bool,
+) -> String {
+    // A failed scan reports only its total, so the sent/received columns stay empty.
+    let (bandwidth_bytes, measured) = ScanError::spent(err);
     let mut row = format!(
-        "{},,error,,,,,,,,,{}",
+        "{},,error,,,,,,,,,{},{},,,{}",
         csv_quote(proxy_display),
-        csv_quote(err),
+        csv_quote(&err.to_string()),
+        bandwidth_bytes,
+        measured,
     );
+    if include_hardware {
+        row.push(',');
+        row.push_str(&csv_hardware_columns(None));
+    }
     if include_ipapi {
         row.push(',');
         row.push_str(&csv_ipapi_columns(None));
@@ -560,6 +1363,43 @@ fn csv_ipapi_columns(ip_info: Option<&IpInfo>) -> String {
     }
 }
 
+fn csv_hardware_columns(hardware: Option<&HardwareProfile>) -> String {
+    match hardware {
+        Some(hw) => format!(
+            "{},{},{}x{},{}",
+            hw.hardware_concurrency,
+            hw.device_memory
+                .map(|gb| gb.to_string())
+                .unwrap_or_default(),
+            hw.screen_width,
+            hw.screen_height,
+            hw.seed,
+        ),
+        None => [""; 4].join(","),
+    }
+}
+
+fn hardware_json(hardware: &HardwareProfile) -> Value {
+    serde_json::json!({
+        "hardware_concurrency": hardware.hardware_concurrency,
+        "device_memory": hardware.device_memory,
+        "screen_width": hardware.screen_width,
+        "screen_height": hardware.screen_height,
+        "seed": hardware.seed,
+    })
+}
+
+fn preset_attempt_json(attempt: &PresetAttempt) -> Value {
+    serde_json::json!({
+        "preset": attempt.preset,
+        "tcpip_os": attempt.tcpip_os,
+        "from_cache": attempt.cached_os.is_some(),
+        "cached_os": attempt.cached_os,
+        "tcpip_detected": attempt.tcpip_detected,
+        "error": attempt.error,
+    })
+}
+
 fn ip_info_json(info: &IpInfo) -> Value {
     serde_json::json!({
         "ip": info.ip,
@@ -0,0 +1,92 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+
+/// Graceful shutdown of a bulk run: the first SIGINT/SIGTERM asks the run to
+/// stop launching scans and wind down, a second one exits immediately.
+pub struct Shutdown {
+    state: Arc<State>,
+    listener: JoinHandle<()>,
+}
+
+#[derive(Default)]
+struct State {
+    requested: AtomicBool,
+    notify: Notify,
+}
+
+impl Shutdown {
+    /// Start listening for signals; `grace` is only used in the notice printed
+    /// on the first signal.
+    pub fn install(grace: Duration) -> Shutdown {
+        let state = Arc::new(State::default());
+        let listener_state = state.clone();
+        let mut signals = Signals::new();
+        let listener = tokio::spawn(async move {
+            signals.next().await;
+            listener_state.requested.store(true, Ordering::SeqCst);
+            listener_state.notify.notify_waiters();
+            eprintln!();
+            eprintln!(
+                "Interrupted: waiting up to {}s for in-flight scans (signal again to exit now)",
+                grace.as_secs()
+            );
+
+            signals.next().await;
+            eprintln!("Exiting immediately");
+            std::process::exit(130);
+        });
+        Shutdown { state, listener }
+    }
+
+    pub fn requested(&self) -> bool {
+        self.state.requested.load(Ordering::SeqCst)
+    }
+
+    /// Resolve once shutdown has been requested.
+    pub async fn wait(&self) {
+        // Register before checking the flag so a signal in between is not missed.
+        let notified = self.state.notify.notified();
+        tokio::pin!(notified);
+        notified.as_mut().enable();
+        if !self.requested() {
+            notified.await;
+        }
+    }
+}
+
+impl Drop for Shutdown {
+    fn drop(&mut self) {
+        self.listener.abort();
+    }
+}
+
+/// Ctrl-C, plus SIGTERM on Unix.
+struct Signals {
+    #[cfg(unix)]
+    terminate: Option<tokio::signal::unix::Signal>,
+}
+
+impl Signals {
+    fn new() -> Signals {
+        Signals {
+            #[cfg(unix)]
+            terminate: tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+                .ok(),
+        }
+    }
+
+    async fn next(&mut self) {
+        #[cfg(unix)]
+        if let Some(ref mut terminate) = self.terminate {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = terminate.recv() => {}
+            }
+            return;
+        }
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
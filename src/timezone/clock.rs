@@ -0,0 +1,35 @@
+use chrono::{DateTime, Utc};
+
+/// Source of "now" for timezone-derived payload fields.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum Clock {
+    #[default]
+    System,
+    /// Pinned to one instant (`--at`), e.g. to check a DST boundary.
+    Fixed(DateTime<Utc>),
+}
+
+impl Clock {
+    pub fn now(&self) -> DateTime<Utc> {
+        match self {
+            Clock::System => Utc::now(),
+            Clock::Fixed(at) => *at,
+        }
+    }
+}
+
+/// Parse an `--at` value: RFC 3339 (`2025-03-30T00:59:59Z`) or Unix seconds.
+pub fn parse_instant(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(seconds) = value.parse::<i64>() {
+        return DateTime::from_timestamp(seconds, 0)
+            .ok_or_else(|| format!("timestamp out of range: {}", value));
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|at| at.with_timezone(&Utc))
+        .map_err(|err| {
+            format!(
+                "expected RFC 3339 or Unix seconds, got '{}': {}",
+                value, err
+            )
+        })
+}
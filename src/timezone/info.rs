@@ -1,4 +1,8 @@
-use chrono::{DateTime, Datelike, Local, Offset, TimeZone, Timelike};
+use super::clock::Clock;
+use super::format::{format_js_date, format_js_time};
+use super::windows_zones::{WINDOWS_ZONES, ZONE_LINKS};
+use crate::locale::Locale;
+use chrono::{Offset, TimeZone};
 use chrono_tz::Tz;
 use serde::Deserialize;
 use std::collections::HashMap;
@@ -7,35 +11,98 @@ use std::collections::HashMap;
 #[derive(Clone, Debug)]
 pub struct Info {
     pub iana_name: String,
-    pub windows_zone: String,
+    /// Windows timezone ID; `None` for zones CLDR has no Windows equivalent for.
+    pub windows_zone: Option<String>,
     pub offset_minutes: i32,
     pub resolved_epoch: i64,
     pub system_epoch: i64,
     pub date_string: String,
     pub time_string: String,
-    pub timestamp_millis: i64,
+}
+
+/// Exit IP location fields used to localize the browser identity.
+#[derive(Clone, Debug)]
+pub struct IpLocation {
+    pub timezone: String,
+    /// ISO 3166-1 alpha-2 country code (may be empty).
+    pub country_code: String,
+    /// Autonomous system, e.g. "AS13335 Cloudflare, Inc." (may be empty).
+    pub asn: String,
 }
 
 #[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct IpApiResponse {
     timezone: Option<String>,
+    country_code: Option<String>,
+    #[serde(rename = "as")]
+    asn: Option<String>,
+    /// IP the lookup was made for.
+    query: Option<String>,
 }
 
-/// Lookup IANA timezone from IP via ip-api.com.
-pub async fn lookup_from_ip(ip: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-    let url = format!("http://ip-api.com/json/{}?fields=timezone", ip);
+/// Lookup IANA timezone, country and ASN from IP via ip-api.com.
+pub async fn lookup_from_ip(
+    ip: &str,
+) -> Result<IpLocation, Box<dyn std::error::Error + Send + Sync>> {
+    let url = format!(
+        "http://ip-api.com/json/{}?fields=timezone,countryCode,as",
+        ip
+    );
     let body = wreq::get(&url).send().await?.text().await?;
-    let resp: IpApiResponse = serde_json::from_str(&body)?;
+    let (_, location) = parse_location(&body)?;
+    Ok(location)
+}
+
+/// Look up the client's own exit IP and its location, so the identity can be
+/// localized before the first engine request. The lookup goes through `proxy`
+/// when given and directly otherwise: ip-api.com is plain http, which the
+/// metering relay refuses without an upstream proxy.
+pub async fn lookup_exit(
+    proxy: Option<&wreq::Client>,
+) -> Result<(String, IpLocation), Box<dyn std::error::Error + Send + Sync>> {
+    let url = "http://ip-api.com/json/?fields=timezone,countryCode,as,query";
+    let request = match proxy {
+        Some(client) => client.get(url),
+        None => wreq::get(url),
+    };
+    let body = request.send().await?.text().await?;
+    parse_location(&body)
+}
+
+/// Exit IP and location from an ip-api.com response.
+fn parse_location(
+    body: &str,
+) -> Result<(String, IpLocation), Box<dyn std::error::Error + Send + Sync>> {
+    let resp: IpApiResponse = serde_json::from_str(body)?;
+    let ip = resp.query.unwrap_or_default();
 
-    resp.timezone
+    let timezone = resp
+        .timezone
         .filter(|s| !s.is_empty())
-        .ok_or_else(|| format!("empty timezone for IP {}", ip).into())
+        .ok_or_else(|| format!("empty timezone for IP {}", ip))?;
+
+    Ok((
+        ip,
+        IpLocation {
+            timezone,
+            country_code: resp.country_code.unwrap_or_default(),
+            asn: resp.asn.unwrap_or_default(),
+        },
+    ))
 }
 
 /// Resolve all timezone-derived values from an IANA timezone name.
-pub fn resolve(iana_name: &str) -> Result<Info, Box<dyn std::error::Error + Send + Sync>> {
+///
+/// The locale selects the zone name language of `Date.toString()` and the clock
+/// format of `toLocaleTimeString()`; the clock supplies "now".
+pub fn resolve(
+    iana_name: &str,
+    locale: &Locale,
+    clock: &Clock,
+) -> Result<Info, Box<dyn std::error::Error + Send + Sync>> {
     let tz: Tz = iana_name.parse()?;
-    let now = Local::now().with_timezone(&tz);
+    let now = clock.now().with_timezone(&tz);
     let fixed = now.offset().fix();
     let offset_seconds = fixed.local_minus_utc();
     let offset_minutes = offset_seconds / 60;
@@ -50,189 +117,149 @@ pub fn resolve(iana_name: &str) -> Result<Info, Box<dyn std::error::Error + Send
         .map(|dt| dt.timestamp_millis())
         .unwrap_or(0);
 
-    let windows_zone = iana_to_windows(iana_name);
-
     Ok(Info {
         iana_name: iana_name.to_string(),
-        windows_zone: windows_zone.to_string(),
+        windows_zone: iana_to_windows(iana_name).map(str::to_string),
         offset_minutes: js_offset,
         resolved_epoch: epoch_1113,
         system_epoch: epoch_1113,
-        date_string: format_js_date(&now, &fixed, windows_zone),
-        time_string: format_js_time(&now),
-        timestamp_millis: now.timestamp_millis(),
+        date_string: format_js_date(&now, iana_name, locale),
+        time_string: format_js_time(&now, locale),
     })
 }
 
-/// Format date like JavaScript's Date.toString().
-fn format_js_date<T: TimeZone>(
-    now: &DateTime<T>,
-    fixed: &chrono::FixedOffset,
-    windows_zone: &str,
-) -> String {
-    let offset_seconds = fixed.local_minus_utc();
-    let sign = if offset_seconds >= 0 { "+" } else { "-" };
-    let abs_offset = offset_seconds.abs();
-    let hours = abs_offset / 3600;
-    let minutes = (abs_offset % 3600) / 60;
-
-    let weekday = match now.weekday() {
-        chrono::Weekday::Mon => "Mon",
-        chrono::Weekday::Tue => "Tue",
-        chrono::Weekday::Wed => "Wed",
-        chrono::Weekday::Thu => "Thu",
-        chrono::Weekday::Fri => "Fri",
-        chrono::Weekday::Sat => "Sat",
-        chrono::Weekday::Sun => "Sun",
-    };
-
-    let month = match now.month() {
-        1 => "Jan",
-        2 => "Feb",
-        3 => "Mar",
-        4 => "Apr",
-        5 => "May",
-        6 => "Jun",
-        7 => "Jul",
-        8 => "Aug",
-        9 => "Sep",
-        10 => "Oct",
-        11 => "Nov",
-        12 => "Dec",
-        _ => "???",
-    };
-
-    format!(
-        "{} {} {:02} {} {:02}:{:02}:{:02} GMT{}{:02}{:02} ({})",
-        weekday,
-        month,
-        now.day(),
-        now.year(),
-        now.hour(),
-        now.minute(),
-        now.second(),
-        sign,
-        hours,
-        minutes,
-        windows_zone
-    )
-}
-
-/// Format time like "12:56:04 PM".
-fn format_js_time<T: TimeZone>(now: &DateTime<T>) -> String {
-    let hour = now.hour();
-    let period = if hour >= 12 { "PM" } else { "AM" };
-    let display_hour = if hour > 12 {
-        hour - 12
-    } else if hour == 0 {
-        12
-    } else {
-        hour
-    };
-    format!(
-        "{}:{:02}:{:02} {}",
-        display_hour,
-        now.minute(),
-        now.second(),
-        period
-    )
-}
-
-/// Map IANA timezone names to Windows display names.
-fn iana_to_windows(iana: &str) -> &str {
+/// Map an IANA timezone name (or tzdata alias) to its Windows timezone ID.
+///
+/// `None` for zones CLDR has no Windows equivalent for.
+fn iana_to_windows(iana: &str) -> Option<&'static str> {
     static IANA_WINDOWS_MAP: std::sync::LazyLock<HashMap<&'static str, &'static str>> =
         std::sync::LazyLock::new(|| {
             let mut m = HashMap::new();
-            // North America
-            m.insert("America/New_York", "Eastern Standard Time");
-            m.insert("America/Chicago", "Central Standard Time");
-            m.insert("America/Denver", "Mountain Standard Time");
-            m.insert("America/Los_Angeles", "Pacific Standard Time");
-            m.insert("America/Phoenix", "US Mountain Standard Time");
-            m.insert("America/Anchorage", "Alaskan Standard Time");
-            m.insert("Pacific/Honolulu", "Hawaiian Standard Time");
-            m.insert("America/Halifax", "Atlantic Standard Time");
-            m.insert("America/St_Johns", "Newfoundland Standard Time");
-            m.insert("America/Regina", "Canada Central Standard Time");
-            m.insert("America/Mexico_City", "Central Standard Time (Mexico)");
-            m.insert("America/Bogota", "SA Pacific Standard Time");
-            m.insert("America/Caracas", "Venezuela Standard Time");
-            m.insert("America/Santiago", "Pacific SA Standard Time");
-            m.insert("America/Argentina/Buenos_Aires", "Argentina Standard Time");
-            m.insert("America/Sao_Paulo", "E. South America Standard Time");
-            m.insert("America/Winnipeg", "Central Standard Time");
-            m.insert("America/Edmonton", "Mountain Standard Time");
-            m.insert("America/Vancouver", "Pacific Standard Time");
-            m.insert("America/Toronto", "Eastern Standard Time");
-            // Europe
-            m.insert("Europe/London", "GMT Standard Time");
-            m.insert("Europe/Paris", "Romance Standard Time");
-            m.insert("Europe/Berlin", "W. Europe Standard Time");
-            m.insert("Europe/Rome", "W. Europe Standard Time");
-            m.insert("Europe/Madrid", "Romance Standard Time");
-            m.insert("Europe/Amsterdam", "W. Europe Standard Time");
-            m.insert("Europe/Brussels", "Romance Standard Time");
-            m.insert("Europe/Vienna", "W. Europe Standard Time");
-            m.insert("Europe/Zurich", "W. Europe Standard Time");
-            m.insert("Europe/Stockholm", "W. Europe Standard Time");
-            m.insert("Europe/Oslo", "W. Europe Standard Time");
-            m.insert("Europe/Copenhagen", "Romance Standard Time");
-            m.insert("Europe/Helsinki", "FLE Standard Time");
-            m.insert("Europe/Warsaw", "Central European Standard Time");
-            m.insert("Europe/Prague", "Central Europe Standard Time");
-            m.insert("Europe/Budapest", "Central Europe Standard Time");
-            m.insert("Europe/Bucharest", "GTB Standard Time");
-            m.insert("Europe/Athens", "GTB Standard Time");
-            m.insert("Europe/Istanbul", "Turkey Standard Time");
-            m.insert("Europe/Moscow", "Russian Standard Time");
-            m.insert("Europe/Kiev", "FLE Standard Time");
-            m.insert("Europe/Kyiv", "FLE Standard Time");
-            m.insert("Europe/Dublin", "GMT Standard Time");
-            m.insert("Europe/Lisbon", "GMT Standard Time");
-            // Asia
-            m.insert("Asia/Tokyo", "Tokyo Standard Time");
-            m.insert("Asia/Shanghai", "China Standard Time");
-            m.insert("Asia/Hong_Kong", "China Standard Time");
-            m.insert("Asia/Taipei", "Taipei Standard Time");
-            m.insert("Asia/Seoul", "Korea Standard Time");
-            m.insert("Asia/Singapore", "Singapore Standard Time");
-            m.insert("Asia/Kolkata", "India Standard Time");
-            m.insert("Asia/Calcutta", "India Standard Time");
-            m.insert("Asia/Dubai", "Arabian Standard Time");
-            m.insert("Asia/Riyadh", "Arab Standard Time");
-            m.insert("Asia/Tehran", "Iran Standard Time");
-            m.insert("Asia/Baghdad", "Arabic Standard Time");
-            m.insert("Asia/Jerusalem", "Israel Standard Time");
-            m.insert("Asia/Bangkok", "SE Asia Standard Time");
-            m.insert("Asia/Jakarta", "SE Asia Standard Time");
-            m.insert("Asia/Kuala_Lumpur", "Singapore Standard Time");
-            m.insert("Asia/Manila", "Singapore Standard Time");
-            m.insert("Asia/Karachi", "Pakistan Standard Time");
-            m.insert("Asia/Dhaka", "Bangladesh Standard Time");
-            m.insert("Asia/Almaty", "Central Asia Standard Time");
-            m.insert("Asia/Vladivostok", "Vladivostok Standard Time");
-            m.insert("Asia/Novosibirsk", "N. Central Asia Standard Time");
-            // Oceania
-            m.insert("Australia/Sydney", "AUS Eastern Standard Time");
-            m.insert("Australia/Melbourne", "AUS Eastern Standard Time");
-            m.insert("Australia/Brisbane", "E. Australia Standard Time");
-            m.insert("Australia/Perth", "W. Australia Standard Time");
-            m.insert("Australia/Adelaide", "Cen. Australia Standard Time");
-            m.insert("Australia/Darwin", "AUS Central Standard Time");
-            m.insert("Pacific/Auckland", "New Zealand Standard Time");
-            m.insert("Pacific/Fiji", "Fiji Standard Time");
-            // Africa
-            m.insert("Africa/Cairo", "Egypt Standard Time");
-            m.insert("Africa/Johannesburg", "South Africa Standard Time");
-            m.insert("Africa/Lagos", "W. Central Africa Standard Time");
-            m.insert("Africa/Nairobi", "E. Africa Standard Time");
-            m.insert("Africa/Casablanca", "Morocco Standard Time");
-            // UTC
-            m.insert("UTC", "UTC");
-            m.insert("Etc/UTC", "UTC");
-            m.insert("Etc/GMT", "GMT Standard Time");
+            for &(windows, zones) in WINDOWS_ZONES {
+                for zone in zones.split(' ') {
+                    m.insert(zone, windows);
+                }
+            }
+            // CLDR keeps some old names (Asia/Calcutta): map their current targets,
+            // then every alias of a mapped zone.
+            for &(alias, target) in ZONE_LINKS {
+                if let (None, Some(&windows)) = (m.get(target), m.get(alias)) {
+                    m.insert(target, windows);
+                }
+            }
+            for &(alias, target) in ZONE_LINKS {
+                if let (None, Some(&windows)) = (m.get(alias), m.get(target)) {
+                    m.insert(alias, windows);
+                }
+            }
             m
         });
 
-    IANA_WINDOWS_MAP.get(iana).copied().unwrap_or(iana)
+    IANA_WINDOWS_MAP.get(iana).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timezone::parse_instant;
+
+    fn resolve_at(zone: &str, tag: &str, at: &str) -> Info {
+        let clock = Clock::Fixed(parse_instant(at).unwrap());
+        resolve(zone, &Locale::parse(tag).unwrap(), &clock).unwrap()
+    }
+
+    #[test]
+    fn half_hour_and_45_minute_offsets() {
+        let kolkata = resolve_at("Asia/Kolkata", "en-IN", "2025-06-01T12:00:00Z");
+        assert_eq!(kolkata.offset_minutes, -330);
+        assert_eq!(
+            kolkata.date_string,
+            "Sun Jun 01 2025 17:30:00 GMT+0530 (India Standard Time)"
+        );
+        assert_eq!(kolkata.windows_zone.as_deref(), Some("India Standard Time"));
+
+        let kathmandu = resolve_at("Asia/Kathmandu", "en-US", "2025-06-01T12:00:00Z");
+        assert_eq!(kathmandu.offset_minutes, -345);
+        assert_eq!(
+            kathmandu.date_string,
+            "Sun Jun 01 2025 17:45:00 GMT+0545 (Nepal Time)"
+        );
+        assert_eq!(kathmandu.time_string, "5:45:00 PM");
+        assert_eq!(
+            kathmandu.windows_zone.as_deref(),
+            Some("Nepal Standard Time")
+        );
+    }
+
+    #[test]
+    fn adelaide_dst_boundaries() {
+        // ACDT (+10:30) ends 2025-04-06 03:00 local, back to ACST (+09:30).
+        let before = resolve_at("Australia/Adelaide", "en-AU", "2025-04-05T16:29:59Z");
+        assert_eq!(before.offset_minutes, -630);
+        assert_eq!(
+            before.date_string,
+            "Sun Apr 06 2025 02:59:59 GMT+1030 (Australian Central Daylight Time)"
+        );
+        let after = resolve_at("Australia/Adelaide", "en-AU", "2025-04-05T16:30:00Z");
+        assert_eq!(after.offset_minutes, -570);
+        assert_eq!(
+            after.date_string,
+            "Sun Apr 06 2025 02:00:00 GMT+0930 (Australian Central Standard Time)"
+        );
+        assert_eq!(after.time_string, "2:00:00 am");
+
+        // ACDT starts 2025-10-05 02:00 local, skipping to 03:00.
+        let before = resolve_at("Australia/Adelaide", "en-AU", "2025-10-04T16:29:59Z");
+        assert_eq!(before.offset_minutes, -570);
+        assert_eq!(before.time_string, "1:59:59 am");
+        let after = resolve_at("Australia/Adelaide", "en-AU", "2025-10-04T16:30:00Z");
+        assert_eq!(after.offset_minutes, -630);
+        assert_eq!(after.time_string, "3:00:00 am");
+    }
+
+    #[test]
+    fn northern_hemisphere_dst_boundaries() {
+        let before = resolve_at("America/New_York", "en-US", "2025-03-09T06:59:59Z");
+        assert_eq!(
+            before.date_string,
+            "Sun Mar 09 2025 01:59:59 GMT-0500 (Eastern Standard Time)"
+        );
+        let after = resolve_at("America/New_York", "en-US", "2025-03-09T07:00:00Z");
+        assert_eq!(
+            after.date_string,
+            "Sun Mar 09 2025 03:00:00 GMT-0400 (Eastern Daylight Time)"
+        );
+        assert_eq!(after.offset_minutes, 240);
+
+        // The repeated hour at the end of CEST.
+        let before = resolve_at("Europe/Berlin", "de-DE", "2025-10-26T00:59:59Z");
+        assert_eq!(
+            before.date_string,
+            "Sun Oct 26 2025 02:59:59 GMT+0200 (Mitteleuropäische Sommerzeit)"
+        );
+        let after = resolve_at("Europe/Berlin", "de-DE", "2025-10-26T01:00:00Z");
+        assert_eq!(
+            after.date_string,
+            "Sun Oct 26 2025 02:00:00 GMT+0100 (Mitteleuropäische Normalzeit)"
+        );
+        assert_eq!(after.offset_minutes, -60);
+    }
+
+    #[test]
+    fn unix_seconds_and_unmapped_zones() {
+        let info = resolve_at("UTC", "en-US", "1735689600");
+        assert_eq!(
+            info.date_string,
+            "Wed Jan 01 2025 00:00:00 GMT+0000 (Coordinated Universal Time)"
+        );
+
+        // No Windows zone: the real zone and offset are kept, only the Windows name is left out.
+        let troll = resolve_at("Antarctica/Troll", "en-US", "2025-06-01T12:00:00Z");
+        assert_eq!(troll.windows_zone, None);
+        assert_eq!(troll.iana_name, "Antarctica/Troll");
+        assert_eq!(troll.offset_minutes, -120);
+
+        let clock = Clock::Fixed(parse_instant("1735689600").unwrap());
+        assert!(resolve("Mars/Olympus_Mons", &Locale::default(), &clock).is_err());
+    }
 }
@@ -1,3 +1,8 @@
+mod clock;
+mod format;
 mod info;
+mod names;
+mod windows_zones;
 
-pub use info::{lookup_from_ip, resolve, Info};
+pub use clock::{parse_instant, Clock};
+pub use info::{lookup_exit, lookup_from_ip, resolve, Info};
@@ -0,0 +1,630 @@
+use super::windows_zones::ZONE_LINKS;
+use crate::locale::Locale;
+
+/// CLDR metazones and the IANA zones currently using them (`metaZones.xml`).
+const METAZONES: &[(&str, &str)] = &[
+    ("America_Eastern", "America/New_York America/Detroit America/Toronto America/Nassau America/Iqaluit America/Indiana/Indianapolis America/Indiana/Petersburg America/Indiana/Vincennes America/Indiana/Winamac America/Indiana/Marengo America/Indiana/Vevay America/Kentucky/Louisville America/Kentucky/Monticello America/Panama America/Jamaica America/Cayman America/Cancun"),
+    ("America_Central", "America/Chicago America/Winnipeg America/Mexico_City America/Monterrey America/Merida America/Bahia_Banderas America/Matamoros America/Chihuahua America/Ojinaga America/Regina America/Swift_Current America/Guatemala America/Belize America/Costa_Rica America/El_Salvador America/Tegucigalpa America/Managua America/Indiana/Knox America/Indiana/Tell_City America/Menominee America/North_Dakota/Beulah America/North_Dakota/Center America/North_Dakota/New_Salem America/Rankin_Inlet America/Resolute"),
+    ("America_Mountain", "America/Denver America/Edmonton America/Boise America/Phoenix America/Ciudad_Juarez America/Cambridge_Bay America/Inuvik America/Creston America/Dawson_Creek America/Fort_Nelson"),
+    ("America_Pacific", "America/Los_Angeles America/Vancouver America/Tijuana"),
+    ("Alaska", "America/Anchorage America/Juneau America/Nome America/Sitka America/Yakutat America/Metlakatla"),
+    ("Hawaii_Aleutian", "Pacific/Honolulu America/Adak"),
+    ("Atlantic", "America/Halifax America/Glace_Bay America/Goose_Bay America/Moncton America/Thule Atlantic/Bermuda America/Puerto_Rico America/Santo_Domingo America/Barbados America/Martinique"),
+    ("Newfoundland", "America/St_Johns"),
+    ("Mexico_Pacific", "America/Mazatlan America/Hermosillo"),
+    ("Colombia", "America/Bogota"),
+    ("Peru", "America/Lima"),
+    ("Ecuador", "America/Guayaquil"),
+    ("Venezuela", "America/Caracas"),
+    ("Bolivia", "America/La_Paz"),
+    ("Chile", "America/Santiago"),
+    ("Argentina", "America/Argentina/Buenos_Aires America/Argentina/Cordoba America/Argentina/Salta America/Argentina/Jujuy America/Argentina/Tucuman America/Argentina/Catamarca America/Argentina/La_Rioja America/Argentina/San_Juan America/Argentina/Mendoza America/Argentina/Rio_Gallegos America/Argentina/Ushuaia"),
+    ("Brasilia", "America/Sao_Paulo America/Bahia America/Fortaleza America/Recife America/Belem America/Maceio America/Araguaina America/Santarem"),
+    ("Amazon", "America/Manaus America/Cuiaba America/Campo_Grande America/Porto_Velho America/Boa_Vista"),
+    ("Uruguay", "America/Montevideo"),
+    ("Paraguay", "America/Asuncion"),
+    ("GMT", "Europe/London Europe/Dublin Africa/Abidjan Africa/Accra Africa/Dakar Africa/Bamako Africa/Monrovia Atlantic/Reykjavik Etc/GMT"),
+    ("Europe_Western", "Europe/Lisbon Atlantic/Canary Atlantic/Madeira Atlantic/Faroe Africa/Casablanca"),
+    ("Europe_Central", "Europe/Berlin Europe/Paris Europe/Madrid Europe/Rome Europe/Amsterdam Europe/Brussels Europe/Vienna Europe/Zurich Europe/Stockholm Europe/Oslo Europe/Copenhagen Europe/Warsaw Europe/Prague Europe/Budapest Europe/Belgrade Europe/Zagreb Europe/Ljubljana Europe/Bratislava Europe/Sarajevo Europe/Skopje Europe/Podgorica Europe/Tirane Europe/Luxembourg Europe/Monaco Europe/Malta Europe/Andorra Europe/Gibraltar Europe/Vaduz Europe/San_Marino Europe/Vatican Europe/Busingen Africa/Ceuta Africa/Tunis Africa/Algiers Arctic/Longyearbyen"),
+    ("Europe_Eastern", "Europe/Athens Europe/Bucharest Europe/Sofia Europe/Helsinki Europe/Kyiv Europe/Riga Europe/Tallinn Europe/Vilnius Europe/Chisinau Europe/Kaliningrad Europe/Mariehamn Asia/Nicosia Asia/Famagusta Asia/Beirut Asia/Gaza Asia/Hebron Africa/Cairo Africa/Tripoli"),
+    ("Moscow", "Europe/Moscow Europe/Simferopol Europe/Kirov"),
+    ("Israel", "Asia/Jerusalem"),
+    ("Arabian", "Asia/Riyadh Asia/Baghdad Asia/Kuwait Asia/Qatar Asia/Bahrain Asia/Aden"),
+    ("Gulf", "Asia/Dubai Asia/Muscat"),
+    ("Iran", "Asia/Tehran"),
+    ("Pakistan", "Asia/Karachi"),
+    ("India", "Asia/Kolkata Asia/Colombo"),
+    ("Nepal", "Asia/Kathmandu"),
+    ("Bangladesh", "Asia/Dhaka"),
+    ("Indochina", "Asia/Bangkok Asia/Ho_Chi_Minh Asia/Phnom_Penh Asia/Vientiane"),
+    ("Indonesia_Western", "Asia/Jakarta Asia/Pontianak"),
+    ("Indonesia_Central", "Asia/Makassar"),
+    ("Indonesia_Eastern", "Asia/Jayapura"),
+    ("Malaysia", "Asia/Kuala_Lumpur Asia/Kuching"),
+    ("Singapore", "Asia/Singapore"),
+    ("Philippines", "Asia/Manila"),
+    ("China", "Asia/Shanghai Asia/Macau"),
+    ("Hong_Kong", "Asia/Hong_Kong"),
+    ("Taipei", "Asia/Taipei"),
+    ("Korea", "Asia/Seoul"),
+    ("Japan", "Asia/Tokyo"),
+    ("Australia_Eastern", "Australia/Sydney Australia/Melbourne Australia/Brisbane Australia/Hobart Australia/Lindeman Antarctica/Macquarie"),
+    ("Australia_Central", "Australia/Adelaide Australia/Darwin Australia/Broken_Hill"),
+    ("Australia_Western", "Australia/Perth"),
+    ("New_Zealand", "Pacific/Auckland Antarctica/McMurdo"),
+    ("Africa_Southern", "Africa/Johannesburg Africa/Maseru Africa/Mbabane"),
+    ("Africa_Central", "Africa/Maputo Africa/Harare Africa/Lusaka Africa/Gaborone Africa/Blantyre Africa/Kigali Africa/Bujumbura Africa/Lubumbashi Africa/Juba Africa/Khartoum Africa/Windhoek"),
+    ("Africa_Eastern", "Africa/Nairobi Africa/Addis_Ababa Africa/Dar_es_Salaam Africa/Kampala Africa/Mogadishu Africa/Djibouti Africa/Asmara Indian/Antananarivo Indian/Comoro Indian/Mayotte"),
+    ("Africa_Western", "Africa/Lagos Africa/Kinshasa Africa/Luanda Africa/Douala Africa/Libreville Africa/Malabo Africa/Niamey Africa/Ndjamena Africa/Bangui Africa/Brazzaville Africa/Porto-Novo"),
+    ("UTC", "Etc/UTC"),
+];
+
+/// Long names per language and metazone: (language, metazone, standard, daylight).
+/// Metazones without daylight time repeat the standard name.
+const LONG_NAMES: &[(&str, &str, &str, &str)] = &[
+    (
+        "en",
+        "America_Eastern",
+        "Eastern Standard Time",
+        "Eastern Daylight Time",
+    ),
+    (
+        "en",
+        "America_Central",
+        "Central Standard Time",
+        "Central Daylight Time",
+    ),
+    (
+        "en",
+        "America_Mountain",
+        "Mountain Standard Time",
+        "Mountain Daylight Time",
+    ),
+    (
+        "en",
+        "America_Pacific",
+        "Pacific Standard Time",
+        "Pacific Daylight Time",
+    ),
+    (
+        "en",
+        "Alaska",
+        "Alaska Standard Time",
+        "Alaska Daylight Time",
+    ),
+    (
+        "en",
+        "Hawaii_Aleutian",
+        "Hawaii-Aleutian Standard Time",
+        "Hawaii-Aleutian Daylight Time",
+    ),
+    (
+        "en",
+        "Atlantic",
+        "Atlantic Standard Time",
+        "Atlantic Daylight Time",
+    ),
+    (
+        "en",
+        "Newfoundland",
+        "Newfoundland Standard Time",
+        "Newfoundland Daylight Time",
+    ),
+    (
+        "en",
+        "Mexico_Pacific",
+        "Mexican Pacific Standard Time",
+        "Mexican Pacific Daylight Time",
+    ),
+    (
+        "en",
+        "Colombia",
+        "Colombia Standard Time",
+        "Colombia Summer Time",
+    ),
+    ("en", "Peru", "Peru Standard Time", "Peru Summer Time"),
+    ("en", "Ecuador", "Ecuador Time", "Ecuador Time"),
+    ("en", "Venezuela", "Venezuela Time", "Venezuela Time"),
+    ("en", "Bolivia", "Bolivia Time", "Bolivia Time"),
+    ("en", "Chile", "Chile Standard Time", "Chile Summer Time"),
+    (
+        "en",
+        "Argentina",
+        "Argentina Standard Time",
+        "Argentina Summer Time",
+    ),
+    (
+        "en",
+        "Brasilia",
+        "Brasilia Standard Time",
+        "Brasilia Summer Time",
+    ),
+    ("en", "Amazon", "Amazon Standard Time", "Amazon Summer Time"),
+    (
+        "en",
+        "Uruguay",
+        "Uruguay Standard Time",
+        "Uruguay Summer Time",
+    ),
+    (
+        "en",
+        "Paraguay",
+        "Paraguay Standard Time",
+        "Paraguay Summer Time",
+    ),
+    ("en", "GMT", "Greenwich Mean Time", "Greenwich Mean Time"),
+    (
+        "en",
+        "Europe_Western",
+        "Western European Standard Time",
+        "Western European Summer Time",
+    ),
+    (
+        "en",
+        "Europe_Central",
+        "Central European Standard Time",
+        "Central European Summer Time",
+    ),
+    (
+        "en",
+        "Europe_Eastern",
+        "Eastern European Standard Time",
+        "Eastern European Summer Time",
+    ),
+    ("en", "Moscow", "Moscow Standard Time", "Moscow Summer Time"),
+    (
+        "en",
+        "Israel",
+        "Israel Standard Time",
+        "Israel Daylight Time",
+    ),
+    (
+        "en",
+        "Arabian",
+        "Arabian Standard Time",
+        "Arabian Daylight Time",
+    ),
+    ("en", "Gulf", "Gulf Standard Time", "Gulf Standard Time"),
+    ("en", "Iran", "Iran Standard Time", "Iran Daylight Time"),
+    (
+        "en",
+        "Pakistan",
+        "Pakistan Standard Time",
+        "Pakistan Summer Time",
+    ),
+    ("en", "India", "India Standard Time", "India Standard Time"),
+    ("en", "Nepal", "Nepal Time", "Nepal Time"),
+    (
+        "en",
+        "Bangladesh",
+        "Bangladesh Standard Time",
+        "Bangladesh Summer Time",
+    ),
+    ("en", "Indochina", "Indochina Time", "Indochina Time"),
+    (
+        "en",
+        "Indonesia_Western",
+        "Western Indonesia Time",
+        "Western Indonesia Time",
+    ),
+    (
+        "en",
+        "Indonesia_Central",
+        "Central Indonesia Time",
+        "Central Indonesia Time",
+    ),
+    (
+        "en",
+        "Indonesia_Eastern",
+        "Eastern Indonesia Time",
+        "Eastern Indonesia Time",
+    ),
+    ("en", "Malaysia", "Malaysia Time", "Malaysia Time"),
+    (
+        "en",
+        "Singapore",
+        "Singapore Standard Time",
+        "Singapore Standard Time",
+    ),
+    (
+        "en",
+        "Philippines",
+        "Philippine Standard Time",
+        "Philippine Summer Time",
+    ),
+    ("en", "China", "China Standard Time", "China Daylight Time"),
+    (
+        "en",
+        "Hong_Kong",
+        "Hong Kong Standard Time",
+        "Hong Kong Summer Time",
+    ),
+    (
+        "en",
+        "Taipei",
+        "Taipei Standard Time",
+        "Taipei Daylight Time",
+    ),
+    (
+        "en",
+        "Korea",
+        "Korean Standard Time",
+        "Korean Daylight Time",
+    ),
+    ("en", "Japan", "Japan Standard Time", "Japan Daylight Time"),
+    (
+        "en",
+        "Australia_Eastern",
+        "Australian Eastern Standard Time",
+        "Australian Eastern Daylight Time",
+    ),
+    (
+        "en",
+        "Australia_Central",
+        "Australian Central Standard Time",
+        "Australian Central Daylight Time",
+    ),
+    (
+        "en",
+        "Australia_Western",
+        "Australian Western Standard Time",
+        "Australian Western Daylight Time",
+    ),
+    (
+        "en",
+        "New_Zealand",
+        "New Zealand Standard Time",
+        "New Zealand Daylight Time",
+    ),
+    (
+        "en",
+        "Africa_Southern",
+        "South Africa Standard Time",
+        "South Africa Standard Time",
+    ),
+    (
+        "en",
+        "Africa_Central",
+        "Central Africa Time",
+        "Central Africa Time",
+    ),
+    (
+        "en",
+        "Africa_Eastern",
+        "East Africa Time",
+        "East Africa Time",
+    ),
+    (
+        "en",
+        "Africa_Western",
+        "West Africa Standard Time",
+        "West Africa Summer Time",
+    ),
+    (
+        "en",
+        "UTC",
+        "Coordinated Universal Time",
+        "Coordinated Universal Time",
+    ),
+    (
+        "de",
+        "Europe_Central",
+        "Mitteleuropäische Normalzeit",
+        "Mitteleuropäische Sommerzeit",
+    ),
+    (
+        "fr",
+        "Europe_Central",
+        "heure normale d’Europe centrale",
+        "heure d’été d’Europe centrale",
+    ),
+    (
+        "fr",
+        "Europe_Western",
+        "heure normale d’Europe de l’Ouest",
+        "heure d’été d’Europe de l’Ouest",
+    ),
+    (
+        "nl",
+        "Europe_Central",
+        "Midden-Europese standaardtijd",
+        "Midden-Europese zomertijd",
+    ),
+    (
+        "it",
+        "Europe_Central",
+        "Ora standard dell’Europa centrale",
+        "Ora legale dell’Europa centrale",
+    ),
+    (
+        "es",
+        "Europe_Central",
+        "hora estándar de Europa central",
+        "hora de verano de Europa central",
+    ),
+    (
+        "es",
+        "America_Eastern",
+        "hora estándar oriental",
+        "hora de verano oriental",
+    ),
+    (
+        "es",
+        "America_Central",
+        "hora estándar central",
+        "hora de verano central",
+    ),
+    (
+        "es",
+        "Atlantic",
+        "hora estándar del Atlántico",
+        "hora de verano del Atlántico",
+    ),
+    (
+        "es",
+        "Colombia",
+        "hora estándar de Colombia",
+        "hora de verano de Colombia",
+    ),
+    (
+        "es",
+        "Peru",
+        "hora estándar de Perú",
+        "hora de verano de Perú",
+    ),
+    ("es", "Ecuador", "hora de Ecuador", "hora de Ecuador"),
+    ("es", "Venezuela", "hora de Venezuela", "hora de Venezuela"),
+    ("es", "Bolivia", "hora de Bolivia", "hora de Bolivia"),
+    (
+        "es",
+        "Chile",
+        "hora estándar de Chile",
+        "hora de verano de Chile",
+    ),
+    (
+        "es",
+        "Argentina",
+        "hora estándar de Argentina",
+        "hora de verano de Argentina",
+    ),
+    (
+        "es",
+        "Uruguay",
+        "hora estándar de Uruguay",
+        "hora de verano de Uruguay",
+    ),
+    (
+        "es",
+        "Paraguay",
+        "hora estándar de Paraguay",
+        "hora de verano de Paraguay",
+    ),
+    (
+        "pt",
+        "Brasilia",
+        "Horário Padrão de Brasília",
+        "Horário de Verão de Brasília",
+    ),
+    (
+        "pt",
+        "Europe_Western",
+        "Hora padrão da Europa Ocidental",
+        "Hora de verão da Europa Ocidental",
+    ),
+    (
+        "sv",
+        "Europe_Central",
+        "centraleuropeisk normaltid",
+        "centraleuropeisk sommartid",
+    ),
+    (
+        "nb",
+        "Europe_Central",
+        "normaltid for Sentral-Europa",
+        "sommertid for Sentral-Europa",
+    ),
+    (
+        "da",
+        "Europe_Central",
+        "Centraleuropæisk normaltid",
+        "Centraleuropæisk sommertid",
+    ),
+    (
+        "fi",
+        "Europe_Eastern",
+        "Itä-Euroopan normaaliaika",
+        "Itä-Euroopan kesäaika",
+    ),
+    (
+        "pl",
+        "Europe_Central",
+        "czas środkowoeuropejski standardowy",
+        "czas środkowoeuropejski letni",
+    ),
+    (
+        "cs",
+        "Europe_Central",
+        "středoevropský standardní čas",
+        "středoevropský letní čas",
+    ),
+    (
+        "sk",
+        "Europe_Central",
+        "stredoeurópsky štandardný čas",
+        "stredoeurópsky letný čas",
+    ),
+    (
+        "hu",
+        "Europe_Central",
+        "közép-európai téli idő",
+        "közép-európai nyári idő",
+    ),
+    (
+        "ru",
+        "Moscow",
+        "Москва, стандартное время",
+        "Москва, летнее время",
+    ),
+    (
+        "uk",
+        "Europe_Eastern",
+        "за східноєвропейським стандартним часом",
+        "за східноєвропейським літнім часом",
+    ),
+    (
+        "el",
+        "Europe_Eastern",
+        "Χειμερινή ώρα Ανατολικής Ευρώπης",
+        "Θερινή ώρα Ανατολικής Ευρώπης",
+    ),
+    (
+        "ro",
+        "Europe_Eastern",
+        "Ora standard a Europei de Est",
+        "Ora de vară a Europei de Est",
+    ),
+    (
+        "bg",
+        "Europe_Eastern",
+        "Източноевропейско стандартно време",
+        "Източноевропейско лятно часово време",
+    ),
+    ("ja", "Japan", "日本標準時", "日本夏時間"),
+    ("ko", "Korea", "대한민국 표준시", "대한민국 하계 표준시"),
+    ("zh", "China", "中国标准时间", "中国夏令时间"),
+    ("zh-Hant", "Taipei", "台北標準時間", "台北夏令時間"),
+    ("zh-Hant", "Hong_Kong", "香港標準時間", "香港夏令時間"),
+    ("zh-Hant", "China", "中國標準時間", "中國夏令時間"),
+    ("he", "Israel", "שעון ישראל (חורף)", "שעון ישראל (קיץ)"),
+    (
+        "ar",
+        "Arabian",
+        "التوقيت العربي الرسمي",
+        "التوقيت العربي الصيفي",
+    ),
+    ("ar", "Gulf", "توقيت الخليج", "توقيت الخليج"),
+    (
+        "ar",
+        "Europe_Eastern",
+        "توقيت شرق أوروبا الرسمي",
+        "توقيت شرق أوروبا الصيفي",
+    ),
+    ("fa", "Iran", "وقت عادی ایران", "وقت تابستانی ایران"),
+    ("th", "Indochina", "เวลาอินโดจีน", "เวลาอินโดจีน"),
+    ("vi", "Indochina", "Giờ Đông Dương", "Giờ Đông Dương"),
+    (
+        "id",
+        "Indonesia_Western",
+        "Waktu Indonesia Barat",
+        "Waktu Indonesia Barat",
+    ),
+    ("ms", "Malaysia", "Waktu Malaysia", "Waktu Malaysia"),
+    ("bn", "Bangladesh", "বাংলাদেশ মানক সময়", "বাংলাদেশ গ্রীষ্মকালীন সময়"),
+];
+
+/// Zones whose daylight name differs from their metazone's (CLDR `zone` entries).
+const ZONE_DAYLIGHT_NAMES: &[(&str, &str, &str)] = &[
+    ("en", "Europe/London", "British Summer Time"),
+    ("en", "Europe/Dublin", "Irish Standard Time"),
+];
+
+/// Languages formatting offsets as "UTC+02:00" rather than "GMT+02:00".
+const UTC_FORMAT_LANGUAGES: &[&str] = &["fr", "fi"];
+
+/// CLDR `hourFormat` (positive;negative) of languages not using "+HH:mm;-HH:mm".
+const HOUR_FORMATS: &[(&str, &str)] = &[("da", "+HH.mm;-HH.mm"), ("fi", "+H.mm;-H.mm")];
+
+fn metazone(iana: &str) -> Option<&'static str> {
+    let find = |zone: &str| {
+        METAZONES
+            .iter()
+            .find(|(_, zones)| zones.split(' ').any(|z| z == zone))
+            .map(|(metazone, _)| *metazone)
+    };
+    find(iana).or_else(|| {
+        let (_, target) = ZONE_LINKS.iter().find(|(alias, _)| *alias == iana)?;
+        find(target)
+    })
+}
+
+/// Language key for the name tables; Traditional Chinese has its own names.
+fn name_language(locale: &Locale) -> &str {
+    match (locale.language.as_str(), locale.region.as_deref()) {
+        ("zh", Some("TW")) | ("zh", Some("HK")) | ("zh", Some("MO")) => "zh-Hant",
+        _ if locale.tag.starts_with("zh-Hant") => "zh-Hant",
+        (language, _) => language,
+    }
+}
+
+/// Long specific zone name in the locale's language, as in the "(…)" suffix of
+/// `Date.prototype.toString()`.
+///
+/// Zones and languages missing from the tables fall back to the localized GMT
+/// format ("GMT+03:00", "UTC+3.00" in Finnish), which is what ICU shows for zones
+/// without a metazone name.
+pub fn long_zone_name(iana: &str, locale: &Locale, daylight: bool, offset_seconds: i32) -> String {
+    let language = name_language(locale);
+    let canonical = ZONE_LINKS
+        .iter()
+        .find(|(alias, _)| *alias == iana)
+        .map(|(_, target)| *target)
+        .unwrap_or(iana);
+
+    if daylight {
+        if let Some((_, _, name)) = ZONE_DAYLIGHT_NAMES
+            .iter()
+            .find(|(lang, zone, _)| *lang == language && *zone == canonical)
+        {
+            return name.to_string();
+        }
+    }
+
+    let names = metazone(iana).and_then(|metazone| {
+        LONG_NAMES
+            .iter()
+            .find(|(lang, mz, _, _)| *lang == language && *mz == metazone)
+    });
+    match names {
+        Some((_, _, standard, daylight_name)) => {
+            if daylight {
+                daylight_name.to_string()
+            } else {
+                standard.to_string()
+            }
+        }
+        None => gmt_format(language, offset_seconds),
+    }
+}
+
+fn gmt_format(language: &str, offset_seconds: i32) -> String {
+    let prefix = if UTC_FORMAT_LANGUAGES.contains(&language) {
+        "UTC"
+    } else {
+        "GMT"
+    };
+    if offset_seconds == 0 {
+        return prefix.to_string();
+    }
+    let hour_format = HOUR_FORMATS
+        .iter()
+        .find(|(lang, _)| *lang == language)
+        .map_or("+HH:mm;-HH:mm", |(_, format)| *format);
+    let (positive, negative) = hour_format
+        .split_once(';')
+        .unwrap_or((hour_format, hour_format));
+    let pattern = if offset_seconds > 0 {
+        positive
+    } else {
+        negative
+    };
+
+    let abs_offset = offset_seconds.abs();
+    let hours = abs_offset / 3600;
+    let offset = if pattern.contains("HH") {
+        pattern.replacen("HH", &format!("{:02}", hours), 1)
+    } else {
+        pattern.replacen('H', &hours.to_string(), 1)
+    };
+    let minutes = format!("{:02}", (abs_offset % 3600) / 60);
+    format!("{}{}", prefix, offset.replacen("mm", &minutes, 1))
+}
@@ -0,0 +1,408 @@
+/// Windows timezone IDs and the IANA zones mapped to them in all territories,
+/// from CLDR `supplemental/windowsZones.xml` (space-separated as in `mapZone`).
+pub(super) const WINDOWS_ZONES: &[(&str, &str)] = &[
+    ("Dateline Standard Time", "Etc/GMT+12"),
+    ("UTC-11", "Etc/GMT+11 Pacific/Pago_Pago Pacific/Niue Pacific/Midway"),
+    ("Aleutian Standard Time", "America/Adak"),
+    ("Hawaiian Standard Time", "Pacific/Honolulu Pacific/Rarotonga Pacific/Tahiti Pacific/Johnston Etc/GMT+10"),
+    ("Marquesas Standard Time", "Pacific/Marquesas"),
+    ("Alaskan Standard Time", "America/Anchorage America/Juneau America/Metlakatla America/Nome America/Sitka America/Yakutat"),
+    ("UTC-09", "Etc/GMT+9 Pacific/Gambier"),
+    ("Pacific Standard Time (Mexico)", "America/Tijuana America/Santa_Isabel"),
+    ("UTC-08", "Etc/GMT+8 Pacific/Pitcairn"),
+    ("Pacific Standard Time", "America/Los_Angeles America/Vancouver PST8PDT"),
+    ("US Mountain Standard Time", "America/Phoenix America/Creston America/Dawson_Creek America/Fort_Nelson America/Hermosillo Etc/GMT+7"),
+    ("Mountain Standard Time (Mexico)", "America/Mazatlan"),
+    ("Mountain Standard Time", "America/Denver America/Edmonton America/Cambridge_Bay America/Inuvik America/Ciudad_Juarez America/Boise MST7MDT"),
+    ("Yukon Standard Time", "America/Whitehorse America/Dawson"),
+    ("Central America Standard Time", "America/Guatemala America/Belize America/Costa_Rica Pacific/Galapagos America/Tegucigalpa America/Managua America/El_Salvador Etc/GMT+6"),
+    ("Central Standard Time", "America/Chicago America/Winnipeg America/Rainy_River America/Rankin_Inlet America/Resolute America/Matamoros America/Ojinaga America/Indiana/Knox America/Indiana/Tell_City America/Menominee America/North_Dakota/Beulah America/North_Dakota/Center America/North_Dakota/New_Salem CST6CDT"),
+    ("Easter Island Standard Time", "Pacific/Easter"),
+    ("Central Standard Time (Mexico)", "America/Mexico_City America/Bahia_Banderas America/Merida America/Monterrey America/Chihuahua"),
+    ("Canada Central Standard Time", "America/Regina America/Swift_Current"),
+    ("SA Pacific Standard Time", "America/Bogota America/Rio_Branco America/Eirunepe America/Coral_Harbour America/Guayaquil America/Jamaica America/Cayman America/Panama America/Lima Etc/GMT+5"),
+    ("Eastern Standard Time (Mexico)", "America/Cancun"),
+    ("Eastern Standard Time", "America/New_York America/Nassau America/Toronto America/Iqaluit America/Montreal America/Nipigon America/Pangnirtung America/Thunder_Bay America/Detroit America/Indiana/Petersburg America/Indiana/Vincennes America/Indiana/Winamac America/Kentucky/Monticello America/Louisville EST5EDT"),
+    ("Haiti Standard Time", "America/Port-au-Prince"),
+    ("Cuba Standard Time", "America/Havana"),
+    ("US Eastern Standard Time", "America/Indianapolis America/Indiana/Marengo America/Indiana/Vevay"),
+    ("Turks And Caicos Standard Time", "America/Grand_Turk"),
+    ("Paraguay Standard Time", "America/Asuncion"),
+    ("Atlantic Standard Time", "America/Halifax Atlantic/Bermuda America/Glace_Bay America/Goose_Bay America/Moncton America/Thule"),
+    ("Venezuela Standard Time", "America/Caracas"),
+    ("Central Brazilian Standard Time", "America/Cuiaba America/Campo_Grande"),
+    ("SA Western Standard Time", "America/La_Paz America/Antigua America/Anguilla America/Aruba America/Barbados America/St_Barthelemy America/Kralendijk America/Manaus America/Boa_Vista America/Porto_Velho America/Blanc-Sablon America/Curacao America/Dominica America/Santo_Domingo America/Grenada America/Guadeloupe America/Guyana America/St_Kitts America/St_Lucia America/Marigot America/Martinique America/Montserrat America/Puerto_Rico America/Lower_Princes America/Port_of_Spain America/St_Vincent America/Tortola America/St_Thomas Etc/GMT+4"),
+    ("Pacific SA Standard Time", "America/Santiago"),
+    ("Newfoundland Standard Time", "America/St_Johns"),
+    ("Tocantins Standard Time", "America/Araguaina"),
+    ("E. South America Standard Time", "America/Sao_Paulo"),
+    ("SA Eastern Standard Time", "America/Cayenne Antarctica/Rothera Antarctica/Palmer America/Fortaleza America/Belem America/Maceio America/Recife America/Santarem Atlantic/Stanley America/Paramaribo Etc/GMT+3"),
+    ("Argentina Standard Time", "America/Buenos_Aires America/Argentina/La_Rioja America/Argentina/Rio_Gallegos America/Argentina/Salta America/Argentina/San_Juan America/Argentina/San_Luis America/Argentina/Tucuman America/Argentina/Ushuaia America/Catamarca America/Cordoba America/Jujuy America/Mendoza"),
+    ("Greenland Standard Time", "America/Godthab"),
+    ("Montevideo Standard Time", "America/Montevideo"),
+    ("Magallanes Standard Time", "America/Punta_Arenas America/Coyhaique"),
+    ("Saint Pierre Standard Time", "America/Miquelon"),
+    ("Bahia Standard Time", "America/Bahia"),
+    ("UTC-02", "Etc/GMT+2 America/Noronha Atlantic/South_Georgia"),
+    ("Azores Standard Time", "Atlantic/Azores America/Scoresbysund"),
+    ("Cape Verde Standard Time", "Atlantic/Cape_Verde Etc/GMT+1"),
+    ("UTC", "Etc/UTC Etc/GMT America/Danmarkshavn"),
+    ("GMT Standard Time", "Europe/London Atlantic/Canary Atlantic/Faeroe Europe/Guernsey Europe/Dublin Europe/Isle_of_Man Europe/Jersey Europe/Lisbon Atlantic/Madeira"),
+    ("Greenwich Standard Time", "Atlantic/Reykjavik Africa/Ouagadougou Africa/Abidjan Africa/Accra Africa/Banjul Africa/Conakry Africa/Bissau Africa/Monrovia Africa/Bamako Africa/Nouakchott Atlantic/St_Helena Africa/Freetown Africa/Dakar Africa/Lome"),
+    ("Sao Tome Standard Time", "Africa/Sao_Tome"),
+    ("Morocco Standard Time", "Africa/Casablanca Africa/El_Aaiun"),
+    ("W. Europe Standard Time", "Europe/Berlin Europe/Andorra Europe/Vienna Europe/Zurich Europe/Busingen Europe/Gibraltar Europe/Rome Europe/Vaduz Europe/Luxembourg Europe/Monaco Europe/Malta Europe/Amsterdam Europe/Oslo Europe/Stockholm Arctic/Longyearbyen Europe/San_Marino Europe/Vatican"),
+    ("Central Europe Standard Time", "Europe/Budapest Europe/Tirane Europe/Prague Europe/Podgorica Europe/Belgrade Europe/Ljubljana Europe/Bratislava"),
+    ("Romance Standard Time", "Europe/Paris Europe/Brussels Europe/Copenhagen Europe/Madrid Africa/Ceuta"),
+    ("W. Central Africa Standard Time", "Africa/Lagos Africa/Luanda Africa/Porto-Novo Africa/Kinshasa Africa/Bangui Africa/Brazzaville Africa/Douala Africa/Algiers Africa/Libreville Africa/Malabo Africa/Niamey Africa/Ndjamena Africa/Tunis Etc/GMT-1"),
+    ("Central European Standard Time", "Europe/Warsaw Europe/Sarajevo Europe/Zagreb Europe/Skopje"),
+    ("Jordan Standard Time", "Asia/Amman"),
+    ("GTB Standard Time", "Europe/Bucharest Asia/Nicosia Asia/Famagusta Europe/Athens"),
+    ("Middle East Standard Time", "Asia/Beirut"),
+    ("Egypt Standard Time", "Africa/Cairo"),
+    ("E. Europe Standard Time", "Europe/Chisinau"),
+    ("Syria Standard Time", "Asia/Damascus"),
+    ("West Bank Standard Time", "Asia/Hebron Asia/Gaza"),
+    ("South Africa Standard Time", "Africa/Johannesburg Africa/Bujumbura Africa/Gaborone Africa/Lubumbashi Africa/Maseru Africa/Blantyre Africa/Maputo Africa/Kigali Africa/Mbabane Africa/Lusaka Africa/Harare Etc/GMT-2"),
+    ("FLE Standard Time", "Europe/Kiev Europe/Mariehamn Europe/Sofia Europe/Tallinn Europe/Helsinki Europe/Vilnius Europe/Riga Europe/Uzhgorod Europe/Zaporozhye"),
+    ("Israel Standard Time", "Asia/Jerusalem"),
+    ("South Sudan Standard Time", "Africa/Juba"),
+    ("Kaliningrad Standard Time", "Europe/Kaliningrad"),
+    ("Sudan Standard Time", "Africa/Khartoum"),
+    ("Libya Standard Time", "Africa/Tripoli"),
+    ("Namibia Standard Time", "Africa/Windhoek"),
+    ("Arabic Standard Time", "Asia/Baghdad"),
+    ("Turkey Standard Time", "Europe/Istanbul"),
+    ("Arab Standard Time", "Asia/Riyadh Asia/Bahrain Asia/Kuwait Asia/Qatar Asia/Aden"),
+    ("Belarus Standard Time", "Europe/Minsk"),
+    ("Russian Standard Time", "Europe/Moscow Europe/Kirov Europe/Simferopol"),
+    ("E. Africa Standard Time", "Africa/Nairobi Antarctica/Syowa Africa/Djibouti Africa/Asmera Africa/Addis_Ababa Indian/Comoro Indian/Antananarivo Africa/Mogadishu Africa/Dar_es_Salaam Africa/Kampala Indian/Mayotte Etc/GMT-3"),
+    ("Volgograd Standard Time", "Europe/Volgograd"),
+    ("Iran Standard Time", "Asia/Tehran"),
+    ("Arabian Standard Time", "Asia/Dubai Asia/Muscat Etc/GMT-4"),
+    ("Astrakhan Standard Time", "Europe/Astrakhan Europe/Ulyanovsk"),
+    ("Azerbaijan Standard Time", "Asia/Baku"),
+    ("Russia Time Zone 3", "Europe/Samara"),
+    ("Mauritius Standard Time", "Indian/Mauritius Indian/Reunion Indian/Mahe"),
+    ("Saratov Standard Time", "Europe/Saratov"),
+    ("Georgian Standard Time", "Asia/Tbilisi"),
+    ("Caucasus Standard Time", "Asia/Yerevan"),
+    ("Afghanistan Standard Time", "Asia/Kabul"),
+    ("West Asia Standard Time", "Asia/Tashkent Antarctica/Mawson Asia/Oral Asia/Aqtau Asia/Aqtobe Asia/Atyrau Indian/Maldives Indian/Kerguelen Asia/Dushanbe Asia/Ashgabat Asia/Samarkand Etc/GMT-5"),
+    ("Ekaterinburg Standard Time", "Asia/Yekaterinburg"),
+    ("Pakistan Standard Time", "Asia/Karachi"),
+    ("Qyzylorda Standard Time", "Asia/Qyzylorda"),
+    ("India Standard Time", "Asia/Calcutta"),
+    ("Sri Lanka Standard Time", "Asia/Colombo"),
+    ("Nepal Standard Time", "Asia/Katmandu"),
+    ("Central Asia Standard Time", "Asia/Almaty Antarctica/Vostok Asia/Urumqi Indian/Chagos Asia/Bishkek Asia/Qostanay Etc/GMT-6"),
+    ("Bangladesh Standard Time", "Asia/Dhaka Asia/Thimphu"),
+    ("Omsk Standard Time", "Asia/Omsk"),
+    ("Myanmar Standard Time", "Asia/Rangoon Indian/Cocos"),
+    ("SE Asia Standard Time", "Asia/Bangkok Antarctica/Davis Indian/Christmas Asia/Jakarta Asia/Pontianak Asia/Phnom_Penh Asia/Vientiane Asia/Saigon Etc/GMT-7"),
+    ("Altai Standard Time", "Asia/Barnaul"),
+    ("W. Mongolia Standard Time", "Asia/Hovd"),
+    ("North Asia Standard Time", "Asia/Krasnoyarsk Asia/Novokuznetsk"),
+    ("N. Central Asia Standard Time", "Asia/Novosibirsk"),
+    ("Tomsk Standard Time", "Asia/Tomsk"),
+    ("China Standard Time", "Asia/Shanghai Asia/Hong_Kong Asia/Macau"),
+    ("North Asia East Standard Time", "Asia/Irkutsk"),
+    ("Singapore Standard Time", "Asia/Singapore Asia/Brunei Asia/Makassar Asia/Kuala_Lumpur Asia/Kuching Asia/Manila Etc/GMT-8"),
+    ("W. Australia Standard Time", "Australia/Perth"),
+    ("Taipei Standard Time", "Asia/Taipei"),
+    ("Ulaanbaatar Standard Time", "Asia/Ulaanbaatar Asia/Choibalsan"),
+    ("Aus Central W. Standard Time", "Australia/Eucla"),
+    ("Transbaikal Standard Time", "Asia/Chita"),
+    ("Tokyo Standard Time", "Asia/Tokyo Asia/Jayapura Pacific/Palau Asia/Dili Etc/GMT-9"),
+    ("North Korea Standard Time", "Asia/Pyongyang"),
+    ("Korea Standard Time", "Asia/Seoul"),
+    ("Yakutsk Standard Time", "Asia/Yakutsk Asia/Khandyga"),
+    ("Cen. Australia Standard Time", "Australia/Adelaide Australia/Broken_Hill"),
+    ("AUS Central Standard Time", "Australia/Darwin"),
+    ("E. Australia Standard Time", "Australia/Brisbane Australia/Lindeman"),
+    ("AUS Eastern Standard Time", "Australia/Sydney Australia/Melbourne"),
+    ("West Pacific Standard Time", "Pacific/Port_Moresby Antarctica/DumontDUrville Pacific/Truk Pacific/Guam Pacific/Saipan Etc/GMT-10"),
+    ("Tasmania Standard Time", "Australia/Hobart Australia/Currie Antarctica/Macquarie"),
+    ("Vladivostok Standard Time", "Asia/Vladivostok Asia/Ust-Nera"),
+    ("Lord Howe Standard Time", "Australia/Lord_Howe"),
+    ("Bougainville Standard Time", "Pacific/Bougainville"),
+    ("Russia Time Zone 10", "Asia/Srednekolymsk"),
+    ("Magadan Standard Time", "Asia/Magadan"),
+    ("Norfolk Standard Time", "Pacific/Norfolk"),
+    ("Sakhalin Standard Time", "Asia/Sakhalin"),
+    ("Central Pacific Standard Time", "Pacific/Guadalcanal Antarctica/Casey Pacific/Ponape Pacific/Kosrae Pacific/Noumea Pacific/Efate Etc/GMT-11"),
+    ("Russia Time Zone 11", "Asia/Kamchatka Asia/Anadyr"),
+    ("New Zealand Standard Time", "Pacific/Auckland Antarctica/McMurdo"),
+    ("UTC+12", "Etc/GMT-12 Pacific/Tarawa Pacific/Majuro Pacific/Kwajalein Pacific/Nauru Pacific/Funafuti Pacific/Wake Pacific/Wallis"),
+    ("Fiji Standard Time", "Pacific/Fiji"),
+    ("Chatham Islands Standard Time", "Pacific/Chatham"),
+    ("UTC+13", "Etc/GMT-13 Pacific/Enderbury Pacific/Fakaofo"),
+    ("Tonga Standard Time", "Pacific/Tongatapu"),
+    ("Samoa Standard Time", "Pacific/Apia"),
+    ("Line Islands Standard Time", "Pacific/Kiritimati Etc/GMT-14"),
+];
+
+/// tzdata links (`alias`, `target`), so both old and current names resolve,
+/// e.g. `Asia/Calcutta` and `Asia/Kolkata`.
+pub(super) const ZONE_LINKS: &[(&str, &str)] = &[
+    ("Africa/Accra", "Africa/Abidjan"),
+    ("Africa/Addis_Ababa", "Africa/Nairobi"),
+    ("Africa/Asmara", "Africa/Nairobi"),
+    ("Africa/Asmera", "Africa/Nairobi"),
+    ("Africa/Bamako", "Africa/Abidjan"),
+    ("Africa/Bangui", "Africa/Lagos"),
+    ("Africa/Banjul", "Africa/Abidjan"),
+    ("Africa/Blantyre", "Africa/Maputo"),
+    ("Africa/Brazzaville", "Africa/Lagos"),
+    ("Africa/Bujumbura", "Africa/Maputo"),
+    ("Africa/Conakry", "Africa/Abidjan"),
+    ("Africa/Dakar", "Africa/Abidjan"),
+    ("Africa/Dar_es_Salaam", "Africa/Nairobi"),
+    ("Africa/Djibouti", "Africa/Nairobi"),
+    ("Africa/Douala", "Africa/Lagos"),
+    ("Africa/Freetown", "Africa/Abidjan"),
+    ("Africa/Gaborone", "Africa/Maputo"),
+    ("Africa/Harare", "Africa/Maputo"),
+    ("Africa/Kampala", "Africa/Nairobi"),
+    ("Africa/Kigali", "Africa/Maputo"),
+    ("Africa/Kinshasa", "Africa/Lagos"),
+    ("Africa/Libreville", "Africa/Lagos"),
+    ("Africa/Lome", "Africa/Abidjan"),
+    ("Africa/Luanda", "Africa/Lagos"),
+    ("Africa/Lubumbashi", "Africa/Maputo"),
+    ("Africa/Lusaka", "Africa/Maputo"),
+    ("Africa/Malabo", "Africa/Lagos"),
+    ("Africa/Maseru", "Africa/Johannesburg"),
+    ("Africa/Mbabane", "Africa/Johannesburg"),
+    ("Africa/Mogadishu", "Africa/Nairobi"),
+    ("Africa/Niamey", "Africa/Lagos"),
+    ("Africa/Nouakchott", "Africa/Abidjan"),
+    ("Africa/Ouagadougou", "Africa/Abidjan"),
+    ("Africa/Porto-Novo", "Africa/Lagos"),
+    ("Africa/Timbuktu", "Africa/Abidjan"),
+    ("America/Anguilla", "America/Puerto_Rico"),
+    ("America/Antigua", "America/Puerto_Rico"),
+    (
+        "America/Argentina/ComodRivadavia",
+        "America/Argentina/Catamarca",
+    ),
+    ("America/Aruba", "America/Puerto_Rico"),
+    ("America/Atikokan", "America/Panama"),
+    ("America/Atka", "America/Adak"),
+    ("America/Blanc-Sablon", "America/Puerto_Rico"),
+    ("America/Buenos_Aires", "America/Argentina/Buenos_Aires"),
+    ("America/Catamarca", "America/Argentina/Catamarca"),
+    ("America/Cayman", "America/Panama"),
+    ("America/Coral_Harbour", "America/Panama"),
+    ("America/Cordoba", "America/Argentina/Cordoba"),
+    ("America/Creston", "America/Phoenix"),
+    ("America/Curacao", "America/Puerto_Rico"),
+    ("America/Dominica", "America/Puerto_Rico"),
+    ("America/Ensenada", "America/Tijuana"),
+    ("America/Fort_Wayne", "America/Indiana/Indianapolis"),
+    ("America/Godthab", "America/Nuuk"),
+    ("America/Grenada", "America/Puerto_Rico"),
+    ("America/Guadeloupe", "America/Puerto_Rico"),
+    ("America/Indianapolis", "America/Indiana/Indianapolis"),
+    ("America/Jujuy", "America/Argentina/Jujuy"),
+    ("America/Knox_IN", "America/Indiana/Knox"),
+    ("America/Kralendijk", "America/Puerto_Rico"),
+    ("America/Louisville", "America/Kentucky/Louisville"),
+    ("America/Lower_Princes", "America/Puerto_Rico"),
+    ("America/Marigot", "America/Puerto_Rico"),
+    ("America/Mendoza", "America/Argentina/Mendoza"),
+    ("America/Montreal", "America/Toronto"),
+    ("America/Montserrat", "America/Puerto_Rico"),
+    ("America/Nassau", "America/Toronto"),
+    ("America/Nipigon", "America/Toronto"),
+    ("America/Pangnirtung", "America/Iqaluit"),
+    ("America/Port_of_Spain", "America/Puerto_Rico"),
+    ("America/Porto_Acre", "America/Rio_Branco"),
+    ("America/Rainy_River", "America/Winnipeg"),
+    ("America/Rosario", "America/Argentina/Cordoba"),
+    ("America/Santa_Isabel", "America/Tijuana"),
+    ("America/Shiprock", "America/Denver"),
+    ("America/St_Barthelemy", "America/Puerto_Rico"),
+    ("America/St_Kitts", "America/Puerto_Rico"),
+    ("America/St_Lucia", "America/Puerto_Rico"),
+    ("America/St_Thomas", "America/Puerto_Rico"),
+    ("America/St_Vincent", "America/Puerto_Rico"),
+    ("America/Thunder_Bay", "America/Toronto"),
+    ("America/Tortola", "America/Puerto_Rico"),
+    ("America/Virgin", "America/Puerto_Rico"),
+    ("America/Yellowknife", "America/Edmonton"),
+    ("Antarctica/DumontDUrville", "Pacific/Port_Moresby"),
+    ("Antarctica/McMurdo", "Pacific/Auckland"),
+    ("Antarctica/South_Pole", "Pacific/Auckland"),
+    ("Antarctica/Syowa", "Asia/Riyadh"),
+    ("Arctic/Longyearbyen", "Europe/Berlin"),
+    ("Asia/Aden", "Asia/Riyadh"),
+    ("Asia/Ashkhabad", "Asia/Ashgabat"),
+    ("Asia/Bahrain", "Asia/Qatar"),
+    ("Asia/Brunei", "Asia/Kuching"),
+    ("Asia/Calcutta", "Asia/Kolkata"),
+    ("Asia/Choibalsan", "Asia/Ulaanbaatar"),
+    ("Asia/Chongqing", "Asia/Shanghai"),
+    ("Asia/Chungking", "Asia/Shanghai"),
+    ("Asia/Dacca", "Asia/Dhaka"),
+    ("Asia/Harbin", "Asia/Shanghai"),
+    ("Asia/Istanbul", "Europe/Istanbul"),
+    ("Asia/Kashgar", "Asia/Urumqi"),
+    ("Asia/Katmandu", "Asia/Kathmandu"),
+    ("Asia/Kuala_Lumpur", "Asia/Singapore"),
+    ("Asia/Kuwait", "Asia/Riyadh"),
+    ("Asia/Macao", "Asia/Macau"),
+    ("Asia/Muscat", "Asia/Dubai"),
+    ("Asia/Phnom_Penh", "Asia/Bangkok"),
+    ("Asia/Rangoon", "Asia/Yangon"),
+    ("Asia/Saigon", "Asia/Ho_Chi_Minh"),
+    ("Asia/Tel_Aviv", "Asia/Jerusalem"),
+    ("Asia/Thimbu", "Asia/Thimphu"),
+    ("Asia/Ujung_Pandang", "Asia/Makassar"),
+    ("Asia/Ulan_Bator", "Asia/Ulaanbaatar"),
+    ("Asia/Vientiane", "Asia/Bangkok"),
+    ("Atlantic/Faeroe", "Atlantic/Faroe"),
+    ("Atlantic/Jan_Mayen", "Europe/Berlin"),
+    ("Atlantic/Reykjavik", "Africa/Abidjan"),
+    ("Atlantic/St_Helena", "Africa/Abidjan"),
+    ("Australia/ACT", "Australia/Sydney"),
+    ("Australia/Canberra", "Australia/Sydney"),
+    ("Australia/Currie", "Australia/Hobart"),
+    ("Australia/LHI", "Australia/Lord_Howe"),
+    ("Australia/NSW", "Australia/Sydney"),
+    ("Australia/North", "Australia/Darwin"),
+    ("Australia/Queensland", "Australia/Brisbane"),
+    ("Australia/South", "Australia/Adelaide"),
+    ("Australia/Tasmania", "Australia/Hobart"),
+    ("Australia/Victoria", "Australia/Melbourne"),
+    ("Australia/West", "Australia/Perth"),
+    ("Australia/Yancowinna", "Australia/Broken_Hill"),
+    ("Brazil/Acre", "America/Rio_Branco"),
+    ("Brazil/DeNoronha", "America/Noronha"),
+    ("Brazil/East", "America/Sao_Paulo"),
+    ("Brazil/West", "America/Manaus"),
+    ("CET", "Europe/Brussels"),
+    ("CST6CDT", "America/Chicago"),
+    ("Canada/Atlantic", "America/Halifax"),
+    ("Canada/Central", "America/Winnipeg"),
+    ("Canada/Eastern", "America/Toronto"),
+    ("Canada/Mountain", "America/Edmonton"),
+    ("Canada/Newfoundland", "America/St_Johns"),
+    ("Canada/Pacific", "America/Vancouver"),
+    ("Canada/Saskatchewan", "America/Regina"),
+    ("Canada/Yukon", "America/Whitehorse"),
+    ("Chile/Continental", "America/Santiago"),
+    ("Chile/EasterIsland", "Pacific/Easter"),
+    ("Cuba", "America/Havana"),
+    ("EET", "Europe/Athens"),
+    ("EST", "America/Panama"),
+    ("EST5EDT", "America/New_York"),
+    ("Egypt", "Africa/Cairo"),
+    ("Eire", "Europe/Dublin"),
+    ("Etc/GMT+0", "Etc/GMT"),
+    ("Etc/GMT-0", "Etc/GMT"),
+    ("Etc/GMT0", "Etc/GMT"),
+    ("Etc/Greenwich", "Etc/GMT"),
+    ("Etc/UCT", "Etc/UTC"),
+    ("Etc/Universal", "Etc/UTC"),
+    ("Etc/Zulu", "Etc/UTC"),
+    ("Europe/Amsterdam", "Europe/Brussels"),
+    ("Europe/Belfast", "Europe/London"),
+    ("Europe/Bratislava", "Europe/Prague"),
+    ("Europe/Busingen", "Europe/Zurich"),
+    ("Europe/Copenhagen", "Europe/Berlin"),
+    ("Europe/Guernsey", "Europe/London"),
+    ("Europe/Isle_of_Man", "Europe/London"),
+    ("Europe/Jersey", "Europe/London"),
+    ("Europe/Kiev", "Europe/Kyiv"),
+    ("Europe/Ljubljana", "Europe/Belgrade"),
+    ("Europe/Luxembourg", "Europe/Brussels"),
+    ("Europe/Mariehamn", "Europe/Helsinki"),
+    ("Europe/Monaco", "Europe/Paris"),
+    ("Europe/Nicosia", "Asia/Nicosia"),
+    ("Europe/Oslo", "Europe/Berlin"),
+    ("Europe/Podgorica", "Europe/Belgrade"),
+    ("Europe/San_Marino", "Europe/Rome"),
+    ("Europe/Sarajevo", "Europe/Belgrade"),
+    ("Europe/Skopje", "Europe/Belgrade"),
+    ("Europe/Stockholm", "Europe/Berlin"),
+    ("Europe/Tiraspol", "Europe/Chisinau"),
+    ("Europe/Uzhgorod", "Europe/Kyiv"),
+    ("Europe/Vaduz", "Europe/Zurich"),
+    ("Europe/Vatican", "Europe/Rome"),
+    ("Europe/Zagreb", "Europe/Belgrade"),
+    ("Europe/Zaporozhye", "Europe/Kyiv"),
+    ("GB", "Europe/London"),
+    ("GB-Eire", "Europe/London"),
+    ("GMT", "Etc/GMT"),
+    ("GMT+0", "Etc/GMT"),
+    ("GMT-0", "Etc/GMT"),
+    ("GMT0", "Etc/GMT"),
+    ("Greenwich", "Etc/GMT"),
+    ("HST", "Pacific/Honolulu"),
+    ("Hongkong", "Asia/Hong_Kong"),
+    ("Iceland", "Africa/Abidjan"),
+    ("Indian/Antananarivo", "Africa/Nairobi"),
+    ("Indian/Christmas", "Asia/Bangkok"),
+    ("Indian/Cocos", "Asia/Yangon"),
+    ("Indian/Comoro", "Africa/Nairobi"),
+    ("Indian/Kerguelen", "Indian/Maldives"),
+    ("Indian/Mahe", "Asia/Dubai"),
+    ("Indian/Mayotte", "Africa/Nairobi"),
+    ("Indian/Reunion", "Asia/Dubai"),
+    ("Iran", "Asia/Tehran"),
+    ("Israel", "Asia/Jerusalem"),
+    ("Jamaica", "America/Jamaica"),
+    ("Japan", "Asia/Tokyo"),
+    ("Kwajalein", "Pacific/Kwajalein"),
+    ("Libya", "Africa/Tripoli"),
+    ("MET", "Europe/Brussels"),
+    ("MST", "America/Phoenix"),
+    ("MST7MDT", "America/Denver"),
+    ("Mexico/BajaNorte", "America/Tijuana"),
+    ("Mexico/BajaSur", "America/Mazatlan"),
+    ("Mexico/General", "America/Mexico_City"),
+    ("NZ", "Pacific/Auckland"),
+    ("NZ-CHAT", "Pacific/Chatham"),
+    ("Navajo", "America/Denver"),
+    ("PRC", "Asia/Shanghai"),
+    ("PST8PDT", "America/Los_Angeles"),
+    ("Pacific/Chuuk", "Pacific/Port_Moresby"),
+    ("Pacific/Enderbury", "Pacific/Kanton"),
+    ("Pacific/Funafuti", "Pacific/Tarawa"),
+    ("Pacific/Johnston", "Pacific/Honolulu"),
+    ("Pacific/Majuro", "Pacific/Tarawa"),
+    ("Pacific/Midway", "Pacific/Pago_Pago"),
+    ("Pacific/Pohnpei", "Pacific/Guadalcanal"),
+    ("Pacific/Ponape", "Pacific/Guadalcanal"),
+    ("Pacific/Saipan", "Pacific/Guam"),
+    ("Pacific/Samoa", "Pacific/Pago_Pago"),
+    ("Pacific/Truk", "Pacific/Port_Moresby"),
+    ("Pacific/Wake", "Pacific/Tarawa"),
+    ("Pacific/Wallis", "Pacific/Tarawa"),
+    ("Pacific/Yap", "Pacific/Port_Moresby"),
+    ("Poland", "Europe/Warsaw"),
+    ("Portugal", "Europe/Lisbon"),
+    ("ROC", "Asia/Taipei"),
+    ("ROK", "Asia/Seoul"),
+    ("Singapore", "Asia/Singapore"),
+    ("Turkey", "Europe/Istanbul"),
+    ("UCT", "Etc/UTC"),
+    ("US/Alaska", "America/Anchorage"),
+    ("US/Aleutian", "America/Adak"),
+    ("US/Arizona", "America/Phoenix"),
+    ("US/Central", "America/Chicago"),
+    ("US/East-Indiana", "America/Indiana/Indianapolis"),
+    ("US/Eastern", "America/New_York"),
+    ("US/Hawaii", "Pacific/Honolulu"),
+    ("US/Indiana-Starke", "America/Indiana/Knox"),
+    ("US/Michigan", "America/Detroit"),
+    ("US/Mountain", "America/Denver"),
+    ("US/Pacific", "America/Los_Angeles"),
+    ("US/Samoa", "Pacific/Pago_Pago"),
+    ("UTC", "Etc/UTC"),
+    ("Universal", "Etc/UTC"),
+    ("W-SU", "Europe/Moscow"),
+    ("WET", "Europe/Lisbon"),
+    ("Zulu", "Etc/UTC"),
+];